@@ -0,0 +1,41 @@
+#ifndef MAP_TO_RANGE_H
+#define MAP_TO_RANGE_H
+
+/* C declarations for the `ffi` feature of the map_to_range crate.
+ * Every function returns false where the rust functions would return None,
+ * and leaves the output untouched in that case. */
+
+#include <stdbool.h>
+#include <stdint.h>
+
+/* A linear mapping between two float ranges. */
+typedef struct MrMapper {
+  float from_lo;
+  float from_hi;
+  float to_lo;
+  float to_hi;
+  /* Clamps values outside of the `from` range instead of rejecting them. */
+  bool clamped;
+} MrMapper;
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+bool mr_map_u8(uint8_t value, uint8_t from_lo, uint8_t from_hi, uint8_t to_lo, uint8_t to_hi, uint8_t *out);
+
+bool mr_map_u8_clamped(uint8_t value, uint8_t from_lo, uint8_t from_hi, uint8_t to_lo, uint8_t to_hi, uint8_t *out);
+
+bool mr_map_f32(float value, float from_lo, float from_hi, float to_lo, float to_hi, float *out);
+
+bool mr_map_f32_clamped(float value, float from_lo, float from_hi, float to_lo, float to_hi, float *out);
+
+MrMapper mr_mapper_create(float from_lo, float from_hi, float to_lo, float to_hi, bool clamped);
+
+bool mr_mapper_evaluate(const MrMapper *mapper, float value, float *out);
+
+#ifdef __cplusplus
+}  // extern "C"
+#endif
+
+#endif  /* MAP_TO_RANGE_H */
@@ -0,0 +1,92 @@
+//! Mapping readings of ADCs with a known bit width.
+//!
+//! Instead of repeating `(0, 4095)` all over the firmware, the resolution is named once
+//! through the type, and readings above full scale are rejected.
+
+use crate::MapRange;
+
+/// An ADC with a resolution of `BITS`, between 1 and 32.
+///
+/// ```
+/// use map_to_range::adc::Adc12;
+///
+/// assert_eq!(4095, Adc12::FULL_SCALE);
+/// assert_eq!(Some(1650_u16), Adc12::map(2048, (0, 3300)));
+/// assert_eq!(None, Adc12::map(4096, (0_u16, 3300)));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Adc<const BITS: u8>;
+
+/// An 8 bit ADC.
+pub type Adc8 = Adc<8>;
+/// A 10 bit ADC.
+pub type Adc10 = Adc<10>;
+/// A 12 bit ADC.
+pub type Adc12 = Adc<12>;
+/// A 14 bit ADC.
+pub type Adc14 = Adc<14>;
+/// A 16 bit ADC.
+pub type Adc16 = Adc<16>;
+
+impl<const BITS: u8> Adc<BITS> {
+    /// The highest reading of this ADC, `2^BITS - 1`.
+    pub const FULL_SCALE: u32 = {
+        assert!(BITS >= 1 && BITS <= 32, "ADCs have between 1 and 32 bits");
+        u32::MAX >> (32 - BITS)
+    };
+    /// Maps a reading into `to_range`, where zero maps to `to_range.0` and full scale to `to_range.1`.
+    ///
+    /// Returns `None` if the reading is above full scale or the result does not fit into `T`.
+    #[must_use]
+    pub fn map<T: MapRange>(reading: u32, to_range: (T, T)) -> Option<T> {
+        let to_range = (
+            to_range.0.checked_f64_cast()?,
+            to_range.1.checked_f64_cast()?,
+        );
+        let mapped = (reading as f64).map_range((0., Self::FULL_SCALE as f64), to_range)?;
+        T::checked_cast_back(mapped)
+    }
+    /// The reading as a fraction of full scale, in `0..=1`.
+    ///
+    /// Returns `None` if the reading is above full scale.
+    #[must_use]
+    pub fn normalized(reading: u32) -> Option<f64> {
+        Self::map(reading, (0., 1.))
+    }
+}
+
+/// Maps a reading of a `BITS` wide ADC into `to_range`. See [`Adc::map`].
+///
+/// ```
+/// use map_to_range::adc::map_adc;
+///
+/// assert_eq!(Some(50_u8), map_adc::<10, _>(1023 / 2 + 1, (0, 100)));
+/// ```
+#[must_use]
+pub fn map_adc<const BITS: u8, T: MapRange>(reading: u32, to_range: (T, T)) -> Option<T> {
+    Adc::<BITS>::map(reading, to_range)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_full_scale() {
+        assert_eq!(1, Adc::<1>::FULL_SCALE);
+        assert_eq!(255, Adc8::FULL_SCALE);
+        assert_eq!(1023, Adc10::FULL_SCALE);
+        assert_eq!(16383, Adc14::FULL_SCALE);
+        assert_eq!(65535, Adc16::FULL_SCALE);
+        assert_eq!(u32::MAX, Adc::<32>::FULL_SCALE);
+    }
+    #[test]
+    fn test_map() {
+        assert_eq!(Some(-10_i8), Adc8::map(0, (-10, 10)));
+        assert_eq!(Some(10_i8), Adc8::map(255, (-10, 10)));
+        assert_eq!(None, Adc8::map(256, (-10_i8, 10)));
+        assert_eq!(Some(1.), Adc16::normalized(65535));
+        assert_eq!(Some(0.), Adc16::normalized(0));
+        assert_eq!(None, Adc10::normalized(1024));
+    }
+}
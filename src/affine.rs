@@ -0,0 +1,334 @@
+//! Affine transforms, the algebra underneath every linear range mapping.
+
+use crate::calibration::LinearCalibration;
+use crate::{math, MapRange};
+
+/// The transform `x * scale + offset`.
+///
+/// Mapping between two ranges is such a transform, so mappings can be composed, inverted and
+/// cached in this form.
+///
+/// ```
+/// use map_to_range::affine::Affine1D;
+///
+/// let celsius_to_fahrenheit = Affine1D::from_ranges((0., 100.), (32., 212.)).unwrap();
+/// let raw_to_celsius = Affine1D::from_ranges((0., 4095.), (-40., 125.)).unwrap();
+/// let raw_to_fahrenheit = raw_to_celsius.compose(celsius_to_fahrenheit);
+/// assert_eq!(-40., raw_to_fahrenheit.apply(0.));
+///
+/// let fahrenheit_to_celsius = celsius_to_fahrenheit.inverse().unwrap();
+/// assert_eq!(100., fahrenheit_to_celsius.apply(212.));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Affine1D {
+    /// The factor `x` is multiplied with.
+    pub scale: f64,
+    /// The value added after scaling.
+    pub offset: f64,
+}
+
+impl Default for Affine1D {
+    fn default() -> Self {
+        Self::IDENTITY
+    }
+}
+
+impl Affine1D {
+    /// The transform leaving every value as it is.
+    pub const IDENTITY: Self = Self::new(1., 0.);
+
+    /// The transform `x * scale + offset`.
+    #[must_use]
+    pub const fn new(scale: f64, offset: f64) -> Self {
+        Self { scale, offset }
+    }
+    /// The transform mapping `from_range` onto `to_range`, like [`MapRange::map_range`] does.
+    ///
+    /// Returns `None` if `from_range` is empty or an end is not finite.
+    #[must_use]
+    pub fn from_ranges(from_range: (f64, f64), to_range: (f64, f64)) -> Option<Self> {
+        let ends = [from_range.0, from_range.1, to_range.0, to_range.1];
+        if ends.iter().any(|end| !end.is_finite()) {
+            return None;
+        }
+        // An empty from range divides by zero, which the finiteness check below rejects.
+        let scale = (to_range.1 - to_range.0) / (from_range.1 - from_range.0);
+        let transform = Self::new(scale, to_range.0 - from_range.0 * scale);
+        (transform.scale.is_finite() && transform.offset.is_finite()).then_some(transform)
+    }
+    /// The range `from_range` is mapped onto.
+    #[must_use]
+    pub fn to_range(&self, from_range: (f64, f64)) -> (f64, f64) {
+        (self.apply(from_range.0), self.apply(from_range.1))
+    }
+    /// Applies the transform to `x`.
+    #[must_use]
+    pub fn apply(&self, x: f64) -> f64 {
+        x * self.scale + self.offset
+    }
+    /// Applies the transform to `x`, casting from `T` and into `U`.
+    ///
+    /// Unlike a range mapping, values are not limited to a range.
+    /// Returns `None` if the result does not fit into `U`.
+    #[must_use]
+    pub fn map<T: MapRange, U: MapRange>(&self, x: T) -> Option<U> {
+        U::checked_cast_back(self.apply(x.checked_f64_cast()?))
+    }
+    /// The transform applying `self` first and `then` second.
+    #[must_use]
+    pub fn compose(self, then: Self) -> Self {
+        Self::new(
+            self.scale * then.scale,
+            self.offset * then.scale + then.offset,
+        )
+    }
+    /// The transform undoing `self`.
+    ///
+    /// Returns `None` if the scale is zero, so every value is mapped onto the same one.
+    #[must_use]
+    pub fn inverse(self) -> Option<Self> {
+        let scale = 1. / self.scale;
+        let inverse = Self::new(scale, -self.offset * scale);
+        (inverse.scale.is_finite() && inverse.offset.is_finite()).then_some(inverse)
+    }
+}
+
+impl From<LinearCalibration> for Affine1D {
+    fn from(calibration: LinearCalibration) -> Self {
+        Self::new(calibration.slope, calibration.offset)
+    }
+}
+
+/// The transform `matrix * x + offset` of `D` dimensional points.
+///
+/// A diagonal matrix scales every axis on its own, like mapping between two boxes. A full
+/// matrix also rotates and skews, like a touchscreen mounted slightly off from its display.
+///
+/// ```
+/// use map_to_range::affine::AffineN;
+///
+/// // Three touches on known screen positions calibrate a rotated and scaled touch panel.
+/// let touches = [
+///     ([100., 100.], [0., 0.]),
+///     ([3900., 200.], [800., 0.]),
+///     ([0., 3900.], [0., 480.]),
+/// ];
+/// let calibration = AffineN::fit(&touches).unwrap();
+/// let [x, y] = calibration.apply([2000., 2000.]);
+/// assert!((x - 410.2).abs() < 0.1 && (y - 233.5).abs() < 0.1);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct AffineN<const D: usize> {
+    /// Row `i` holds the factors of every input axis for output axis `i`.
+    pub matrix: [[f64; D]; D],
+    /// The point added after multiplying with the matrix.
+    pub offset: [f64; D],
+}
+
+impl<const D: usize> Default for AffineN<D> {
+    fn default() -> Self {
+        Self::identity()
+    }
+}
+
+impl<const D: usize> AffineN<D> {
+    /// The transform leaving every point as it is.
+    #[must_use]
+    pub fn identity() -> Self {
+        Self::from_axes([Affine1D::IDENTITY; D])
+    }
+    /// The transform applying one [`Affine1D`] per axis.
+    #[must_use]
+    pub fn from_axes(axes: [Affine1D; D]) -> Self {
+        let mut transform = Self {
+            matrix: [[0.; D]; D],
+            offset: [0.; D],
+        };
+        for (index, ((row, offset), axis)) in transform
+            .matrix
+            .iter_mut()
+            .zip(&mut transform.offset)
+            .zip(axes)
+            .enumerate()
+        {
+            if let Some(scale) = row.get_mut(index) {
+                *scale = axis.scale;
+            }
+            *offset = axis.offset;
+        }
+        transform
+    }
+    /// The transform mapping the box `from_box` onto `to_box`, given as one range per axis.
+    ///
+    /// Returns `None` if a range of `from_box` is empty or an end is not finite.
+    #[must_use]
+    pub fn from_boxes(from_box: [(f64, f64); D], to_box: [(f64, f64); D]) -> Option<Self> {
+        let mut axes = [Affine1D::IDENTITY; D];
+        for ((axis, from_range), to_range) in axes.iter_mut().zip(from_box).zip(to_box) {
+            *axis = Affine1D::from_ranges(from_range, to_range)?;
+        }
+        Some(Self::from_axes(axes))
+    }
+    /// Fits the transform mapping the first point of every pair closest to the second one, with
+    /// the least squares method.
+    ///
+    /// With `D + 1` pairs the fit is exact, more pairs average out measurement noise.
+    /// Returns `None` if the points do not span all `D` dimensions or a value is not finite.
+    #[must_use]
+    pub fn fit(pairs: &[([f64; D], [f64; D])]) -> Option<Self> {
+        if pairs.is_empty()
+            || pairs
+                .iter()
+                .flat_map(|(from, to)| from.iter().chain(to))
+                .any(|value| !value.is_finite())
+        {
+            return None;
+        }
+        let count = pairs.len() as f64;
+        let mut from_center = [0.; D];
+        let mut to_center = [0.; D];
+        for (from, to) in pairs {
+            for (center, value) in from_center.iter_mut().zip(from) {
+                *center += value / count;
+            }
+            for (center, value) in to_center.iter_mut().zip(to) {
+                *center += value / count;
+            }
+        }
+        // Relative to the centers only the matrix is left, solved from the normal equations
+        // `covariance * row = cross` once for every output axis.
+        let mut covariance = [[0.; D]; D];
+        let mut cross = [[0.; D]; D];
+        for (from, to) in pairs {
+            let mut centered = [0.; D];
+            for ((centered, value), center) in centered.iter_mut().zip(from).zip(&from_center) {
+                *centered = value - center;
+            }
+            for (row, a) in covariance.iter_mut().zip(&centered) {
+                for (entry, b) in row.iter_mut().zip(&centered) {
+                    *entry += a * b;
+                }
+            }
+            for ((row, value), center) in cross.iter_mut().zip(to).zip(&to_center) {
+                for (entry, a) in row.iter_mut().zip(&centered) {
+                    *entry += (value - center) * a;
+                }
+            }
+        }
+        let mut matrix = [[0.; D]; D];
+        for (row, cross) in matrix.iter_mut().zip(cross) {
+            *row = math::solve(covariance, cross)?;
+        }
+        let mut transform = Self {
+            matrix,
+            offset: [0.; D],
+        };
+        let shifted = transform.apply(from_center);
+        for ((offset, center), shifted) in transform.offset.iter_mut().zip(to_center).zip(shifted) {
+            *offset = center - shifted;
+        }
+        Some(transform)
+    }
+    /// Applies the transform to `point`.
+    #[must_use]
+    pub fn apply(&self, point: [f64; D]) -> [f64; D] {
+        let mut result = self.offset;
+        for (value, row) in result.iter_mut().zip(&self.matrix) {
+            *value += row.iter().zip(&point).map(|(a, b)| a * b).sum::<f64>();
+        }
+        result
+    }
+    /// The transform applying `self` first and `then` second.
+    #[must_use]
+    pub fn compose(&self, then: &Self) -> Self {
+        let mut matrix = [[0.; D]; D];
+        for (row, then_row) in matrix.iter_mut().zip(&then.matrix) {
+            for (column, entry) in row.iter_mut().enumerate() {
+                *entry = then_row
+                    .iter()
+                    .zip(&self.matrix)
+                    .map(|(a, self_row)| a * self_row.get(column).copied().unwrap_or_default())
+                    .sum();
+            }
+        }
+        Self {
+            matrix,
+            offset: then.apply(self.offset),
+        }
+    }
+    /// The transform undoing `self`.
+    ///
+    /// Returns `None` if the matrix is singular, so points are flattened onto fewer dimensions.
+    #[must_use]
+    pub fn inverse(&self) -> Option<Self> {
+        // Column `i` of the inverse solves `matrix * column = e_i`.
+        let mut columns = [[0.; D]; D];
+        for (index, column) in columns.iter_mut().enumerate() {
+            let mut unit = [0.; D];
+            *unit.get_mut(index)? = 1.;
+            *column = math::solve(self.matrix, unit)?;
+        }
+        let mut matrix = [[0.; D]; D];
+        for (index, row) in matrix.iter_mut().enumerate() {
+            for (entry, column) in row.iter_mut().zip(&columns) {
+                *entry = *column.get(index)?;
+            }
+        }
+        let mut inverse = Self {
+            matrix,
+            offset: [0.; D],
+        };
+        let moved = inverse.apply(self.offset);
+        for (offset, value) in inverse.offset.iter_mut().zip(moved) {
+            *offset = -value;
+        }
+        Some(inverse)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_affine_1d() {
+        assert_eq!(None, Affine1D::from_ranges((1., 1.), (0., 1.)));
+        assert_eq!(None, Affine1D::from_ranges((0., f64::NAN), (0., 1.)));
+        let reversed = Affine1D::from_ranges((0., 10.), (100., 0.));
+        assert_eq!(Some(Affine1D::new(-10., 100.)), reversed);
+        assert_eq!(Some((100., 0.)), reversed.map(|t| t.to_range((0., 10.))));
+        assert_eq!(Some(Some(70_u8)), reversed.map(|t| t.map(3_u8)));
+        assert_eq!(Some(None), reversed.map(|t| t.map::<u8, u8>(11)));
+        let round_trip = reversed.and_then(|t| Some(t.compose(t.inverse()?)));
+        assert_eq!(Some(Affine1D::IDENTITY), round_trip);
+        assert_eq!(None, Affine1D::new(0., 5.).inverse());
+        let calibration = LinearCalibration::two_point((0., 1.), (1., 3.));
+        assert_eq!(Some(Affine1D::new(2., 1.)), calibration.map(Affine1D::from));
+    }
+    #[test]
+    fn test_affine_n() {
+        let boxes = AffineN::from_boxes([(0., 4095.), (4095., 0.)], [(0., 800.), (0., 480.)]);
+        assert_eq!(Some([0., 480.]), boxes.map(|t| t.apply([0., 0.])));
+        assert_eq!(None, AffineN::from_boxes([(1., 1.)], [(0., 1.)]));
+        let transform = AffineN {
+            matrix: [[0., -2.], [1., 0.]],
+            offset: [3., -1.],
+        };
+        let pairs = [[0., 0.], [1., 0.], [0., 1.], [2., 5.]].map(|p| (p, transform.apply(p)));
+        assert_eq!(Some(transform), AffineN::fit(&pairs));
+        let collinear = [[0., 0.], [1., 1.], [2., 2.]].map(|p| (p, p));
+        assert_eq!(None, AffineN::fit(&collinear));
+        assert_eq!(None, AffineN::<2>::fit(&[]));
+        let round_trip = transform
+            .inverse()
+            .map(|inverse| transform.compose(&inverse));
+        assert_eq!(Some(AffineN::identity()), round_trip);
+        assert_eq!(
+            None,
+            AffineN::from_axes([Affine1D::new(0., 1.); 2]).inverse()
+        );
+        let axes = AffineN::from_axes([Affine1D::new(2., 1.), Affine1D::new(-1., 0.)]);
+        assert_eq!(Some([5., -3.]), Some(axes.apply([2., 3.])));
+        assert_eq!(Some([[4., 0.], [0., 1.]]), Some(axes.compose(&axes).matrix));
+    }
+}
@@ -0,0 +1,166 @@
+//! Gain mappings for mixing audio.
+
+use crate::math;
+
+/// How much both channels are attenuated with the signal panned to the center.
+///
+/// At the far left and right, all laws send the full signal to one channel and nothing to the other.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum PanLaw {
+    /// -3 dB in the center. The squared gains add up to one, so the loudness stays the same
+    /// while panning in a room.
+    #[default]
+    ConstantPower,
+    /// -4.5 dB in the center, halfway between the other two laws.
+    Compromise,
+    /// -6 dB in the center. The gains add up to one, so mono downmixes stay at the same level.
+    Linear,
+}
+
+impl PanLaw {
+    /// The `(left, right)` gains for `position`, which goes from -1 for hard left over 0 for the
+    /// center to 1 for hard right.
+    ///
+    /// Returns `None` if `position` is outside of `-1..=1`.
+    ///
+    /// ```
+    /// use map_to_range::{audio::PanLaw, MapRange};
+    ///
+    /// assert_eq!(Some((0.5, 0.5)), PanLaw::Linear.gains(0.));
+    /// assert_eq!(Some((1., 0.)), PanLaw::ConstantPower.gains(-1.));
+    ///
+    /// // A pan knob reading 0..=1023, a quarter of the way from the center to the right.
+    /// let position = 640_f64.map_range((0., 1024.), (-1., 1.)).unwrap();
+    /// assert_eq!(Some((0.375, 0.625)), PanLaw::Linear.gains(position));
+    /// ```
+    #[must_use]
+    pub fn gains(self, position: f64) -> Option<(f64, f64)> {
+        if !(-1. ..=1.).contains(&position) {
+            return None;
+        }
+        let linear = (f64::midpoint(1., -position), f64::midpoint(1., position));
+        let apply = |gain: f64| match self {
+            Self::ConstantPower => math::sqrt(gain),
+            // The geometric mean of the other two laws, gain^(3/4).
+            Self::Compromise => math::sqrt(gain) * math::sqrt(math::sqrt(gain)),
+            Self::Linear => gain,
+        };
+        Some((apply(linear.0), apply(linear.1)))
+    }
+}
+
+/// The static curve of a compressor, mapping an input level to an output level.
+///
+/// Above `threshold`, every `ratio` units of input only raise the output by one unit. Around the
+/// threshold, the knee bends smoothly from one slope into the other over `knee` units.
+/// Levels are usually decibels, but any unit works, like the brightness of a light that should
+/// approach its maximum gracefully instead of clipping.
+///
+/// ```
+/// use map_to_range::audio::SoftKnee;
+///
+/// let compressor = SoftKnee::new(-20., 4., 0.).unwrap();
+/// assert_eq!(-30., compressor.output(-30.));
+/// assert_eq!(-15., compressor.output(0.));
+/// assert_eq!(-15., compressor.gain(0.));
+///
+/// // A limiter with a soft knee never goes above its threshold.
+/// let limiter = SoftKnee::new(-1., f64::INFINITY, 4.).unwrap();
+/// assert_eq!(-1.5, limiter.output(-1.));
+/// assert_eq!(-1., limiter.output(24.));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SoftKnee {
+    threshold: f64,
+    slope: f64,
+    knee: f64,
+}
+
+impl SoftKnee {
+    /// A curve with the given threshold, ratio and knee width.
+    ///
+    /// A ratio of `f64::INFINITY` makes it a limiter.
+    /// Returns `None` if `threshold` or `knee` is not finite, `knee` is negative or `ratio` is below 1.
+    #[must_use]
+    pub fn new(threshold: f64, ratio: f64, knee: f64) -> Option<Self> {
+        if !threshold.is_finite() || !knee.is_finite() || knee < 0. || ratio.is_nan() || ratio < 1.
+        {
+            return None;
+        }
+        Some(Self {
+            threshold,
+            slope: 1. / ratio,
+            knee,
+        })
+    }
+    /// The output level for the input `level`.
+    #[must_use]
+    pub fn output(&self, level: f64) -> f64 {
+        let over = level - self.threshold;
+        if 2. * over <= -self.knee {
+            level
+        } else if 2. * over < self.knee {
+            let into_knee = over + self.knee / 2.;
+            level + (self.slope - 1.) * into_knee * into_knee / (2. * self.knee)
+        } else {
+            self.threshold + over * self.slope
+        }
+    }
+    /// How much the curve changes the input `level`, negative when it is turned down.
+    #[must_use]
+    pub fn gain(&self, level: f64) -> f64 {
+        self.output(level) - level
+    }
+    /// The level at which the curve starts to turn levels down, in the middle of the knee.
+    #[must_use]
+    pub const fn threshold(&self) -> f64 {
+        self.threshold
+    }
+    /// The width of the knee around the threshold.
+    #[must_use]
+    pub const fn knee(&self) -> f64 {
+        self.knee
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pan_law_center() {
+        let decibels = |gain: f64| 20. * math::ln(gain) / core::f64::consts::LN_10;
+        for (law, expected) in [
+            (PanLaw::ConstantPower, -3.01),
+            (PanLaw::Compromise, -4.52),
+            (PanLaw::Linear, -6.02),
+        ] {
+            let gains = law.gains(0.);
+            assert!(gains.is_some_and(|(left, right)| left.to_bits() == right.to_bits()));
+            assert!(gains.is_some_and(|(left, _)| (decibels(left) - expected).abs() < 0.01));
+            assert_eq!(Some((0., 1.)), law.gains(1.));
+            assert_eq!(None, law.gains(1.5));
+            assert_eq!(None, law.gains(f64::NAN));
+        }
+        let power = PanLaw::ConstantPower.gains(0.3);
+        assert!(power.is_some_and(|(left, right)| (left * left + right * right - 1.).abs() < 1e-15));
+    }
+    #[test]
+    fn test_soft_knee() {
+        assert_eq!(None, SoftKnee::new(0., 0.5, 1.));
+        assert_eq!(None, SoftKnee::new(0., 2., -1.));
+        assert_eq!(None, SoftKnee::new(f64::NAN, 2., 1.));
+        let curve = SoftKnee::new(-10., 2., 6.);
+        assert_eq!(Some(-13.), curve.map(|c| c.output(-13.)));
+        assert_eq!(Some(-10.375), curve.map(|c| c.output(-10.)));
+        assert_eq!(Some(-8.5), curve.map(|c| c.output(-7.)));
+        assert_eq!(Some(-5.), curve.map(|c| c.output(0.)));
+        // The curve is continuous and never falls while the input rises.
+        let mut previous = -20.1;
+        for step in 0..=200 {
+            let output = curve.map_or(f64::NAN, |c| c.output(-20. + f64::from(step) * 0.1));
+            assert!(output >= previous && output - previous < 0.11);
+            previous = output;
+        }
+    }
+}
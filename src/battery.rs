@@ -0,0 +1,156 @@
+//! Estimating the state of charge of a battery from its voltage.
+//!
+//! The voltage of a battery is far from linear to its charge, so mapping it linearly to a
+//! percentage is very inaccurate. These curves map a resting voltage per cell through a table instead.
+
+use crate::{piecewise, MapRange};
+
+/// A discharge curve as a table of `(volts per cell, percent)` points.
+///
+/// The built-in curves are typical resting voltages. Real batteries deviate from them with
+/// load, temperature and age, so measure your own curve with [`BatteryCurve::custom`] when
+/// precision matters.
+///
+/// ```
+/// use map_to_range::battery::BatteryCurve;
+///
+/// // A 3 cell LiPo pack.
+/// let pack_voltage = 11.475_f64;
+/// let percent: Option<u8> = BatteryCurve::LIPO.state_of_charge(pack_voltage / 3.);
+/// assert_eq!(Some(46), percent);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BatteryCurve<'a> {
+    table: &'a [(f64, f64)],
+}
+
+impl<'a> BatteryCurve<'a> {
+    /// Lithium polymer and lithium ion cells, 3.27 V to 4.2 V.
+    pub const LIPO: BatteryCurve<'static> = BatteryCurve {
+        table: &[
+            (3.27, 0.),
+            (3.61, 5.),
+            (3.69, 10.),
+            (3.71, 15.),
+            (3.73, 20.),
+            (3.75, 25.),
+            (3.77, 30.),
+            (3.79, 35.),
+            (3.80, 40.),
+            (3.82, 45.),
+            (3.84, 50.),
+            (3.85, 55.),
+            (3.87, 60.),
+            (3.91, 65.),
+            (3.95, 70.),
+            (3.98, 75.),
+            (4.02, 80.),
+            (4.08, 85.),
+            (4.11, 90.),
+            (4.15, 95.),
+            (4.20, 100.),
+        ],
+    };
+    /// Lithium iron phosphate cells, 2.5 V to 3.4 V. The curve is very flat in the middle,
+    /// so small voltage errors cause big jumps in the estimate there.
+    pub const LIFEPO4: BatteryCurve<'static> = BatteryCurve {
+        table: &[
+            (2.50, 0.),
+            (3.00, 9.),
+            (3.20, 14.),
+            (3.22, 17.),
+            (3.25, 20.),
+            (3.26, 30.),
+            (3.27, 40.),
+            (3.30, 70.),
+            (3.32, 90.),
+            (3.35, 99.),
+            (3.40, 100.),
+        ],
+    };
+    /// Alkaline primary cells, 0.9 V to 1.58 V.
+    pub const ALKALINE: BatteryCurve<'static> = BatteryCurve {
+        table: &[
+            (0.90, 0.),
+            (1.00, 5.),
+            (1.10, 15.),
+            (1.18, 30.),
+            (1.24, 50.),
+            (1.30, 70.),
+            (1.36, 80.),
+            (1.42, 90.),
+            (1.50, 97.),
+            (1.58, 100.),
+        ],
+    };
+    /// A custom curve of `(volts per cell, percent)` points.
+    ///
+    /// Returns `None` if the table is not valid (see [`piecewise::is_valid`]) or a percentage
+    /// lies outside of `0..=100`.
+    #[must_use]
+    pub fn custom(table: &'a [(f64, f64)]) -> Option<Self> {
+        let percentages_valid = table
+            .iter()
+            .all(|(_, percent)| (0. ..=100.).contains(percent));
+        (piecewise::is_valid(table) && percentages_valid).then_some(Self { table })
+    }
+    /// The `(volts per cell, percent)` points of this curve.
+    #[must_use]
+    pub const fn table(&self) -> &'a [(f64, f64)] {
+        self.table
+    }
+    /// The state of charge in percent for a voltage per cell, without any casting.
+    ///
+    /// Voltages outside of the curve are clamped to 0% or 100%.
+    /// Returns `None` if the voltage is `NaN`.
+    #[must_use]
+    pub fn state_of_charge_f64(&self, volts_per_cell: f64) -> Option<f64> {
+        piecewise::interpolate_clamped(self.table, volts_per_cell)
+    }
+    /// The state of charge in percent for a voltage per cell.
+    ///
+    /// Voltages outside of the curve are clamped to 0% or 100%.
+    /// Returns `None` if the voltage is `NaN`.
+    #[must_use]
+    pub fn state_of_charge<T: MapRange, U: MapRange>(&self, volts_per_cell: T) -> Option<U> {
+        U::checked_cast_back(self.state_of_charge_f64(volts_per_cell.checked_f64_cast()?)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_builtin_curves_are_valid() {
+        for curve in [
+            BatteryCurve::LIPO,
+            BatteryCurve::LIFEPO4,
+            BatteryCurve::ALKALINE,
+        ] {
+            assert_eq!(Some(curve), BatteryCurve::custom(curve.table()));
+            assert_eq!(Some(0.), curve.state_of_charge_f64(0.));
+            assert_eq!(Some(100.), curve.state_of_charge_f64(5.));
+        }
+    }
+    #[test]
+    fn test_state_of_charge() {
+        assert_eq!(Some(50.), BatteryCurve::LIPO.state_of_charge_f64(3.84));
+        assert_eq!(
+            Some(55_u8),
+            BatteryCurve::LIFEPO4.state_of_charge(3.285_f64)
+        );
+        assert_eq!(None, BatteryCurve::ALKALINE.state_of_charge_f64(f64::NAN));
+    }
+    #[test]
+    fn test_custom_curve() {
+        let table = [(10., 0.), (12., 100.)];
+        let curve = BatteryCurve::custom(&table);
+        assert_eq!(
+            Some(Some(25_u8)),
+            curve.map(|c| c.state_of_charge(10.5_f32))
+        );
+        assert_eq!(None, BatteryCurve::custom(&[(10., 0.), (12., 120.)]));
+        assert_eq!(None, BatteryCurve::custom(&[(12., 0.), (10., 100.)]));
+    }
+}
@@ -0,0 +1,131 @@
+//! Integer mapping without branches, for interrupt handlers that need a constant run time.
+//!
+//! [`BranchlessMapper`] does all checks and the only division when it is created. Mapping a value
+//! is then a clamp, which compiles to conditional moves, one widening multiply and a shift,
+//! so it takes the same number of cycles for every input and never reaches a panic.
+
+/// A mapping between two `i32` ranges that runs in constant time.
+///
+/// The scale between the ranges is stored as a 32.32 fixed point number, rounded up. Results are
+/// rounded to the nearest whole number, halves upwards. For from ranges up to 32767 wide they
+/// are exact, wider ranges can be one off when the exact result is close to a half.
+/// The ends of the from range always map exactly to the ends of the target range.
+///
+/// ```
+/// use map_to_range::branchless::BranchlessMapper;
+///
+/// let adc_to_duty = BranchlessMapper::new((0, 4095), (0, 1000)).unwrap();
+/// assert_eq!(500, adc_to_duty.map(2048));
+/// assert_eq!(1000, adc_to_duty.map(4095));
+/// assert_eq!(1000, adc_to_duty.map(5000));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BranchlessMapper {
+    from_range: (i32, i32),
+    to_start: i32,
+    /// `(to.1 - to.0) / (from.1 - from.0)` with 32 fractional bits.
+    scale: i64,
+}
+
+impl BranchlessMapper {
+    /// Creates the mapper, doing the division between the ranges once.
+    ///
+    /// `to_range` may be reversed. Returns `None` if `from_range` is empty or reversed,
+    /// or a range is wider than `i32::MAX`.
+    #[must_use]
+    pub fn new(from_range: (i32, i32), to_range: (i32, i32)) -> Option<Self> {
+        let from_width = i64::from(from_range.1) - i64::from(from_range.0);
+        let to_width = i64::from(to_range.1) - i64::from(to_range.0);
+        let limit = i64::from(i32::MAX);
+        if from_width <= 0 || from_width > limit || to_width.abs() > limit {
+            return None;
+        }
+        // Rounding the scale up keeps every error positive, so rounding the result corrects it.
+        let numerator = i128::from(to_width) << 32;
+        let scale = -(-numerator).div_euclid(i128::from(from_width));
+        Some(Self {
+            from_range,
+            to_start: to_range.0,
+            scale: i64::try_from(scale).ok()?,
+        })
+    }
+    /// Maps `value` into the target range, clamping it into the from range first.
+    #[must_use]
+    pub fn map(&self, value: i32) -> i32 {
+        // `min` and `max` instead of `clamp`, which asserts its bounds.
+        let value = value.max(self.from_range.0).min(self.from_range.1);
+        let offset = i64::from(value) - i64::from(self.from_range.0);
+        // The ranges are limited in `new`, so none of these can wrap. The wrapping operations
+        // only keep overflow checks of debug builds out of the generated code.
+        let scaled = offset.wrapping_mul(self.scale).wrapping_add(1 << 31);
+        (i64::from(self.to_start).wrapping_add(scaled >> 32)) as i32
+    }
+    /// Maps every value in `values` in place, see [`BranchlessMapper::map`].
+    pub fn map_slice(&self, values: &mut [i32]) {
+        for value in values {
+            *value = self.map(*value);
+        }
+    }
+    /// The range values are mapped from.
+    #[must_use]
+    pub const fn from_range(&self) -> (i32, i32) {
+        self.from_range
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// The exact result, rounded to nearest with halves upwards.
+    fn exact(value: i32, from: (i32, i32), to: (i32, i32)) -> i32 {
+        let numerator =
+            2 * i128::from(value - from.0) * i128::from(to.1 - to.0) + i128::from(from.1 - from.0);
+        let denominator = 2 * i128::from(from.1 - from.0);
+        (i128::from(to.0) + numerator.div_euclid(denominator)) as i32
+    }
+
+    #[test]
+    fn test_exact_for_narrow_ranges() {
+        for (from, to) in [
+            ((0, 7), (3, 1000)),
+            ((-100, 100), (1000, -1000)),
+            ((0, 4095), (0, 255)),
+            ((0, 32767), (-32768, 32767)),
+            ((10, 13), (0, -7)),
+        ] {
+            let mapper = BranchlessMapper::new(from, to);
+            for value in from.0..=from.1 {
+                assert_eq!(Some(exact(value, from, to)), mapper.map(|m| m.map(value)));
+            }
+        }
+    }
+    #[test]
+    fn test_wide_ranges() {
+        let full = BranchlessMapper::new((i32::MIN, -1), (i32::MAX, 0));
+        assert_eq!(Some(i32::MAX), full.map(|m| m.map(i32::MIN)));
+        assert_eq!(Some(0), full.map(|m| m.map(-1)));
+        assert_eq!(Some(0), full.map(|m| m.map(i32::MAX)));
+        let widening = BranchlessMapper::new((0, 1), (i32::MIN + 1, 0));
+        assert_eq!(Some(0), widening.map(|m| m.map(1)));
+        let narrowing = BranchlessMapper::new((0, i32::MAX), (0, 1));
+        for value in [0, 1 << 29, 3 << 29, i32::MAX] {
+            assert_eq!(
+                Some(exact(value, (0, i32::MAX), (0, 1))),
+                narrowing.map(|m| m.map(value))
+            );
+        }
+    }
+    #[test]
+    fn test_rejects_and_slices() {
+        assert_eq!(None, BranchlessMapper::new((5, 5), (0, 1)));
+        assert_eq!(None, BranchlessMapper::new((5, 4), (0, 1)));
+        assert_eq!(None, BranchlessMapper::new((-1, i32::MAX), (0, 1)));
+        assert_eq!(None, BranchlessMapper::new((0, 1), (i32::MAX, -1)));
+        let mut values = [-5, 0, 50, 100, 500];
+        if let Some(mapper) = BranchlessMapper::new((0, 100), (0, 10)) {
+            mapper.map_slice(&mut values);
+        }
+        assert_eq!([0, 0, 5, 10, 10], values);
+    }
+}
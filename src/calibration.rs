@@ -0,0 +1,918 @@
+//! Fitting mappings to measured calibration points.
+//!
+//! Every point is a `(raw, reference)` pair: the value the sensor reported and the value
+//! it should have reported.
+
+use crate::piecewise::PiecewiseLinear;
+use crate::{math, MapRange};
+
+/// The best linear mapping `reference = slope * raw + offset` through a set of points.
+///
+/// ```
+/// use map_to_range::calibration::LinearCalibration;
+///
+/// let points = [(100., 0.), (200., 10.1), (300., 19.9), (400., 30.)];
+/// let calibration = LinearCalibration::fit(&points).unwrap();
+/// assert_eq!(Some(15_u8), calibration.map(250_u16));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LinearCalibration {
+    /// The change of the reference value per raw unit.
+    pub slope: f64,
+    /// The reference value at a raw value of zero.
+    pub offset: f64,
+}
+
+impl LinearCalibration {
+    /// Fits a line through `points` with the least squares method.
+    ///
+    /// Returns `None` if there are less than two distinct raw values or a point is not finite.
+    #[must_use]
+    pub fn fit(points: &[(f64, f64)]) -> Option<Self> {
+        Self::fit_iter(points.iter().copied())
+    }
+    /// [`LinearCalibration::fit`] over an iterator, so transformed points need no buffer.
+    fn fit_iter<I>(points: I) -> Option<Self>
+    where
+        I: Iterator<Item = (f64, f64)> + Clone,
+    {
+        if points
+            .clone()
+            .any(|(raw, reference)| !raw.is_finite() || !reference.is_finite())
+        {
+            return None;
+        }
+        let count = points.clone().count() as f64;
+        let mean_raw = points.clone().map(|point| point.0).sum::<f64>() / count;
+        let mean_reference = points.clone().map(|point| point.1).sum::<f64>() / count;
+        let mut spread = 0.;
+        let mut covariance = 0.;
+        for (raw, reference) in points {
+            spread += (raw - mean_raw) * (raw - mean_raw);
+            covariance += (raw - mean_raw) * (reference - mean_reference);
+        }
+        if spread <= 0. {
+            return None;
+        }
+        let slope = covariance / spread;
+        Some(Self {
+            slope,
+            offset: mean_reference - slope * mean_raw,
+        })
+    }
+    /// The line through exactly two points.
+    ///
+    /// Returns `None` if both points have the same raw value.
+    #[must_use]
+    pub fn two_point(first: (f64, f64), second: (f64, f64)) -> Option<Self> {
+        Self::fit(&[first, second])
+    }
+    /// Maps a raw value to its reference value without any casting.
+    #[must_use]
+    pub fn map_f64(&self, raw: f64) -> f64 {
+        self.slope * raw + self.offset
+    }
+    /// Maps a raw value to its reference value.
+    ///
+    /// Returns `None` if the result does not fit into `U`.
+    #[must_use]
+    pub fn map<T: MapRange, U: MapRange>(&self, raw: T) -> Option<U> {
+        U::checked_cast_back(self.map_f64(raw.checked_f64_cast()?))
+    }
+    /// Expresses the calibration as a `(from_range, to_range)` pair that can be passed to
+    /// [`MapRange::map_range`].
+    ///
+    /// ```
+    /// use map_to_range::{calibration::LinearCalibration, MapRange};
+    ///
+    /// let calibration = LinearCalibration::two_point((10., 0.), (20., 100.)).unwrap();
+    /// let (from_range, to_range) = calibration.ranges((10., 20.));
+    /// assert_eq!((0., 100.), to_range);
+    /// assert_eq!(Some(50.), 15_f64.map_range(from_range, to_range));
+    /// ```
+    #[must_use]
+    pub fn ranges(&self, from_range: (f64, f64)) -> ((f64, f64), (f64, f64)) {
+        let to_range = (self.map_f64(from_range.0), self.map_f64(from_range.1));
+        (from_range, to_range)
+    }
+}
+
+/// The highest degree supported by [`PolynomialCalibration`].
+pub const MAX_POLYNOMIAL_DEGREE: usize = 3;
+
+/// The best low order polynomial mapping through a set of points.
+///
+/// This is useful to linearize sensors like thermocouples or NTCs, where a straight line
+/// is not good enough.
+///
+/// ```
+/// use map_to_range::calibration::PolynomialCalibration;
+///
+/// let points = [(0., 0.), (1., 1.), (2., 4.), (3., 9.), (4., 16.)];
+/// let calibration = PolynomialCalibration::fit(&points, 2).unwrap();
+/// assert_eq!(Some(25_u8), calibration.map(5_u8));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PolynomialCalibration {
+    /// Coefficients of ascending powers of the normalized raw value.
+    coefficients: [f64; MAX_POLYNOMIAL_DEGREE + 1],
+    degree: usize,
+    /// The raw values are normalized with `(raw - center) / scale` to keep the fit well
+    /// conditioned for big raw values like ADC counts.
+    center: f64,
+    scale: f64,
+}
+
+impl PolynomialCalibration {
+    /// Fits a polynomial of `degree` through `points` with the least squares method.
+    ///
+    /// Returns `None` if `degree` is not within `1..=3`, a point is not finite or there are
+    /// not enough distinct raw values to determine the polynomial.
+    #[must_use]
+    pub fn fit(points: &[(f64, f64)], degree: usize) -> Option<Self> {
+        if !(1..=MAX_POLYNOMIAL_DEGREE).contains(&degree)
+            || points
+                .iter()
+                .any(|(raw, reference)| !raw.is_finite() || !reference.is_finite())
+        {
+            return None;
+        }
+        let distinct = points
+            .iter()
+            .enumerate()
+            .filter(|(i, (raw, _))| {
+                points
+                    .iter()
+                    .take(*i)
+                    .all(|(other, _)| other.total_cmp(raw).is_ne())
+            })
+            .count();
+        if distinct <= degree {
+            return None;
+        }
+        let smallest = points.iter().map(|point| point.0).reduce(f64::min)?;
+        let largest = points.iter().map(|point| point.0).reduce(f64::max)?;
+        let center = f64::midpoint(smallest, largest);
+        let scale = (largest - smallest) / 2.;
+        if scale <= 0. {
+            return None;
+        }
+        // Build the normal equations. Powers above the degree get an identity row,
+        // so their coefficient is solved to zero.
+        let mut matrix = [[0.; MAX_POLYNOMIAL_DEGREE + 1]; MAX_POLYNOMIAL_DEGREE + 1];
+        let mut rhs = [0.; MAX_POLYNOMIAL_DEGREE + 1];
+        for (raw, reference) in points {
+            let u = (raw - center) / scale;
+            let mut row_power = 1.;
+            for (row, value) in matrix.iter_mut().zip(&mut rhs).take(degree + 1) {
+                let mut power = row_power;
+                for entry in row.iter_mut().take(degree + 1) {
+                    *entry += power;
+                    power *= u;
+                }
+                *value += row_power * reference;
+                row_power *= u;
+            }
+        }
+        for (index, row) in matrix.iter_mut().enumerate().skip(degree + 1) {
+            *row.get_mut(index)? = 1.;
+        }
+        let coefficients = math::solve(matrix, rhs)?;
+        Some(Self {
+            coefficients,
+            degree,
+            center,
+            scale,
+        })
+    }
+    /// The degree of the fitted polynomial.
+    #[must_use]
+    pub const fn degree(&self) -> usize {
+        self.degree
+    }
+    /// Maps a raw value to its reference value without any casting.
+    #[must_use]
+    pub fn map_f64(&self, raw: f64) -> f64 {
+        let u = (raw - self.center) / self.scale;
+        self.coefficients
+            .iter()
+            .rev()
+            .fold(0., |result, coefficient| result * u + coefficient)
+    }
+    /// Maps a raw value to its reference value.
+    ///
+    /// Returns `None` if the result does not fit into `U`.
+    #[must_use]
+    pub fn map<T: MapRange, U: MapRange>(&self, raw: T) -> Option<U> {
+        U::checked_cast_back(self.map_f64(raw.checked_f64_cast()?))
+    }
+}
+
+/// The best exponential mapping `reference = scale * e^(rate * raw)` through a set of points.
+///
+/// This fits for example light sensors or discharge curves.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ExponentialCalibration {
+    /// The reference value at a raw value of zero.
+    pub scale: f64,
+    /// The growth rate per raw unit, negative for decay.
+    pub rate: f64,
+}
+
+impl ExponentialCalibration {
+    /// Fits the exponential through `points` with the least squares method on the
+    /// logarithm of the reference values.
+    ///
+    /// Returns `None` if a reference value is not positive or there are less than
+    /// two distinct raw values.
+    #[must_use]
+    pub fn fit(points: &[(f64, f64)]) -> Option<Self> {
+        // Non positive references turn into non finite logarithms, which the fit rejects.
+        let transformed = points
+            .iter()
+            .map(|(raw, reference)| (*raw, math::ln(*reference)));
+        LinearCalibration::fit_iter(transformed).map(|line| Self {
+            scale: math::exp(line.offset),
+            rate: line.slope,
+        })
+    }
+    /// Maps a raw value to its reference value without any casting.
+    #[must_use]
+    pub fn map_f64(&self, raw: f64) -> f64 {
+        self.scale * math::exp(self.rate * raw)
+    }
+    /// Maps a raw value to its reference value.
+    ///
+    /// Returns `None` if the result does not fit into `U`.
+    #[must_use]
+    pub fn map<T: MapRange, U: MapRange>(&self, raw: T) -> Option<U> {
+        U::checked_cast_back(self.map_f64(raw.checked_f64_cast()?))
+    }
+}
+
+/// The best logarithmic mapping `reference = offset + slope * ln(raw)` through a set of points.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LogarithmicCalibration {
+    /// The reference value at a raw value of one.
+    pub offset: f64,
+    /// The change of the reference value per natural logarithm of the raw value.
+    pub slope: f64,
+}
+
+impl LogarithmicCalibration {
+    /// Fits the logarithm through `points` with the least squares method on the
+    /// logarithm of the raw values.
+    ///
+    /// Returns `None` if a raw value is not positive or there are less than
+    /// two distinct raw values.
+    #[must_use]
+    pub fn fit(points: &[(f64, f64)]) -> Option<Self> {
+        // Non positive raw values turn into non finite logarithms, which the fit rejects.
+        let transformed = points
+            .iter()
+            .map(|(raw, reference)| (math::ln(*raw), *reference));
+        LinearCalibration::fit_iter(transformed).map(|line| Self {
+            offset: line.offset,
+            slope: line.slope,
+        })
+    }
+    /// Maps a raw value to its reference value without any casting.
+    ///
+    /// Raw values that are not positive produce `NaN`.
+    #[must_use]
+    pub fn map_f64(&self, raw: f64) -> f64 {
+        self.offset + self.slope * math::ln(raw)
+    }
+    /// Maps a raw value to its reference value.
+    ///
+    /// Returns `None` if the result does not fit into `U`.
+    #[must_use]
+    pub fn map<T: MapRange, U: MapRange>(&self, raw: T) -> Option<U> {
+        U::checked_cast_back(self.map_f64(raw.checked_f64_cast()?))
+    }
+}
+
+/// A fitted calibration of any of the supported models.
+///
+/// ```
+/// use map_to_range::calibration::Calibration;
+///
+/// // A light sensor, doubling its reading every 10 raw units.
+/// let points = [(0., 1.), (10., 2.01), (20., 3.98), (30., 8.02), (40., 15.9)];
+/// let calibration = Calibration::best_fit(&points).unwrap();
+/// assert!(matches!(calibration, Calibration::Exponential(_)));
+/// let brightness: f32 = calibration.map(50_u16).unwrap();
+/// assert!((brightness - 32.).abs() < 0.5);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Calibration {
+    /// A straight line.
+    Linear(LinearCalibration),
+    /// A polynomial of degree two or three.
+    Polynomial(PolynomialCalibration),
+    /// An exponential growth or decay.
+    Exponential(ExponentialCalibration),
+    /// A logarithm.
+    Logarithmic(LogarithmicCalibration),
+}
+
+impl Calibration {
+    /// Fits every model to `points` and returns the one with the smallest
+    /// [`residual_error`](Calibration::residual_error).
+    ///
+    /// Models are only considered if there are more points than they have parameters,
+    /// otherwise they would trivially match every point.
+    ///
+    /// Returns `None` if no model could be fitted.
+    #[must_use]
+    pub fn best_fit(points: &[(f64, f64)]) -> Option<Self> {
+        let candidates = [
+            LinearCalibration::fit(points).map(Calibration::Linear),
+            PolynomialCalibration::fit(points, 2).map(Calibration::Polynomial),
+            PolynomialCalibration::fit(points, 3).map(Calibration::Polynomial),
+            ExponentialCalibration::fit(points).map(Calibration::Exponential),
+            LogarithmicCalibration::fit(points).map(Calibration::Logarithmic),
+        ];
+        candidates
+            .into_iter()
+            .flatten()
+            .filter_map(|candidate| Some((candidate.residual_error(points)?, candidate)))
+            .min_by(|a, b| a.0.total_cmp(&b.0))
+            .map(|(_, candidate)| candidate)
+    }
+    /// Builds a table mapping through arbitrary measured points, for sensors that no model fits.
+    ///
+    /// The points are sorted by their raw value, and repeated measurements of the same raw
+    /// value are averaged. The reference values then have to be strictly increasing or strictly
+    /// decreasing, so every reference value belongs to exactly one raw value.
+    ///
+    /// Returns `None` if there are more than `N` or less than two distinct raw values,
+    /// a point is not finite or the references are not monotonic.
+    ///
+    /// ```
+    /// use map_to_range::calibration::Calibration;
+    ///
+    /// let points = [(300., 25.), (100., 5.), (200., 12.), (100., 7.)];
+    /// let table = Calibration::from_points::<8>(&points).unwrap();
+    /// assert_eq!([(100., 6.), (200., 12.), (300., 25.)], table.points());
+    /// assert_eq!(Some(9_u8), table.map(150_u16));
+    ///
+    /// assert_eq!(None, Calibration::from_points::<8>(&[(0., 0.), (1., 2.), (2., 1.)]));
+    /// ```
+    #[must_use]
+    pub fn from_points<const N: usize>(points: &[(f64, f64)]) -> Option<PiecewiseLinear<N>> {
+        if points
+            .iter()
+            .any(|(raw, reference)| !raw.is_finite() || !reference.is_finite())
+        {
+            return None;
+        }
+        // The sum and count of the references of every distinct raw value, sorted by raw value.
+        let mut sorted = [(0., 0., 0_u32); N];
+        let mut distinct = 0;
+        for &(raw, reference) in points {
+            let position = sorted
+                .get(..distinct)?
+                .partition_point(|point| point.0 < raw);
+            match sorted.get_mut(position) {
+                // Nothing from `position` on is below `raw`, so this means equal.
+                Some(point) if position < distinct && point.0 <= raw => {
+                    point.1 += reference;
+                    point.2 += 1;
+                }
+                _ => {
+                    *sorted.get_mut(distinct)? = (raw, reference, 1);
+                    sorted.get_mut(position..=distinct)?.rotate_right(1);
+                    distinct += 1;
+                }
+            }
+        }
+        let mut table = [(0., 0.); N];
+        for (slot, (raw, sum, count)) in table.iter_mut().zip(sorted) {
+            *slot = (raw, sum / f64::from(count));
+        }
+        let table = table.get(..distinct)?;
+        let rising = table.windows(2).all(|pair| match pair {
+            [a, b] => a.1 < b.1,
+            _ => false,
+        });
+        let falling = table.windows(2).all(|pair| match pair {
+            [a, b] => a.1 > b.1,
+            _ => false,
+        });
+        if distinct < 2 || !(rising || falling) {
+            return None;
+        }
+        PiecewiseLinear::new(table)
+    }
+    /// The number of parameters of the model.
+    #[must_use]
+    pub const fn parameters(&self) -> usize {
+        match self {
+            Calibration::Polynomial(polynomial) => polynomial.degree() + 1,
+            Calibration::Linear(_) | Calibration::Exponential(_) | Calibration::Logarithmic(_) => 2,
+        }
+    }
+    /// The residual standard error of the model over `points`,
+    /// `sqrt(sum of squared residuals / (points - parameters))`.
+    ///
+    /// Returns `None` if there are not more points than parameters or a residual is not finite.
+    #[must_use]
+    pub fn residual_error(&self, points: &[(f64, f64)]) -> Option<f64> {
+        let degrees_of_freedom = points.len().checked_sub(self.parameters())?;
+        if degrees_of_freedom == 0 {
+            return None;
+        }
+        let squared: f64 = points
+            .iter()
+            .map(|(raw, reference)| {
+                (self.map_f64(*raw) - reference) * (self.map_f64(*raw) - reference)
+            })
+            .sum();
+        squared
+            .is_finite()
+            .then(|| math::sqrt(squared / degrees_of_freedom as f64))
+    }
+    /// Maps a raw value to its reference value without any casting.
+    #[must_use]
+    pub fn map_f64(&self, raw: f64) -> f64 {
+        match self {
+            Calibration::Linear(calibration) => calibration.map_f64(raw),
+            Calibration::Polynomial(calibration) => calibration.map_f64(raw),
+            Calibration::Exponential(calibration) => calibration.map_f64(raw),
+            Calibration::Logarithmic(calibration) => calibration.map_f64(raw),
+        }
+    }
+    /// Maps a raw value to its reference value.
+    ///
+    /// Returns `None` if the result does not fit into `U`.
+    #[must_use]
+    pub fn map<T: MapRange, U: MapRange>(&self, raw: T) -> Option<U> {
+        U::checked_cast_back(self.map_f64(raw.checked_f64_cast()?))
+    }
+}
+
+/// The size of a [`Calibration`] serialized with [`Calibration::to_bytes`].
+pub const SERIALIZED_CALIBRATION_LEN: usize = 53;
+
+/// The format version written by [`Calibration::to_bytes`].
+pub const CALIBRATION_FORMAT_VERSION: u8 = 1;
+
+/// The reason [`Calibration::from_bytes`] rejected its input.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CalibrationDecodeError {
+    /// The bytes were written in another format version, which is included.
+    Version(u8),
+    /// The checksum does not match, for example because the storage was never written.
+    Checksum,
+    /// The checksum matches, but the bytes do not describe a valid calibration.
+    Invalid,
+}
+
+impl Calibration {
+    /// Serializes the calibration for storage in EEPROM or flash.
+    ///
+    /// The layout is a version byte, a model byte, the polynomial degree, six little endian
+    /// `f64` parameters and a little endian CRC-16/CCITT of everything before it.
+    ///
+    /// ```
+    /// use map_to_range::calibration::{Calibration, LinearCalibration};
+    ///
+    /// let calibration = Calibration::Linear(LinearCalibration::two_point((0., 1.), (10., 21.)).unwrap());
+    /// let bytes = calibration.to_bytes();
+    /// assert_eq!(Ok(calibration), Calibration::from_bytes(&bytes));
+    /// ```
+    #[must_use]
+    pub fn to_bytes(&self) -> [u8; SERIALIZED_CALIBRATION_LEN] {
+        let (model, degree, parameters) = match self {
+            Calibration::Linear(line) => (0, 0, [line.slope, line.offset, 0., 0., 0., 0.]),
+            Calibration::Polynomial(polynomial) => {
+                let mut coefficients = polynomial.coefficients;
+                for coefficient in coefficients.iter_mut().skip(polynomial.degree + 1) {
+                    *coefficient = 0.;
+                }
+                let [c0, c1, c2, c3] = coefficients;
+                let parameters = [c0, c1, c2, c3, polynomial.center, polynomial.scale];
+                (1, polynomial.degree as u8, parameters)
+            }
+            Calibration::Exponential(curve) => (2, 0, [curve.scale, curve.rate, 0., 0., 0., 0.]),
+            Calibration::Logarithmic(curve) => (3, 0, [curve.offset, curve.slope, 0., 0., 0., 0.]),
+        };
+        let mut bytes = [0; SERIALIZED_CALIBRATION_LEN];
+        let (header, rest) = bytes.split_at_mut(3);
+        header.copy_from_slice(&[CALIBRATION_FORMAT_VERSION, model, degree]);
+        for (chunk, parameter) in rest.chunks_exact_mut(8).zip(parameters) {
+            chunk.copy_from_slice(&parameter.to_le_bytes());
+        }
+        let (payload, checksum) = bytes.split_at_mut(SERIALIZED_CALIBRATION_LEN - 2);
+        checksum.copy_from_slice(&crc16(payload).to_le_bytes());
+        bytes
+    }
+    /// Restores a calibration written by [`to_bytes`](Calibration::to_bytes).
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the version is not [`CALIBRATION_FORMAT_VERSION`], the checksum does
+    /// not match or the parameters are not finite or do not fit the model. Only polynomials
+    /// may have a non-zero degree, and parameters the model does not use must be zero.
+    pub fn from_bytes(
+        bytes: &[u8; SERIALIZED_CALIBRATION_LEN],
+    ) -> Result<Self, CalibrationDecodeError> {
+        let (payload, checksum) = bytes.split_at(SERIALIZED_CALIBRATION_LEN - 2);
+        if checksum != crc16(payload).to_le_bytes() {
+            return Err(CalibrationDecodeError::Checksum);
+        }
+        let [version, model, degree, rest @ ..] = payload else {
+            return Err(CalibrationDecodeError::Invalid);
+        };
+        if *version != CALIBRATION_FORMAT_VERSION {
+            return Err(CalibrationDecodeError::Version(*version));
+        }
+        let mut parameters = [0.; 6];
+        for (parameter, chunk) in parameters.iter_mut().zip(rest.chunks_exact(8)) {
+            let mut le_bytes = [0; 8];
+            le_bytes.copy_from_slice(chunk);
+            *parameter = f64::from_le_bytes(le_bytes);
+        }
+        if parameters.iter().any(|parameter| !parameter.is_finite()) {
+            return Err(CalibrationDecodeError::Invalid);
+        }
+        let degree = usize::from(*degree);
+        // Unused slots must be zero, so every calibration has exactly one encoding.
+        let unused = if *model == 1 { degree + 1..4 } else { 2..6 };
+        if !parameters
+            .get(unused)
+            .is_some_and(|unused| unused.iter().all(|parameter| parameter.to_bits() == 0))
+        {
+            return Err(CalibrationDecodeError::Invalid);
+        }
+        let [p0, p1, p2, p3, p4, p5] = parameters;
+        match model {
+            0 if degree == 0 => Ok(Calibration::Linear(LinearCalibration {
+                slope: p0,
+                offset: p1,
+            })),
+            1 if (1..=MAX_POLYNOMIAL_DEGREE).contains(&degree) && p5 > 0. => {
+                Ok(Calibration::Polynomial(PolynomialCalibration {
+                    coefficients: [p0, p1, p2, p3],
+                    degree,
+                    center: p4,
+                    scale: p5,
+                }))
+            }
+            2 if degree == 0 => Ok(Calibration::Exponential(ExponentialCalibration {
+                scale: p0,
+                rate: p1,
+            })),
+            3 if degree == 0 => Ok(Calibration::Logarithmic(LogarithmicCalibration {
+                offset: p0,
+                slope: p1,
+            })),
+            _ => Err(CalibrationDecodeError::Invalid),
+        }
+    }
+}
+
+/// The model byte of a serialized [`PiecewiseLinear`] table.
+const PIECEWISE_MODEL: u8 = 4;
+
+impl<const N: usize> PiecewiseLinear<N> {
+    /// The size of a table serialized with [`PiecewiseLinear::to_bytes`].
+    pub const SERIALIZED_LEN: usize = 4 + 16 * N + 2;
+
+    /// Serializes the table for storage in EEPROM or flash.
+    ///
+    /// The layout is a version byte, a model byte, the little endian `u16` number of points,
+    /// `N` pairs of little endian `f64`, with the unused ones zeroed, and a little endian
+    /// CRC-16/CCITT of everything before it. `L` must be
+    /// [`SERIALIZED_LEN`](PiecewiseLinear::SERIALIZED_LEN), otherwise this fails to compile.
+    ///
+    /// ```
+    /// use map_to_range::piecewise::PiecewiseLinear;
+    ///
+    /// let table = PiecewiseLinear::<4>::new(&[(0., 0.), (1., 10.), (2., 15.)]).unwrap();
+    /// let bytes: [u8; PiecewiseLinear::<4>::SERIALIZED_LEN] = table.to_bytes();
+    /// assert_eq!(Ok(table), PiecewiseLinear::from_bytes(&bytes));
+    /// ```
+    #[must_use]
+    pub fn to_bytes<const L: usize>(&self) -> [u8; L] {
+        const {
+            assert!(L == Self::SERIALIZED_LEN && N <= u16::MAX as usize);
+        }
+        let mut bytes = [0; L];
+        let points = self.points();
+        #[allow(clippy::cast_possible_truncation)] // `N` fits, see the assertion above.
+        let [count_low, count_high] = (points.len() as u16).to_le_bytes();
+        let (header, rest) = bytes.split_at_mut(4);
+        header.copy_from_slice(&[
+            CALIBRATION_FORMAT_VERSION,
+            PIECEWISE_MODEL,
+            count_low,
+            count_high,
+        ]);
+        for (chunk, (x, y)) in rest.chunks_exact_mut(16).zip(points) {
+            let (x_bytes, y_bytes) = chunk.split_at_mut(8);
+            x_bytes.copy_from_slice(&x.to_le_bytes());
+            y_bytes.copy_from_slice(&y.to_le_bytes());
+        }
+        let (payload, checksum) = bytes.split_at_mut(L - 2);
+        checksum.copy_from_slice(&crc16(payload).to_le_bytes());
+        bytes
+    }
+    /// Restores a table written by [`to_bytes`](PiecewiseLinear::to_bytes).
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the version is not [`CALIBRATION_FORMAT_VERSION`], the checksum does
+    /// not match, the bytes hold another model, more than `N` points or non-zero unused points,
+    /// or the points do not form a valid table, see [`is_valid`](crate::piecewise::is_valid).
+    pub fn from_bytes<const L: usize>(bytes: &[u8; L]) -> Result<Self, CalibrationDecodeError> {
+        const {
+            assert!(L == Self::SERIALIZED_LEN);
+        }
+        let (payload, checksum) = bytes.split_at(L - 2);
+        if checksum != crc16(payload).to_le_bytes() {
+            return Err(CalibrationDecodeError::Checksum);
+        }
+        let [version, model, count_low, count_high, rest @ ..] = payload else {
+            return Err(CalibrationDecodeError::Invalid);
+        };
+        if *version != CALIBRATION_FORMAT_VERSION {
+            return Err(CalibrationDecodeError::Version(*version));
+        }
+        let count = usize::from(u16::from_le_bytes([*count_low, *count_high]));
+        let unused = rest.get(16 * count..).unwrap_or_default();
+        if *model != PIECEWISE_MODEL || count > N || unused.iter().any(|byte| *byte != 0) {
+            return Err(CalibrationDecodeError::Invalid);
+        }
+        let mut points = [(0., 0.); N];
+        for (point, chunk) in points.iter_mut().zip(rest.chunks_exact(16)) {
+            let mut x_bytes = [0; 8];
+            let mut y_bytes = [0; 8];
+            let (x_chunk, y_chunk) = chunk.split_at(8);
+            x_bytes.copy_from_slice(x_chunk);
+            y_bytes.copy_from_slice(y_chunk);
+            *point = (f64::from_le_bytes(x_bytes), f64::from_le_bytes(y_bytes));
+        }
+        points
+            .get(..count)
+            .and_then(Self::new)
+            .ok_or(CalibrationDecodeError::Invalid)
+    }
+}
+
+/// CRC-16/CCITT-FALSE, the polynomial `0x1021` starting from `0xffff`.
+fn crc16(bytes: &[u8]) -> u16 {
+    bytes.iter().fold(0xffff, |crc, byte| {
+        (0..8).fold(crc ^ (u16::from(*byte) << 8), |crc, _| {
+            if crc & 0x8000 == 0 {
+                crc << 1
+            } else {
+                (crc << 1) ^ 0x1021
+            }
+        })
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fit_exact_line() {
+        let points = [(0., 1.), (1., 3.), (2., 5.)];
+        let calibration = LinearCalibration::fit(&points);
+        assert_eq!(Some(2.), calibration.map(|c| c.slope));
+        assert_eq!(Some(1.), calibration.map(|c| c.offset));
+        assert_eq!(Some(Some(21_i32)), calibration.map(|c| c.map(10_i32)));
+    }
+    #[test]
+    fn test_fit_noisy_points() {
+        let points = [(0., 0.1), (1., 0.9), (2., 2.1), (3., 2.9)];
+        let calibration = LinearCalibration::fit(&points);
+        assert!(calibration.is_some_and(|c| (c.slope - 0.96).abs() < 1e-12));
+        assert!(calibration.is_some_and(|c| (c.offset - 0.06).abs() < 1e-12));
+    }
+    #[test]
+    fn test_polynomial_fit() {
+        // reference = 2x³ - x + 1000, with raw values in ADC magnitudes.
+        let cubic = |x: f64| 2e-9 * x * x * x - x + 1000.;
+        let points = [0., 700., 1500., 2300., 3100., 4095.].map(|x| (x, cubic(x)));
+        let calibration = PolynomialCalibration::fit(&points, 3);
+        assert_eq!(Some(3), calibration.map(|c| c.degree()));
+        for x in [0., 1000., 3333., 4095.] {
+            assert!(calibration.is_some_and(|c| (c.map_f64(x) - cubic(x)).abs() < 1e-6));
+        }
+        let line = PolynomialCalibration::fit(&[(0., 1.), (1., 3.), (2., 5.)], 1);
+        assert!(line.is_some_and(|c| (c.map_f64(10.) - 21.).abs() < 1e-9));
+    }
+    #[test]
+    fn test_polynomial_fit_rejects() {
+        let points = [(0., 0.), (1., 1.), (2., 4.)];
+        assert_eq!(None, PolynomialCalibration::fit(&points, 0));
+        assert_eq!(None, PolynomialCalibration::fit(&points, 4));
+        assert_eq!(None, PolynomialCalibration::fit(&points, 3));
+        assert_eq!(None, PolynomialCalibration::fit(&[(1., 0.), (1., 1.)], 1));
+        assert_eq!(None, PolynomialCalibration::fit(&[], 2));
+    }
+    #[test]
+    fn test_exponential_and_logarithmic_fit() {
+        let decay = |x: f64| 5. * math::exp(-0.25 * x);
+        let points = [0., 1., 2., 5., 9.].map(|x| (x, decay(x)));
+        let exponential = ExponentialCalibration::fit(&points);
+        assert!(exponential.is_some_and(|c| (c.scale - 5.).abs() < 1e-12));
+        assert!(exponential.is_some_and(|c| (c.rate + 0.25).abs() < 1e-12));
+        assert_eq!(None, ExponentialCalibration::fit(&[(0., 1.), (1., 0.)]));
+
+        let logarithm = |x: f64| 3. + 2. * math::ln(x);
+        let points = [1., 2., 4., 100.].map(|x| (x, logarithm(x)));
+        let logarithmic = LogarithmicCalibration::fit(&points);
+        assert!(logarithmic.is_some_and(|c| (c.map_f64(50.) - logarithm(50.)).abs() < 1e-12));
+        assert_eq!(None, LogarithmicCalibration::fit(&[(0., 1.), (1., 2.)]));
+    }
+    #[test]
+    fn test_best_fit() {
+        let line = [(0., 1.), (1., 3.1), (2., 4.9), (3., 7.), (4., 9.05)];
+        let best = Calibration::best_fit(&line);
+        assert!(matches!(
+            best,
+            Some(Calibration::Linear(_) | Calibration::Polynomial(_))
+        ));
+        let logarithm = [1., 2., 5., 10., 50., 200.].map(|x| (x, 4. - math::ln(x)));
+        let best = Calibration::best_fit(&logarithm);
+        assert!(matches!(best, Some(Calibration::Logarithmic(_))));
+        assert_eq!(
+            Some(0.),
+            best.and_then(|c| c.residual_error(&logarithm))
+                .map(|e| (e * 1e9).trunc())
+        );
+        assert_eq!(None, Calibration::best_fit(&[(0., 0.), (1., 1.)]));
+    }
+    #[test]
+    fn test_fit_rejects() {
+        assert_eq!(None, LinearCalibration::fit(&[]));
+        assert_eq!(None, LinearCalibration::fit(&[(1., 1.)]));
+        assert_eq!(None, LinearCalibration::fit(&[(1., 1.), (1., 2.)]));
+        assert_eq!(None, LinearCalibration::fit(&[(1., 1.), (f64::NAN, 2.)]));
+        let calibration = LinearCalibration::two_point((0., 0.), (1., 1000.));
+        assert_eq!(Some(None), calibration.map(|c| c.map::<u8, u8>(1)));
+    }
+    #[test]
+    fn test_serialization() {
+        assert_eq!(0x29b1, crc16(b"123456789"));
+        let points = [(0., 1.), (1., 2.5), (2., 7.), (3., 9.5), (4., 17.)];
+        let calibrations = [
+            LinearCalibration::fit(&points).map(Calibration::Linear),
+            PolynomialCalibration::fit(&points, 3).map(Calibration::Polynomial),
+            ExponentialCalibration::fit(&points).map(Calibration::Exponential),
+            LogarithmicCalibration::fit(&points[1..]).map(Calibration::Logarithmic),
+        ];
+        for calibration in calibrations.into_iter().flatten() {
+            let bytes = calibration.to_bytes();
+            assert_eq!(Ok(calibration), Calibration::from_bytes(&bytes));
+            let mut corrupted = bytes;
+            corrupted[10] ^= 1;
+            let error = Calibration::from_bytes(&corrupted);
+            assert_eq!(Err(CalibrationDecodeError::Checksum), error);
+        }
+        let blank = [0xff; SERIALIZED_CALIBRATION_LEN];
+        let error = Calibration::from_bytes(&blank);
+        assert_eq!(Err(CalibrationDecodeError::Checksum), error);
+    }
+    #[test]
+    fn test_deserialization_rejects() {
+        let with_checksum = |mut bytes: [u8; SERIALIZED_CALIBRATION_LEN]| {
+            let checksum = crc16(&bytes[..SERIALIZED_CALIBRATION_LEN - 2]).to_le_bytes();
+            bytes[SERIALIZED_CALIBRATION_LEN - 2..].copy_from_slice(&checksum);
+            Calibration::from_bytes(&bytes)
+        };
+        let mut bytes = [0; SERIALIZED_CALIBRATION_LEN];
+        bytes[0] = 2;
+        assert_eq!(
+            Err(CalibrationDecodeError::Version(2)),
+            with_checksum(bytes)
+        );
+        bytes[0] = CALIBRATION_FORMAT_VERSION;
+        bytes[1] = 4;
+        assert_eq!(Err(CalibrationDecodeError::Invalid), with_checksum(bytes));
+        // A polynomial with a scale of zero.
+        bytes[1] = 1;
+        bytes[2] = 2;
+        assert_eq!(Err(CalibrationDecodeError::Invalid), with_checksum(bytes));
+        bytes[1] = 0;
+        bytes[3..11].copy_from_slice(&f64::NAN.to_le_bytes());
+        assert_eq!(Err(CalibrationDecodeError::Invalid), with_checksum(bytes));
+        // Only polynomials have a degree.
+        bytes[2] = 0;
+        bytes[3..11].copy_from_slice(&1_f64.to_le_bytes());
+        assert!(with_checksum(bytes).is_ok());
+        for model in [0, 2, 3] {
+            bytes[1] = model;
+            bytes[2] = 1;
+            assert_eq!(Err(CalibrationDecodeError::Invalid), with_checksum(bytes));
+        }
+    }
+    #[test]
+    fn test_deserialization_rejects_unused_parameters() {
+        let points = [(0., 1.), (1., 2.5), (2., 7.), (3., 9.5), (4., 17.)];
+        let calibrations = [
+            (
+                LinearCalibration::fit(&points).map(Calibration::Linear),
+                2..6,
+            ),
+            (
+                PolynomialCalibration::fit(&points, 1).map(Calibration::Polynomial),
+                2..4,
+            ),
+            (
+                PolynomialCalibration::fit(&points, 2).map(Calibration::Polynomial),
+                3..4,
+            ),
+        ];
+        for (calibration, unused) in calibrations {
+            let bytes = calibration.map(|c| c.to_bytes());
+            for slot in unused {
+                for offset in [0, 7] {
+                    let mut changed = bytes.unwrap_or([0; SERIALIZED_CALIBRATION_LEN]);
+                    if let Some(byte) = changed.get_mut(3 + 8 * slot + offset) {
+                        *byte ^= 0x80;
+                    }
+                    let checksum = crc16(&changed[..SERIALIZED_CALIBRATION_LEN - 2]);
+                    changed[SERIALIZED_CALIBRATION_LEN - 2..]
+                        .copy_from_slice(&checksum.to_le_bytes());
+                    assert_eq!(
+                        Err(CalibrationDecodeError::Invalid),
+                        Calibration::from_bytes(&changed)
+                    );
+                }
+            }
+        }
+    }
+    #[test]
+    fn test_piecewise_serialization() {
+        const LEN: usize = PiecewiseLinear::<4>::SERIALIZED_LEN;
+        let with_checksum = |mut bytes: [u8; LEN]| {
+            let checksum = crc16(&bytes[..LEN - 2]).to_le_bytes();
+            bytes[LEN - 2..].copy_from_slice(&checksum);
+            PiecewiseLinear::<4>::from_bytes(&bytes)
+        };
+        assert_eq!(70, LEN);
+        for table in [
+            &[(0., 0.), (1., 10.), (2., 15.)][..],
+            &[(-1.5, 3.), (0., -2.), (1e9, 7.25), (2e9, 0.)],
+        ] {
+            let piecewise = PiecewiseLinear::<4>::new(table);
+            let bytes = piecewise.map(|p| p.to_bytes::<LEN>());
+            assert_eq!(
+                piecewise,
+                bytes.and_then(|b| PiecewiseLinear::from_bytes(&b).ok())
+            );
+        }
+        let mut bytes = PiecewiseLinear::<4>::new(&[(0., 0.), (1., 10.)])
+            .map_or([0; LEN], |p| p.to_bytes::<LEN>());
+        assert_eq!([1, 4, 2, 0], bytes[..4]);
+        assert!(bytes[36..LEN - 2].iter().all(|byte| *byte == 0));
+        bytes[20] ^= 1;
+        assert_eq!(
+            Err(CalibrationDecodeError::Checksum),
+            PiecewiseLinear::<4>::from_bytes(&bytes)
+        );
+        assert!(with_checksum(bytes).is_ok());
+        bytes[40] = 1;
+        assert_eq!(Err(CalibrationDecodeError::Invalid), with_checksum(bytes));
+        bytes[40] = 0;
+        bytes[0] = 2;
+        assert_eq!(
+            Err(CalibrationDecodeError::Version(2)),
+            with_checksum(bytes)
+        );
+        bytes[0] = CALIBRATION_FORMAT_VERSION;
+        bytes[1] = 0;
+        assert_eq!(Err(CalibrationDecodeError::Invalid), with_checksum(bytes));
+        bytes[1] = 4;
+        bytes[2] = 5;
+        assert_eq!(Err(CalibrationDecodeError::Invalid), with_checksum(bytes));
+        // Three points, the last one repeating the input of the unused zeroed slot.
+        bytes[2] = 3;
+        assert_eq!(Err(CalibrationDecodeError::Invalid), with_checksum(bytes));
+        bytes[2] = 2;
+        bytes[4..12].copy_from_slice(&f64::NAN.to_le_bytes());
+        assert_eq!(Err(CalibrationDecodeError::Invalid), with_checksum(bytes));
+    }
+    #[test]
+    fn test_from_points() {
+        let falling = Calibration::from_points::<3>(&[(2., 0.), (0., 10.), (1., 4.), (1., 4.)]);
+        assert_eq!(Some(Some(2.)), falling.map(|t| t.map_f64(1.5)));
+        assert_eq!(
+            None,
+            Calibration::from_points::<2>(&[(2., 0.), (0., 10.), (1., 4.)])
+        );
+        assert_eq!(None, Calibration::from_points::<4>(&[(1., 1.), (1., 2.)]));
+        assert_eq!(None, Calibration::from_points::<4>(&[(0., 1.), (1., 1.)]));
+        assert_eq!(
+            None,
+            Calibration::from_points::<4>(&[(0., 1.), (f64::NAN, 2.)])
+        );
+        assert_eq!(None, Calibration::from_points::<4>(&[]));
+    }
+}
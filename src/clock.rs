@@ -0,0 +1,148 @@
+//! A minimal clock abstraction for driving time based types from a platform timer.
+//!
+//! Every time based type, like [`Tween`](crate::tween::Tween), takes the elapsed time in seconds.
+//! A [`Ticker`] turns any [`Clock`] into those elapsed times, so the same animation code runs
+//! from an ISR with a hardware timer, an RTOS task, or `std`.
+
+/// A monotonic tick counter, like a hardware timer or a system tick.
+pub trait Clock {
+    /// The number of ticks per second.
+    fn frequency(&self) -> u32;
+    /// The current tick count. It may wrap around, as long as it does so at `u64::MAX`.
+    fn now(&self) -> u64;
+}
+
+impl<C: Clock> Clock for &C {
+    fn frequency(&self) -> u32 {
+        (*self).frequency()
+    }
+    fn now(&self) -> u64 {
+        (*self).now()
+    }
+}
+
+/// A [`Clock`] counting nanoseconds with `std::time::Instant`, since the clock was created.
+#[cfg(feature = "std")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct StdClock {
+    epoch: std::time::Instant,
+}
+
+#[cfg(feature = "std")]
+impl StdClock {
+    /// A clock starting at zero now.
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            epoch: std::time::Instant::now(),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl Default for StdClock {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(feature = "std")]
+impl Clock for StdClock {
+    fn frequency(&self) -> u32 {
+        1_000_000_000
+    }
+    fn now(&self) -> u64 {
+        self.epoch.elapsed().as_nanos() as u64
+    }
+}
+
+/// Measures the time between calls to [`Ticker::tick`] on a [`Clock`].
+///
+/// ```
+/// use core::cell::Cell;
+/// use map_to_range::clock::{Clock, Ticker};
+///
+/// struct Millis(Cell<u64>);
+/// impl Clock for Millis {
+///     fn frequency(&self) -> u32 { 1000 }
+///     fn now(&self) -> u64 { self.0.get() }
+/// }
+///
+/// let millis = Millis(Cell::new(0));
+/// let mut ticker = Ticker::new(&millis);
+/// millis.0.set(250);
+/// assert_eq!(0.25, ticker.tick());
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Ticker<C> {
+    clock: C,
+    last: u64,
+}
+
+impl<C: Clock> Ticker<C> {
+    /// Starts measuring from the current time of `clock`.
+    pub fn new(clock: C) -> Self {
+        let last = clock.now();
+        Self { clock, last }
+    }
+    /// The seconds since the last tick, or since creating the ticker.
+    ///
+    /// Returns `0` for a clock with a frequency of zero.
+    pub fn tick(&mut self) -> f64 {
+        let now = self.clock.now();
+        let ticks = now.wrapping_sub(self.last);
+        self.last = now;
+        match self.clock.frequency() {
+            0 => 0.,
+            frequency => ticks as f64 / frequency as f64,
+        }
+    }
+    /// The clock this ticker reads.
+    pub fn clock(&self) -> &C {
+        &self.clock
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use core::cell::Cell;
+
+    struct TestClock {
+        frequency: u32,
+        ticks: Cell<u64>,
+    }
+
+    impl Clock for TestClock {
+        fn frequency(&self) -> u32 {
+            self.frequency
+        }
+        fn now(&self) -> u64 {
+            self.ticks.get()
+        }
+    }
+
+    #[test]
+    fn test_ticker() {
+        let clock = TestClock {
+            frequency: 4,
+            ticks: Cell::new(u64::MAX - 1),
+        };
+        let mut ticker = Ticker::new(&clock);
+        clock.ticks.set(2);
+        assert_eq!(Some(1.), Some(ticker.tick()));
+        assert_eq!(Some(0.), Some(ticker.tick()));
+        let stopped = TestClock {
+            frequency: 0,
+            ticks: Cell::new(0),
+        };
+        assert_eq!(Some(0.), Some(Ticker::new(stopped).tick()));
+    }
+    #[test]
+    #[cfg(feature = "std")]
+    fn test_std_clock() {
+        let mut ticker = Ticker::new(StdClock::new());
+        std::thread::sleep(std::time::Duration::from_millis(2));
+        assert!(ticker.tick() >= 0.002);
+    }
+}
@@ -0,0 +1,310 @@
+//! Integer mappings that can be evaluated at compile time.
+//!
+//! Unlike [`MapRange`](crate::MapRange), these never go through `f64`, so they are `const fn`
+//! and can fill lookup tables and thresholds at compile time.
+//!
+//! ```
+//! use map_to_range::const_map::map_u16;
+//!
+//! const fn table() -> [u16; 5] {
+//!     let mut table = [0; 5];
+//!     let mut i = 0;
+//!     while i < table.len() {
+//!         if let Some(value) = map_u16(i as u16, (0, 4), (1000, 2000)) {
+//!             table[i] = value;
+//!         }
+//!         i += 1;
+//!     }
+//!     table
+//! }
+//! const SERVO_STEPS: [u16; 5] = table();
+//! assert_eq!([1000, 1250, 1500, 1750, 2000], SERVO_STEPS);
+//! ```
+
+/// Maps an integer at compile time, like [`MapRange::map_range`](crate::MapRange::map_range).
+///
+/// The integer type comes first, and the value and both ranges must be of that type.
+/// To map into another type, name both as `from => to`: the value and the `from` range are of
+/// the first, the target range and the result of the second. The arithmetic happens on
+/// integers, so the result matches `map_range`, truncating towards zero.
+/// When used in a `const`, a value outside of the `from` range or an empty `from` range
+/// is a compile error.
+///
+/// ```
+/// use map_to_range::map_range;
+///
+/// const HALF: u8 = map_range!(u8: 128, (0, 255), (0, 100));
+/// assert_eq!(50, HALF);
+/// const CENTER: i16 = map_range!(i16: 0, (-100, 100), (-1000, 1000));
+/// assert_eq!(0, CENTER);
+/// const MID: u8 = map_range!(u16 => u8: 512, (0, 1023), (0, 255));
+/// assert_eq!(127, MID);
+/// ```
+///
+/// ```compile_fail
+/// use map_to_range::map_range;
+///
+/// const BROKEN: u16 = map_range!(u16: 2000, (0, 1023), (0, 255));
+/// ```
+///
+/// A target range that does not fit into its type, a value that is not an integer,
+/// or a type that is not an integer does not compile either:
+///
+/// ```compile_fail
+/// use map_to_range::map_range;
+///
+/// const WRAPPED: u8 = map_range!(u16 => u8: 200, (0, 255), (0, 300));
+/// ```
+///
+/// ```compile_fail
+/// use map_to_range::map_range;
+///
+/// const TRUNCATED: u8 = map_range!(u8: 2.9, (0, 4), (0, 100));
+/// ```
+///
+/// ```compile_fail
+/// use map_to_range::map_range;
+///
+/// const FLOAT: u8 = map_range!(f64 => u8: 0.5, (0., 1.), (0, 100));
+/// ```
+#[macro_export]
+macro_rules! map_range {
+    (@integer u8) => {};
+    (@integer u16) => {};
+    (@integer u32) => {};
+    (@integer u64) => {};
+    (@integer usize) => {};
+    (@integer i8) => {};
+    (@integer i16) => {};
+    (@integer i32) => {};
+    (@integer i64) => {};
+    (@integer isize) => {};
+    ($from:ident => $to:ident: $value:expr, ($from_lo:expr, $from_hi:expr), ($to_lo:expr, $to_hi:expr) $(,)?) => {{
+        $crate::map_range!(@integer $from);
+        $crate::map_range!(@integer $to);
+        // The typed bindings reject values that are not of the named types, before casting.
+        let value: $from = $value;
+        let from_range: ($from, $from) = ($from_lo, $from_hi);
+        let to_range: ($to, $to) = ($to_lo, $to_hi);
+        match $crate::const_map::map_i128(
+            value as i128,
+            (from_range.0 as i128, from_range.1 as i128),
+            (to_range.0 as i128, to_range.1 as i128),
+        ) {
+            // The result lies between the ends of `to_range`, so it always fits.
+            ::core::option::Option::Some(result) => result as $to,
+            ::core::option::Option::None => ::core::panic!(
+                "map_range!: the value is outside of the range it is mapped from, or that range is empty"
+            ),
+        }
+    }};
+    ($type:ident: $($rest:tt)*) => { $crate::map_range!($type => $type: $($rest)*) };
+}
+
+/// The integer mapping behind the `const fn`s of this module and [`map_range!`].
+#[doc(hidden)]
+#[must_use]
+pub const fn map_i128(
+    value: i128,
+    from_range: (i128, i128),
+    to_range: (i128, i128),
+) -> Option<i128> {
+    if value < from_range.0 || value > from_range.1 || from_range.0 == from_range.1 {
+        return None;
+    }
+    let diff_from = from_range.1 - from_range.0;
+    let Some(diff_to) = to_range.1.checked_sub(to_range.0) else {
+        return None;
+    };
+    let Some(scaled) = (value - from_range.0).checked_mul(diff_to) else {
+        return None;
+    };
+    let Some(result) = to_range.0.checked_add(scaled.div_euclid(diff_from)) else {
+        return None;
+    };
+    // Rounding down and correcting negative results truncates towards zero,
+    // like casting the f64 result of `map_range` does.
+    if result < 0 && scaled.rem_euclid(diff_from) != 0 {
+        Some(result + 1)
+    } else {
+        Some(result)
+    }
+}
+
+macro_rules! const_map_fns {
+    ($($name:ident: $t:ty),* $(,)?) => {
+        $(
+            #[doc = concat!("Maps a `", stringify!($t), "` like [`MapRange::map_range`](crate::MapRange::map_range), as a `const fn`.")]
+            ///
+            /// Returns `None` if the value is outside of `from_range`, `from_range` is empty,
+            /// or an intermediate step overflows.
+            #[must_use]
+            pub const fn $name(value: $t, from_range: ($t, $t), to_range: ($t, $t)) -> Option<$t> {
+                match map_i128(
+                    value as i128,
+                    (from_range.0 as i128, from_range.1 as i128),
+                    (to_range.0 as i128, to_range.1 as i128),
+                ) {
+                    // The result lies between the ends of `to_range`, so it always fits.
+                    Some(result) => Some(result as $t),
+                    None => None,
+                }
+            }
+        )*
+    };
+}
+
+const_map_fns!(
+    map_u8: u8,
+    map_u16: u16,
+    map_u32: u32,
+    map_u64: u64,
+    map_usize: usize,
+    map_i8: i8,
+    map_i16: i16,
+    map_i32: i32,
+    map_i64: i64,
+    map_isize: isize,
+);
+
+/// A mapping between ranges that are fixed at compile time.
+///
+/// The ranges are checked when the mapper is created, so a reversed or empty `from` range,
+/// or ranges too wide to map without overflowing, are a compile error instead of a `None`.
+///
+/// ```
+/// use map_to_range::const_map::StaticMapper;
+///
+/// const ADC_TO_DMX: StaticMapper<0, 4095, 0, 255> = StaticMapper::new();
+/// assert_eq!(Some(127), ADC_TO_DMX.map(2048));
+/// assert_eq!(None, ADC_TO_DMX.map(5000));
+/// assert_eq!(255, ADC_TO_DMX.map_clamped(5000));
+/// ```
+///
+/// ```compile_fail
+/// use map_to_range::const_map::StaticMapper;
+///
+/// let reversed = StaticMapper::<4095, 0, 0, 255>::new();
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct StaticMapper<const FROM_LO: i64, const FROM_HI: i64, const TO_LO: i64, const TO_HI: i64>(
+    (),
+);
+
+impl<const FROM_LO: i64, const FROM_HI: i64, const TO_LO: i64, const TO_HI: i64> Default
+    for StaticMapper<FROM_LO, FROM_HI, TO_LO, TO_HI>
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<const FROM_LO: i64, const FROM_HI: i64, const TO_LO: i64, const TO_HI: i64>
+    StaticMapper<FROM_LO, FROM_HI, TO_LO, TO_HI>
+{
+    const VALID: () = {
+        assert!(
+            FROM_LO < FROM_HI,
+            "the from range must be ascending and not empty"
+        );
+        // The widest intermediate product appears when mapping FROM_HI.
+        assert!(
+            (FROM_HI as i128 - FROM_LO as i128)
+                .checked_mul(TO_HI as i128 - TO_LO as i128)
+                .is_some(),
+            "the ranges are too wide to map without overflowing"
+        );
+    };
+    /// Creates the mapper, checking the ranges at compile time.
+    #[must_use]
+    pub const fn new() -> Self {
+        let () = Self::VALID;
+        Self(())
+    }
+    /// Maps `value` from `FROM_LO..=FROM_HI` to `TO_LO..=TO_HI`, truncating towards zero.
+    ///
+    /// Returns `None` only if `value` is outside of the `from` range.
+    #[must_use]
+    pub const fn map(self, value: i64) -> Option<i64> {
+        match map_i128(
+            value as i128,
+            (FROM_LO as i128, FROM_HI as i128),
+            (TO_LO as i128, TO_HI as i128),
+        ) {
+            Some(result) => Some(result as i64),
+            None => None,
+        }
+    }
+    /// Maps `value` like [`StaticMapper::map`], but clamps it into the `from` range first.
+    #[must_use]
+    pub const fn map_clamped(self, value: i64) -> i64 {
+        let value = if value < FROM_LO {
+            FROM_LO
+        } else if value > FROM_HI {
+            FROM_HI
+        } else {
+            value
+        };
+        match self.map(value) {
+            Some(result) => result,
+            // The ranges are validated and the value is clamped, so this is never reached.
+            None => TO_LO,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::MapRange;
+
+    #[test]
+    fn test_matches_map_range() {
+        for value in -100_i32..=100 {
+            let expected = value.map_range((-100, 100), (-10, 10));
+            assert_eq!(
+                expected.map(i128::from),
+                map_i128(value.into(), (-100, 100), (-10, 10))
+            );
+        }
+    }
+    #[test]
+    fn test_typed() {
+        const HALF: Option<u8> = map_u8(128, (0, 255), (0, 100));
+        assert_eq!(Some(50), HALF);
+        assert_eq!(Some(u64::MAX), map_u64(1, (0, 1), (0, u64::MAX)));
+        assert_eq!(Some(i64::MIN), map_i64(0, (0, 1), (i64::MIN, i64::MAX)));
+        assert_eq!(Some(-5), map_isize(15, (10, 20), (0, -10)));
+        assert_eq!(
+            5_u32.map_range((0, 7), (3, 1000)),
+            map_u32(5, (0, 7), (3, 1000))
+        );
+    }
+    #[test]
+    fn test_static_mapper() {
+        let mapper = StaticMapper::<-100, 100, 1000, 0>::new();
+        assert_eq!(Some(500), mapper.map(0));
+        assert_eq!(Some(0), mapper.map(100));
+        assert_eq!(None, mapper.map(-101));
+        assert_eq!(1000, mapper.map_clamped(i64::MIN));
+        let full = StaticMapper::<{ i64::MIN }, { i64::MAX }, 0, 1>::default();
+        assert_eq!(Some(1), full.map(i64::MAX));
+    }
+    #[test]
+    fn test_rejects() {
+        assert_eq!(None, map_i128(11, (0, 10), (0, 1)));
+        assert_eq!(None, map_i128(5, (5, 5), (0, 1)));
+        assert_eq!(None, map_i128(2, (0, 2), (0, i128::MAX)));
+        assert_eq!(None, map_u8(11, (0, 10), (0, 255)));
+        let degrees = map_range!(i16: 3, (0, 4), (0, 360));
+        assert_eq!(270, degrees);
+    }
+    #[test]
+    fn test_macro_between_types() {
+        const DIMMED: u8 = map_range!(u16 => u8: 512, (0, 1023), (0, 255));
+        const CELSIUS: i8 = map_range!(u32 => i8: 0, (0, 4095), (-40, 125));
+        assert_eq!(127, DIMMED);
+        assert_eq!(-40, CELSIUS);
+        assert_eq!(-9, map_range!(u64 => i64: 1, (0, 2), (0, -19)));
+    }
+}
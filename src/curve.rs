@@ -0,0 +1,380 @@
+//! A common interface for everything that can be evaluated along a parameter,
+//! and utilities built on top of it.
+
+use crate::{integrate, math, polynomial::NewtonPolynomial, MapRange};
+
+/// Something that produces a value for every parameter inside its domain.
+pub trait Curve {
+    /// The type of the evaluated values.
+    type Output;
+    /// The range of parameters this curve is defined over.
+    fn domain(&self) -> (f64, f64);
+    /// Evaluates the curve at the parameter `t`.
+    ///
+    /// Returns `None` if the curve cannot be evaluated there.
+    fn evaluate(&self, t: f64) -> Option<Self::Output>;
+    /// Evaluates the curve at `count` evenly spaced parameters, from the start to the end of
+    /// its domain.
+    ///
+    /// ```
+    /// use map_to_range::curve::Curve;
+    /// use map_to_range::polynomial::NewtonPolynomial;
+    ///
+    /// let line = NewtonPolynomial::<2>::from_points(&[(0., 0.), (1., 10.)]).unwrap();
+    /// let mut samples = line.samples(3);
+    /// assert_eq!(Some(Some(0.)), samples.next());
+    /// assert_eq!(Some(Some(5.)), samples.next());
+    /// assert_eq!(Some(Some(10.)), samples.next());
+    /// assert_eq!(None, samples.next());
+    /// ```
+    fn samples(&self, count: usize) -> Samples<'_, Self>
+    where
+        Self: Sized,
+    {
+        Samples {
+            curve: self,
+            index: 0,
+            count,
+        }
+    }
+    /// Fills `buffer` with evenly spaced samples of the curve, from the start to the end of its
+    /// domain. This is the allocation free counterpart of [`Curve::samples`].
+    ///
+    /// Returns `None` as soon as the curve cannot be evaluated at a parameter,
+    /// leaving the rest of the buffer untouched.
+    ///
+    /// ```
+    /// use map_to_range::curve::Curve;
+    /// use map_to_range::polynomial::NewtonPolynomial;
+    ///
+    /// let line = NewtonPolynomial::<2>::from_points(&[(0., 0.), (1., 10.)]).unwrap();
+    /// let mut buffer = [0.; 5];
+    /// assert_eq!(Some(()), line.sample_into(&mut buffer));
+    /// assert_eq!([0., 2.5, 5., 7.5, 10.], buffer);
+    /// ```
+    fn sample_into(&self, buffer: &mut [Self::Output]) -> Option<()>
+    where
+        Self: Sized,
+    {
+        let count = buffer.len();
+        for (index, slot) in buffer.iter_mut().enumerate() {
+            *slot = self.evaluate(sample_parameter(self.domain(), index, count)?)?;
+        }
+        Some(())
+    }
+    /// The rate of change of the curve at the parameter `t`.
+    ///
+    /// This is approximated with a central difference that stays inside the domain.
+    /// Curves that know their exact derivative override this.
+    ///
+    /// Returns `None` if the domain has no width or the curve cannot be evaluated around `t`.
+    ///
+    /// ```
+    /// use map_to_range::curve::Curve;
+    /// use map_to_range::polynomial::NewtonPolynomial;
+    ///
+    /// let line = NewtonPolynomial::<2>::from_points(&[(0., 0.), (2., 10.)]).unwrap();
+    /// assert_eq!(Some(5.), line.derivative_at(1.));
+    /// ```
+    fn derivative_at(&self, t: f64) -> Option<f64>
+    where
+        Self::Output: Into<f64>,
+    {
+        let (start, end) = self.domain();
+        let step = (end - start).abs() * DERIVATIVE_STEP;
+        let before = (t - step).max(start.min(end));
+        let after = (t + step).min(start.max(end));
+        if after - before <= 0. {
+            return None;
+        }
+        let rise = self.evaluate(after)?.into() - self.evaluate(before)?.into();
+        Some(rise / (after - before))
+    }
+    /// The area under the curve over the parameter `range`, approximated with `steps`
+    /// trapezoids. See [`integrate::trapezoidal`].
+    ///
+    /// ```
+    /// use map_to_range::curve::Curve;
+    /// use map_to_range::polynomial::NewtonPolynomial;
+    ///
+    /// let line = NewtonPolynomial::<2>::from_points(&[(0., 0.), (2., 10.)]).unwrap();
+    /// assert_eq!(Some(10.), line.integral((0., 2.), 8));
+    /// ```
+    fn integral(&self, range: (f64, f64), steps: usize) -> Option<f64>
+    where
+        Self::Output: Into<f64>,
+    {
+        integrate::trapezoidal(|t| self.evaluate(t).map(Into::into), range, steps)
+    }
+}
+
+/// Step width of numeric derivatives, relative to the width of the domain.
+const DERIVATIVE_STEP: f64 = 1e-6;
+
+/// Iterator over evenly spaced samples of a curve, created by [`Curve::samples`].
+///
+/// Each item is `None` if the curve could not be evaluated at that parameter.
+#[derive(Debug, Clone)]
+pub struct Samples<'a, C> {
+    curve: &'a C,
+    index: usize,
+    count: usize,
+}
+
+impl<C: Curve> Iterator for Samples<'_, C> {
+    type Item = Option<C::Output>;
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.index >= self.count {
+            return None;
+        }
+        let t = sample_parameter(self.curve.domain(), self.index, self.count);
+        self.index += 1;
+        Some(t.and_then(|t| self.curve.evaluate(t)))
+    }
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = self.count.saturating_sub(self.index);
+        (remaining, Some(remaining))
+    }
+}
+impl<C: Curve> ExactSizeIterator for Samples<'_, C> {}
+
+/// The parameter of the `index`th of `count` evenly spaced samples over `domain`.
+fn sample_parameter(domain: (f64, f64), index: usize, count: usize) -> Option<f64> {
+    if count < 2 {
+        return Some(domain.0);
+    }
+    (index as f64).map_range((0., (count - 1) as f64), domain)
+}
+
+/// Distance between two values of a curve, used to measure its length.
+pub trait Distance {
+    /// The distance between `self` and `other`. This must never be negative.
+    fn distance(&self, other: &Self) -> f64;
+}
+
+impl Distance for f32 {
+    fn distance(&self, other: &Self) -> f64 {
+        (*self as f64 - *other as f64).abs()
+    }
+}
+impl Distance for f64 {
+    fn distance(&self, other: &Self) -> f64 {
+        (self - other).abs()
+    }
+}
+impl<const D: usize> Distance for [f32; D] {
+    fn distance(&self, other: &Self) -> f64 {
+        let squared: f64 = self
+            .iter()
+            .zip(other)
+            .map(|(a, b)| (*a as f64 - *b as f64) * (*a as f64 - *b as f64))
+            .sum();
+        math::sqrt(squared)
+    }
+}
+impl<const D: usize> Distance for [f64; D] {
+    fn distance(&self, other: &Self) -> f64 {
+        let squared: f64 = self.iter().zip(other).map(|(a, b)| (a - b) * (a - b)).sum();
+        math::sqrt(squared)
+    }
+}
+
+/// Reparameterizes a curve by its arc length, so it can be traversed at constant speed.
+///
+/// The length is approximated by evaluating the curve at `N` evenly spaced parameters
+/// and summing up the distances between them. A bigger `N` gives a more precise result.
+///
+/// The reparameterized curve has the domain `(0, length)`.
+///
+/// ```
+/// use map_to_range::curve::{ArcLength, Curve};
+/// use map_to_range::polynomial::NewtonPolynomial;
+///
+/// let line = NewtonPolynomial::<2>::from_points(&[(0., 0.), (10., 5.)]).unwrap();
+/// let by_length = ArcLength::<_, 16>::new(line).unwrap();
+/// assert_eq!(5., by_length.length());
+/// assert_eq!(Some(2.5), by_length.evaluate(2.5));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ArcLength<C, const N: usize> {
+    curve: C,
+    /// Accumulated length up to each of the evenly spaced parameters.
+    table: [f64; N],
+}
+
+impl<C: Curve, const N: usize> ArcLength<C, N>
+where
+    C::Output: Distance,
+{
+    /// Precomputes the length table of `curve`.
+    ///
+    /// Returns `None` if `N` is smaller than 2 or the curve cannot be evaluated inside its domain.
+    pub fn new(curve: C) -> Option<Self> {
+        if N < 2 {
+            return None;
+        }
+        let mut table = [0.; N];
+        let mut previous = curve.evaluate(sample_parameter(curve.domain(), 0, N)?)?;
+        let mut length = 0.;
+        for (i, entry) in table.iter_mut().enumerate().skip(1) {
+            let current = curve.evaluate(sample_parameter(curve.domain(), i, N)?)?;
+            length += previous.distance(&current);
+            *entry = length;
+            previous = current;
+        }
+        Some(Self { curve, table })
+    }
+    /// The total length of the curve.
+    pub fn length(&self) -> f64 {
+        self.table.last().copied().unwrap_or_default()
+    }
+    /// The parameter of the underlying curve at which `distance` has been travelled.
+    ///
+    /// Returns `None` if `distance` is not inside `0..=length`.
+    pub fn parameter_at(&self, distance: f64) -> Option<f64> {
+        if !(0. ..=self.length()).contains(&distance) {
+            return None;
+        }
+        let segment = self
+            .table
+            .partition_point(|length| *length < distance)
+            .max(1);
+        let from = (*self.table.get(segment - 1)?, *self.table.get(segment)?);
+        let domain = self.curve.domain();
+        let to = (
+            sample_parameter(domain, segment - 1, N)?,
+            sample_parameter(domain, segment, N)?,
+        );
+        if from.1 - from.0 <= 0. {
+            return Some(to.0);
+        }
+        distance.map_range(from, to)
+    }
+    /// Evaluates the underlying curve after travelling `distance` along it.
+    pub fn evaluate_at(&self, distance: f64) -> Option<C::Output> {
+        self.curve.evaluate(self.parameter_at(distance)?)
+    }
+    /// The curve that is being reparameterized.
+    pub fn curve(&self) -> &C {
+        &self.curve
+    }
+}
+
+impl<C: Curve, const N: usize> Curve for ArcLength<C, N>
+where
+    C::Output: Distance,
+{
+    type Output = C::Output;
+    fn domain(&self) -> (f64, f64) {
+        (0., self.length())
+    }
+    fn evaluate(&self, t: f64) -> Option<Self::Output> {
+        self.evaluate_at(t)
+    }
+}
+
+impl<const N: usize> Curve for NewtonPolynomial<N> {
+    type Output = f64;
+    /// The span between the smallest and largest x value of the points.
+    fn domain(&self) -> (f64, f64) {
+        let smallest = self.xs().iter().copied().reduce(f64::min);
+        let largest = self.xs().iter().copied().reduce(f64::max);
+        (smallest.unwrap_or_default(), largest.unwrap_or_default())
+    }
+    fn evaluate(&self, t: f64) -> Option<Self::Output> {
+        NewtonPolynomial::evaluate(self, t)
+    }
+    fn derivative_at(&self, t: f64) -> Option<f64> {
+        self.derivative(t)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A quarter of the unit circle.
+    #[derive(Clone, Copy)]
+    struct QuarterCircle;
+    impl Curve for QuarterCircle {
+        type Output = [f64; 2];
+        fn domain(&self) -> (f64, f64) {
+            (0., 1.)
+        }
+        fn evaluate(&self, t: f64) -> Option<Self::Output> {
+            Some([1. - t, math::sqrt(1. - (1. - t) * (1. - t))])
+        }
+    }
+
+    /// `t²` over `(0, 1)`.
+    struct Square;
+    impl Curve for Square {
+        type Output = f64;
+        fn domain(&self) -> (f64, f64) {
+            (0., 1.)
+        }
+        fn evaluate(&self, t: f64) -> Option<Self::Output> {
+            Some(t * t)
+        }
+    }
+
+    #[test]
+    fn test_distance() {
+        assert_eq!(Some(5.), Some([0., 0.].distance(&[3., 4.])));
+        assert_eq!(Some(5.), Some([0_f32, 0.].distance(&[3., 4.])));
+        assert_eq!(Some(2.), Some((-1_f64).distance(&1.)));
+    }
+    #[test]
+    fn test_arc_length_of_circle() {
+        let by_length = ArcLength::<_, 4096>::new(QuarterCircle);
+        let length = by_length.map(|curve| curve.length());
+        assert!(length.is_some_and(|length| (length - core::f64::consts::FRAC_PI_2).abs() < 1e-3));
+        // Halfway along the arc is at 45 degrees.
+        let halfway = by_length.and_then(|curve| curve.evaluate_at(curve.length() / 2.));
+        assert!(halfway.is_some_and(|[x, y]| (x - y).abs() < 1e-3));
+    }
+    #[test]
+    fn test_samples() {
+        let samples = QuarterCircle.samples(5);
+        assert_eq!(5, samples.len());
+        assert_eq!(Some(Some([1., 0.])), samples.clone().next());
+        assert_eq!(Some(Some([0., 1.])), samples.last());
+        assert_eq!(Some(Some([1., 0.])), QuarterCircle.samples(1).next());
+        assert_eq!(None, QuarterCircle.samples(0).next());
+    }
+    #[test]
+    fn test_sample_into() {
+        let mut buffer = [[0.; 2]; 3];
+        assert_eq!(Some(()), QuarterCircle.sample_into(&mut buffer));
+        assert_eq!(Some(&[1., 0.]), buffer.first());
+        assert_eq!(Some(&[0., 1.]), buffer.last());
+        assert_eq!(Some(()), QuarterCircle.sample_into(&mut []));
+        let empty = NewtonPolynomial::<2>::new();
+        assert_eq!(None, empty.sample_into(&mut [0.; 2]));
+    }
+    #[test]
+    fn test_derivative() {
+        for (t, expected) in [(0., 0.), (0.5, 1.), (1., 2.)] {
+            let derivative = Square.derivative_at(t);
+            assert!(derivative.is_some_and(|d| (d - expected).abs() < 1e-5));
+        }
+        let flat = NewtonPolynomial::<2>::from_points(&[(0., 1.), (4., 1.)]);
+        let without_length = flat.and_then(ArcLength::<_, 8>::new);
+        assert_eq!(None, without_length.and_then(|c| c.derivative_at(0.)));
+    }
+    #[test]
+    fn test_integral() {
+        let area = Square.integral((0., 1.), 1000);
+        assert!(area.is_some_and(|area| (area - 1. / 3.).abs() < 1e-6));
+        assert_eq!(None, NewtonPolynomial::<1>::new().integral((0., 1.), 10));
+    }
+    #[test]
+    fn test_arc_length_bounds() {
+        let line = NewtonPolynomial::<2>::from_points(&[(0., 1.), (4., 1.)]);
+        let by_length = line.and_then(ArcLength::<_, 8>::new);
+        assert_eq!(Some(0.), by_length.map(|curve| curve.length()));
+        assert_eq!(Some(0.), by_length.and_then(|curve| curve.parameter_at(0.)));
+        assert_eq!(None, by_length.and_then(|curve| curve.parameter_at(1.)));
+        assert_eq!(None, line.and_then(ArcLength::<_, 1>::new));
+    }
+}
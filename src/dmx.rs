@@ -0,0 +1,107 @@
+//! Mapping values into 16 bit DMX channel pairs.
+//!
+//! Fine grained attributes like pan and tilt of moving heads use two DMX channels: the coarse
+//! channel carries the high byte and the fine channel carries the low byte.
+
+use crate::MapRange;
+
+/// Maps `value` from `from_range` onto the full 16 bit DMX range `0..=65535`,
+/// rounded to the nearest step.
+///
+/// Returns `None` if `value` is outside of `from_range`.
+///
+/// ```
+/// use map_to_range::dmx;
+///
+/// assert_eq!(Some(32768), dmx::map_to_dmx16(270_u16, (0, 540)));
+/// assert_eq!(Some(65535), dmx::map_to_dmx16(540_u16, (0, 540)));
+/// ```
+#[must_use]
+pub fn map_to_dmx16<T: MapRange>(value: T, from_range: (T, T)) -> Option<u16> {
+    let from_range = (
+        from_range.0.checked_f64_cast()?,
+        from_range.1.checked_f64_cast()?,
+    );
+    let mapped = value
+        .checked_f64_cast()?
+        .map_range(from_range, (0., u16::MAX as f64))?;
+    Some((mapped + 0.5) as u16)
+}
+
+/// Maps a 16 bit DMX value back onto `to_range`.
+///
+/// Returns `None` if the result does not fit into `T`.
+///
+/// ```
+/// use map_to_range::dmx;
+///
+/// assert_eq!(Some(540.), dmx::map_from_dmx16(65535, (0., 540.)));
+/// ```
+#[must_use]
+pub fn map_from_dmx16<T: MapRange>(value: u16, to_range: (T, T)) -> Option<T> {
+    let to_range = (
+        to_range.0.checked_f64_cast()?,
+        to_range.1.checked_f64_cast()?,
+    );
+    let mapped = (value as f64).map_range((0., u16::MAX as f64), to_range)?;
+    T::checked_cast_back(mapped)
+}
+
+/// Splits a 16 bit DMX value into its `(coarse, fine)` channel bytes.
+///
+/// ```
+/// assert_eq!((0x12, 0x34), map_to_range::dmx::split(0x1234));
+/// ```
+#[must_use]
+pub const fn split(value: u16) -> (u8, u8) {
+    let [coarse, fine] = value.to_be_bytes();
+    (coarse, fine)
+}
+
+/// Merges `coarse` and `fine` channel bytes into a 16 bit DMX value.
+///
+/// ```
+/// assert_eq!(0x1234, map_to_range::dmx::merge(0x12, 0x34));
+/// ```
+#[must_use]
+pub const fn merge(coarse: u8, fine: u8) -> u16 {
+    u16::from_be_bytes([coarse, fine])
+}
+
+/// Maps `value` from `from_range` directly into `(coarse, fine)` channel bytes.
+///
+/// Returns `None` if `value` is outside of `from_range`.
+///
+/// ```
+/// use map_to_range::dmx;
+///
+/// assert_eq!(Some((128, 0)), dmx::map_to_channels(0.5_f32, (0., 1.)));
+/// ```
+#[must_use]
+pub fn map_to_channels<T: MapRange>(value: T, from_range: (T, T)) -> Option<(u8, u8)> {
+    map_to_dmx16(value, from_range).map(split)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_map_to_dmx16() {
+        assert_eq!(Some(0), map_to_dmx16(0_u8, (0, 255)));
+        assert_eq!(Some(257), map_to_dmx16(1_u8, (0, 255)));
+        assert_eq!(Some(65535), map_to_dmx16(255_u8, (0, 255)));
+        assert_eq!(Some(16384), map_to_dmx16(-45_i16, (-90, 90)));
+        assert_eq!(None, map_to_dmx16(91_i16, (-90, 90)));
+    }
+    #[test]
+    fn test_channels_round_trip() {
+        for value in [0, 1, 255, 256, 0x7fff, 0xfffe, u16::MAX] {
+            let (coarse, fine) = split(value);
+            assert_eq!(value, merge(coarse, fine));
+        }
+        assert_eq!(Some((255, 255)), map_to_channels(1_f64, (0., 1.)));
+        assert_eq!(Some(-90_i16), map_from_dmx16(0, (-90, 90)));
+        assert_eq!(Some(90_i16), map_from_dmx16(u16::MAX, (-90, 90)));
+    }
+}
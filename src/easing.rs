@@ -0,0 +1,210 @@
+//! Easing functions that shape a normalized progress `t` in `0..=1`.
+
+use crate::curve::Curve;
+
+/// Commonly used easing functions.
+///
+/// Every easing maps `0` to `0` and `1` to `1` and is monotonic in between.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Easing {
+    /// No easing at all.
+    #[default]
+    Linear,
+    /// Accelerates quadratically.
+    QuadIn,
+    /// Decelerates quadratically.
+    QuadOut,
+    /// Accelerates and then decelerates quadratically.
+    QuadInOut,
+    /// Accelerates cubically.
+    CubicIn,
+    /// Decelerates cubically.
+    CubicOut,
+    /// Accelerates and then decelerates cubically.
+    CubicInOut,
+    /// The hermite polynomial `3t² - 2t³`.
+    SmoothStep,
+    /// Ken Perlin's `6t⁵ - 15t⁴ + 10t³`, with a flat first and second derivative at both ends.
+    SmootherStep,
+}
+
+impl Easing {
+    /// Applies the easing to `t`. Values of `t` outside of `0..=1` are clamped.
+    ///
+    /// ```
+    /// use map_to_range::easing::Easing;
+    ///
+    /// assert_eq!(0.25, Easing::QuadIn.apply(0.5));
+    /// assert_eq!(1., Easing::QuadIn.apply(2.));
+    /// ```
+    #[must_use]
+    pub fn apply(self, t: f64) -> f64 {
+        let t = t.clamp(0., 1.);
+        match self {
+            Easing::Linear => t,
+            Easing::QuadIn => t * t,
+            Easing::QuadOut => 1. - (1. - t) * (1. - t),
+            Easing::QuadInOut if t < 0.5 => 2. * t * t,
+            Easing::QuadInOut => 1. - 2. * (1. - t) * (1. - t),
+            Easing::CubicIn => t * t * t,
+            Easing::CubicOut => 1. - (1. - t) * (1. - t) * (1. - t),
+            Easing::CubicInOut if t < 0.5 => 4. * t * t * t,
+            Easing::CubicInOut => 1. - 4. * (1. - t) * (1. - t) * (1. - t),
+            Easing::SmoothStep => t * t * (3. - 2. * t),
+            Easing::SmootherStep => t * t * t * (t * (t * 6. - 15.) + 10.),
+        }
+    }
+    /// The slope of the easing at `t`, the exact derivative of [`Easing::apply`].
+    ///
+    /// [`Easing::apply`] is constant outside of `0..=1`, so the slope there is `0`.
+    ///
+    /// ```
+    /// use map_to_range::easing::Easing;
+    ///
+    /// assert_eq!(1., Easing::QuadIn.derivative(0.5));
+    /// assert_eq!(1.5, Easing::SmoothStep.derivative(0.5));
+    /// assert_eq!(0., Easing::Linear.derivative(2.));
+    /// ```
+    #[must_use]
+    pub fn derivative(self, t: f64) -> f64 {
+        if !(0. ..=1.).contains(&t) {
+            return 0.;
+        }
+        match self {
+            Easing::Linear => 1.,
+            Easing::QuadIn => 2. * t,
+            Easing::QuadOut => 2. * (1. - t),
+            Easing::QuadInOut if t < 0.5 => 4. * t,
+            Easing::QuadInOut => 4. * (1. - t),
+            Easing::CubicIn => 3. * t * t,
+            Easing::CubicOut => 3. * (1. - t) * (1. - t),
+            Easing::CubicInOut if t < 0.5 => 12. * t * t,
+            Easing::CubicInOut => 12. * (1. - t) * (1. - t),
+            Easing::SmoothStep => 6. * t * (1. - t),
+            Easing::SmootherStep => 30. * t * t * (1. - t) * (1. - t),
+        }
+    }
+    /// Finds the `t` for which the easing produces `value`, inverting [`Easing::apply`].
+    ///
+    /// Returns `None` if `value` is not inside `0..=1`.
+    ///
+    /// ```
+    /// use map_to_range::easing::Easing;
+    ///
+    /// let t = Easing::QuadIn.solve_t(0.25).unwrap();
+    /// assert!((t - 0.5).abs() < 1e-12);
+    /// assert_eq!(None, Easing::QuadIn.solve_t(1.5));
+    /// ```
+    #[must_use]
+    pub fn solve_t(self, value: f64) -> Option<f64> {
+        if !(0. ..=1.).contains(&value) {
+            return None;
+        }
+        if value <= 0. || value >= 1. {
+            return Some(value);
+        }
+        // Bisection is robust for every monotonic easing, including the flat ends of the
+        // smooth steps where newton's method would stall.
+        let (mut low, mut high) = (0_f64, 1_f64);
+        for _ in 0..SOLVE_ITERATIONS {
+            let middle = f64::midpoint(low, high);
+            if self.apply(middle) < value {
+                low = middle;
+            } else {
+                high = middle;
+            }
+        }
+        Some(f64::midpoint(low, high))
+    }
+}
+
+/// Bisection halves the interval every iteration, so this reaches full `f64` precision.
+const SOLVE_ITERATIONS: usize = 64;
+
+impl Curve for Easing {
+    type Output = f64;
+    fn domain(&self) -> (f64, f64) {
+        (0., 1.)
+    }
+    fn evaluate(&self, t: f64) -> Option<Self::Output> {
+        Some(self.apply(t))
+    }
+    fn derivative_at(&self, t: f64) -> Option<f64> {
+        Some(self.derivative(t))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ALL: [Easing; 9] = [
+        Easing::Linear,
+        Easing::QuadIn,
+        Easing::QuadOut,
+        Easing::QuadInOut,
+        Easing::CubicIn,
+        Easing::CubicOut,
+        Easing::CubicInOut,
+        Easing::SmoothStep,
+        Easing::SmootherStep,
+    ];
+
+    #[test]
+    fn test_endpoints() {
+        for easing in ALL {
+            assert_eq!(Some(0.), Some(easing.apply(0.)));
+            assert_eq!(Some(1.), Some(easing.apply(1.)));
+        }
+        for easing in [
+            Easing::QuadInOut,
+            Easing::CubicInOut,
+            Easing::SmoothStep,
+            Easing::SmootherStep,
+        ] {
+            assert_eq!(Some(0.5), Some(easing.apply(0.5)));
+        }
+    }
+    #[test]
+    fn test_solve_t_round_trips() {
+        for easing in ALL {
+            for t in [0.1, 0.3, 0.5, 0.77, 0.9] {
+                let solved = easing.solve_t(easing.apply(t));
+                assert!(solved.is_some_and(|solved| (solved - t).abs() < 1e-9));
+            }
+            assert_eq!(Some(0.), easing.solve_t(0.));
+            assert_eq!(Some(1.), easing.solve_t(1.));
+        }
+        assert_eq!(None, Easing::SmoothStep.solve_t(-0.1));
+        assert_eq!(None, Easing::SmoothStep.solve_t(f64::NAN));
+    }
+    #[test]
+    fn test_derivative() {
+        for (easing, t, slope) in [
+            (Easing::Linear, 0.3, 1.),
+            (Easing::QuadIn, 0.25, 0.5),
+            (Easing::QuadIn, 1., 2.),
+            (Easing::QuadOut, 0.75, 0.5),
+            (Easing::QuadInOut, 0.25, 1.),
+            (Easing::QuadInOut, 0.75, 1.),
+            (Easing::CubicIn, 0.5, 0.75),
+            (Easing::CubicOut, 0.5, 0.75),
+            (Easing::CubicInOut, 0.25, 0.75),
+            (Easing::CubicInOut, 0.5, 3.),
+            (Easing::SmoothStep, 0.25, 1.125),
+            (Easing::SmootherStep, 0.5, 1.875),
+            (Easing::SmootherStep, 0., 0.),
+            (Easing::CubicIn, 2., 0.),
+            (Easing::Linear, -0.5, 0.),
+        ] {
+            assert_eq!(Some(slope), easing.derivative_at(t));
+        }
+        // The exact slope agrees with a difference quotient of `apply`.
+        for easing in ALL {
+            for t in [0.1, 0.3, 0.6, 0.9] {
+                let quotient = (easing.apply(t + 1e-6) - easing.apply(t - 1e-6)) / 2e-6;
+                assert!((easing.derivative(t) - quotient).abs() < 1e-6);
+            }
+        }
+    }
+}
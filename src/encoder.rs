@@ -0,0 +1,115 @@
+//! Turning the wrapping counter of a rotary encoder into a continuous position.
+
+use crate::MapRange;
+
+/// Unwraps readings of a counter that wraps around at `modulus`, like the count register
+/// of a quadrature decoder.
+///
+/// Every reading is compared with the previous one, taking the shorter way around, so the
+/// counter must not move by half of the modulus or more between two readings.
+///
+/// ```
+/// use map_to_range::encoder::Encoder;
+///
+/// let mut encoder = Encoder::new(65536).unwrap();
+/// encoder.update(65530);
+/// assert_eq!(16, encoder.update(10));
+/// assert_eq!(-10, encoder.update(65520));
+/// assert_eq!(1136, encoder.update(1130));
+/// // Almost three turns at 384 counts per turn.
+/// assert_eq!(Some(345.), encoder.map_cycle(384, (0., 360.)));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Encoder {
+    modulus: u32,
+    last: Option<u32>,
+    position: i64,
+}
+
+impl Encoder {
+    /// An encoder whose counter counts `0..modulus`. The first reading becomes position zero.
+    ///
+    /// Returns `None` if `modulus` is below 2.
+    #[must_use]
+    pub const fn new(modulus: u32) -> Option<Self> {
+        if modulus < 2 {
+            return None;
+        }
+        Some(Self {
+            modulus,
+            last: None,
+            position: 0,
+        })
+    }
+    /// Takes a new counter reading and returns the continuous position.
+    ///
+    /// Readings at or above the modulus are wrapped into it first.
+    pub fn update(&mut self, reading: u32) -> i64 {
+        let modulus = i64::from(self.modulus);
+        let reading = reading % self.modulus;
+        if let Some(last) = self.last {
+            // Take the shorter way around, so the delta lies in `-modulus / 2..modulus / 2`.
+            let delta = (i64::from(reading) - i64::from(last)).rem_euclid(modulus);
+            let delta = if delta >= (modulus + 1) / 2 {
+                delta - modulus
+            } else {
+                delta
+            };
+            self.position = self.position.saturating_add(delta);
+        }
+        self.last = Some(reading);
+        self.position
+    }
+    /// The continuous position, counted from the first reading.
+    #[must_use]
+    pub const fn position(&self) -> i64 {
+        self.position
+    }
+    /// Moves the current position to `position`, e.g. after homing.
+    pub fn set_position(&mut self, position: i64) {
+        self.position = position;
+    }
+    /// The position within the current cycle of `counts_per_cycle`, mapped onto `to_range`.
+    /// This suits angles, where every full turn starts over.
+    ///
+    /// Returns `None` if `counts_per_cycle` is not positive or the result does not fit into `T`.
+    #[must_use]
+    pub fn map_cycle<T: MapRange>(&self, counts_per_cycle: i64, to_range: (T, T)) -> Option<T> {
+        if counts_per_cycle <= 0 {
+            return None;
+        }
+        let cycles = (0., counts_per_cycle as f64);
+        let to_range = (
+            to_range.0.checked_f64_cast()?,
+            to_range.1.checked_f64_cast()?,
+        );
+        T::checked_cast_back((self.position as f64).map_range_wrapped(cycles, to_range)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_multiple_wraps() {
+        let mut encoder = Encoder {
+            modulus: 100,
+            last: None,
+            position: 0,
+        };
+        assert_eq!(Some(encoder), Encoder::new(100));
+        assert_eq!(0, encoder.update(90));
+        for reading in [30, 70, 10, 50, 90, 30] {
+            encoder.update(reading);
+        }
+        assert_eq!(240, encoder.position());
+        for reading in [90, 50, 10, 70] {
+            encoder.update(reading);
+        }
+        assert_eq!(80, encoder.position());
+        assert_eq!(Some(288_u16), encoder.map_cycle(100, (0, 360)));
+        assert_eq!(None, encoder.map_cycle(0, (0_u16, 360)));
+        assert_eq!(None, Encoder::new(1));
+    }
+}
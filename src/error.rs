@@ -0,0 +1,32 @@
+//! The error type of the `Result` based mapping functions.
+
+use core::fmt;
+
+/// The reason a mapping failed.
+///
+/// This is returned by [`MapRange::try_map_range`](crate::MapRange::try_map_range)
+/// where the `Option` based functions would return `None`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum MapRangeError {
+    /// The value or an end of a range is `NaN` or infinite.
+    NotFinite,
+    /// The value lies outside of the range it is mapped from.
+    OutOfRange,
+    /// The range mapped from has no width, so every result would be a division by zero.
+    EmptyRange,
+    /// The result, or a step on the way, does not fit into the number type.
+    Overflow,
+}
+
+impl fmt::Display for MapRangeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::NotFinite => "a value or range end is not finite",
+            Self::OutOfRange => "value is outside of the range it is mapped from",
+            Self::EmptyRange => "the range mapped from is empty",
+            Self::Overflow => "the mapped value does not fit into its type",
+        })
+    }
+}
+
+impl core::error::Error for MapRangeError {}
@@ -0,0 +1,99 @@
+//! Fast 8 bit integer approximations for mixing values, modeled after the `lib8tion` part of `FastLED`.
+//!
+//! These avoid the `f64` path of [`MapRange`](crate::MapRange) entirely and compile down to
+//! a few integer instructions, which matters for per pixel work on chips without an FPU.
+
+/// Scales `value` by `scale / 256`, where a scale of 255 returns `value` unchanged.
+///
+/// ```
+/// use map_to_range::fast8::scale8;
+///
+/// assert_eq!(64, scale8(128, 128));
+/// assert_eq!(200, scale8(200, 255));
+/// assert_eq!(0, scale8(200, 0));
+/// ```
+#[must_use]
+pub const fn scale8(value: u8, scale: u8) -> u8 {
+    ((value as u16 * (1 + scale as u16)) >> 8) as u8
+}
+
+/// Scales every value in `values` in place by `scale / 256`, see [`scale8`].
+///
+/// ```
+/// use map_to_range::fast8::nscale8;
+///
+/// let mut pixels = [255, 128, 0];
+/// nscale8(&mut pixels, 128);
+/// assert_eq!([128, 64, 0], pixels);
+/// ```
+pub fn nscale8(values: &mut [u8], scale: u8) {
+    for value in values {
+        *value = scale8(*value, scale);
+    }
+}
+
+/// Blends from `a` towards `b`, where `amount_of_b` of 0 returns `a` and 255 returns almost `b`.
+///
+/// ```
+/// use map_to_range::fast8::blend8;
+///
+/// assert_eq!(100, blend8(100, 200, 0));
+/// assert_eq!(150, blend8(100, 200, 128));
+/// assert_eq!(200, blend8(100, 200, 255));
+/// ```
+#[must_use]
+pub const fn blend8(a: u8, b: u8, amount_of_b: u8) -> u8 {
+    let partial = ((a as u32) << 8 | b as u32) + b as u32 * amount_of_b as u32
+        - a as u32 * amount_of_b as u32;
+    (partial >> 8) as u8
+}
+
+/// Linearly interpolates between `a` and `b` by `fraction / 256`, where a fraction of 255
+/// returns `b` exactly.
+///
+/// ```
+/// use map_to_range::fast8::lerp8by8;
+///
+/// assert_eq!(10, lerp8by8(10, 250, 0));
+/// assert_eq!(130, lerp8by8(10, 250, 127));
+/// assert_eq!(250, lerp8by8(10, 250, 255));
+/// assert_eq!(10, lerp8by8(250, 10, 255));
+/// ```
+#[must_use]
+pub const fn lerp8by8(a: u8, b: u8, fraction: u8) -> u8 {
+    if b > a {
+        a + scale8(b - a, fraction)
+    } else {
+        a - scale8(a - b, fraction)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_scale8_exhaustive() {
+        for value in 0..=u8::MAX {
+            assert_eq!(value, scale8(value, 255));
+            assert_eq!(0, scale8(value, 0));
+            for scale in 0..=u8::MAX {
+                let exact = value as u16 * scale as u16 / 255;
+                assert!(scale8(value, scale).abs_diff(exact as u8) <= 1);
+            }
+        }
+    }
+    #[test]
+    fn test_blend8_and_lerp8by8() {
+        for a in 0..=u8::MAX {
+            for b in 0..=u8::MAX {
+                assert_eq!(a, blend8(a, b, 0));
+                assert!(blend8(a, b, 255).abs_diff(b) <= 1);
+                assert_eq!(a, lerp8by8(a, b, 0));
+                assert_eq!(b, lerp8by8(a, b, 255));
+                let middle = lerp8by8(a, b, 128);
+                assert!(middle >= a.min(b) && middle <= a.max(b));
+            }
+        }
+    }
+}
@@ -0,0 +1,299 @@
+//! `extern "C"` functions, so C code can share the exact same mapping math.
+//!
+//! The matching declarations live in `include/map_to_range.h`, which is written by hand.
+//! A test compares it to the signatures in this file, so update both together.
+//! Every function returns `false` where the rust functions would return `None`,
+//! and leaves the output untouched in that case.
+//!
+//! The crate is an `rlib` only, because a `no_std` static library also needs a panic handler.
+//! With the standard library, build the static library for C projects directly:
+//!
+//! ```text
+//! cargo rustc --release --features std,ffi --crate-type staticlib
+//! ```
+//!
+//! Firmware links a small wrapper crate instead, with `crate-type = ["staticlib"]`, this crate
+//! as a dependency with the `ffi` feature, `use map_to_range as _;` to link it and the
+//! `#[panic_handler]` of the target.
+
+use crate::MapRange;
+
+/// A linear mapping between two `f32` ranges, for use from C.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MrMapper {
+    pub from_lo: f32,
+    pub from_hi: f32,
+    pub to_lo: f32,
+    pub to_hi: f32,
+    /// Clamps values outside of the `from` range instead of rejecting them.
+    pub clamped: bool,
+}
+
+/// Writes `result` to `out`, if both are present.
+///
+/// # Safety
+///
+/// `out` must be null or valid for writes.
+unsafe fn write<T>(out: *mut T, result: Option<T>) -> bool {
+    // SAFETY: the caller guarantees `out` is null or valid.
+    match (result, unsafe { out.as_mut() }) {
+        (Some(result), Some(out)) => {
+            *out = result;
+            true
+        }
+        _ => false,
+    }
+}
+
+/// Clamps `value` into `range`, leaving reversed ranges to fail during mapping.
+fn clamp_into<T: PartialOrd + Copy>(value: T, range: (T, T)) -> T {
+    if value < range.0 {
+        range.0
+    } else if value > range.1 {
+        range.1
+    } else {
+        value
+    }
+}
+
+/// Maps a `u8` like [`MapRange::map_range`].
+///
+/// # Safety
+///
+/// `out` must be null or valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn mr_map_u8(
+    value: u8,
+    from_lo: u8,
+    from_hi: u8,
+    to_lo: u8,
+    to_hi: u8,
+    out: *mut u8,
+) -> bool {
+    // SAFETY: forwarded from the caller.
+    unsafe { write(out, value.map_range((from_lo, from_hi), (to_lo, to_hi))) }
+}
+
+/// Maps a `u8` like [`mr_map_u8`], but clamps `value` into the `from` range first.
+///
+/// # Safety
+///
+/// `out` must be null or valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn mr_map_u8_clamped(
+    value: u8,
+    from_lo: u8,
+    from_hi: u8,
+    to_lo: u8,
+    to_hi: u8,
+    out: *mut u8,
+) -> bool {
+    let value = clamp_into(value, (from_lo, from_hi));
+    // SAFETY: forwarded from the caller.
+    unsafe { mr_map_u8(value, from_lo, from_hi, to_lo, to_hi, out) }
+}
+
+/// Maps a `f32` like [`MapRange::map_range`].
+///
+/// # Safety
+///
+/// `out` must be null or valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn mr_map_f32(
+    value: f32,
+    from_lo: f32,
+    from_hi: f32,
+    to_lo: f32,
+    to_hi: f32,
+    out: *mut f32,
+) -> bool {
+    // SAFETY: forwarded from the caller.
+    unsafe { write(out, value.map_range((from_lo, from_hi), (to_lo, to_hi))) }
+}
+
+/// Maps a `f32` like [`mr_map_f32`], but clamps `value` into the `from` range first.
+///
+/// # Safety
+///
+/// `out` must be null or valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn mr_map_f32_clamped(
+    value: f32,
+    from_lo: f32,
+    from_hi: f32,
+    to_lo: f32,
+    to_hi: f32,
+    out: *mut f32,
+) -> bool {
+    let value = clamp_into(value, (from_lo, from_hi));
+    // SAFETY: forwarded from the caller.
+    unsafe { mr_map_f32(value, from_lo, from_hi, to_lo, to_hi, out) }
+}
+
+/// Creates a mapper from the given ranges.
+#[no_mangle]
+pub extern "C" fn mr_mapper_create(
+    from_lo: f32,
+    from_hi: f32,
+    to_lo: f32,
+    to_hi: f32,
+    clamped: bool,
+) -> MrMapper {
+    MrMapper {
+        from_lo,
+        from_hi,
+        to_lo,
+        to_hi,
+        clamped,
+    }
+}
+
+/// Maps `value` with `mapper`.
+///
+/// # Safety
+///
+/// `mapper` must be null or valid for reads, and `out` must be null or valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn mr_mapper_evaluate(
+    mapper: *const MrMapper,
+    value: f32,
+    out: *mut f32,
+) -> bool {
+    // SAFETY: the caller guarantees `mapper` is null or valid.
+    let Some(mapper) = (unsafe { mapper.as_ref() }) else {
+        return false;
+    };
+    let value = if mapper.clamped {
+        clamp_into(value, (mapper.from_lo, mapper.from_hi))
+    } else {
+        value
+    };
+    // SAFETY: forwarded from the caller.
+    unsafe {
+        mr_map_f32(
+            value,
+            mapper.from_lo,
+            mapper.from_hi,
+            mapper.to_lo,
+            mapper.to_hi,
+            out,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_map_u8() {
+        let mut out = 0;
+        assert!(unsafe { mr_map_u8(5, 0, 10, 10, 20, &raw mut out) });
+        assert_eq!(15, out);
+        assert!(!unsafe { mr_map_u8(11, 0, 10, 10, 20, &raw mut out) });
+        assert!(unsafe { mr_map_u8_clamped(11, 0, 10, 10, 20, &raw mut out) });
+        assert_eq!(20, out);
+        assert!(!unsafe { mr_map_u8(5, 0, 10, 10, 20, core::ptr::null_mut()) });
+    }
+    #[test]
+    fn test_mapper() {
+        let mapper = mr_mapper_create(0., 1., 0., 100., true);
+        let mut out = 0.;
+        assert!(unsafe { mr_mapper_evaluate(&raw const mapper, 0.25, &raw mut out) });
+        assert_eq!(Some(25.), Some(out));
+        assert!(unsafe { mr_mapper_evaluate(&raw const mapper, 2., &raw mut out) });
+        assert_eq!(Some(100.), Some(out));
+        assert!(!unsafe { mr_mapper_evaluate(core::ptr::null(), 0.5, &raw mut out) });
+        let strict = mr_mapper_create(0., 1., 0., 100., false);
+        assert!(!unsafe { mr_mapper_evaluate(&raw const strict, 2., &raw mut out) });
+    }
+
+    const HEADER: &str = include_str!("../include/map_to_range.h");
+    const SOURCE: &str = include_str!("ffi.rs");
+
+    /// The C spelling of a rust type used in this module.
+    fn c_type(rust: &str) -> (&str, &str) {
+        let (qualifier, pointee) = if let Some(pointee) = rust.strip_prefix("*const ") {
+            ("const ", pointee)
+        } else {
+            ("", rust.strip_prefix("*mut ").unwrap_or(rust))
+        };
+        let c = match pointee {
+            "u8" => "uint8_t",
+            "f32" => "float",
+            other => other,
+        };
+        (qualifier, c)
+    }
+    /// Strips `pieces` from the front of `line` in order.
+    fn strip_all<'a>(line: &'a str, pieces: &[&str]) -> Option<&'a str> {
+        pieces
+            .iter()
+            .try_fold(line, |line, piece| line.strip_prefix(piece))
+    }
+
+    #[test]
+    fn test_header_matches_signatures() {
+        let mut functions = 0;
+        for item in SOURCE.split("extern \"C\" fn ").skip(1) {
+            let (name, rest) = item.split_once('(').unwrap_or_default();
+            let (parameters, rest) = rest.split_once(')').unwrap_or_default();
+            let returns = rest
+                .strip_prefix(" -> ")
+                .and_then(|rest| rest.split_once(" {"))
+                .map_or("void", |(returns, _)| returns);
+            let prototype = HEADER.lines().find(|line| line.contains(name));
+            let (_, returns) = c_type(returns);
+            let mut rest = prototype.and_then(|line| strip_all(line, &[returns, " ", name, "("]));
+            let parameters = parameters
+                .split(',')
+                .map(str::trim)
+                .filter(|parameter| !parameter.is_empty());
+            for (index, parameter) in parameters.enumerate() {
+                let (name, rust) = parameter.split_once(": ").unwrap_or_default();
+                let (qualifier, c) = c_type(rust);
+                let pointer = if rust.starts_with('*') { " *" } else { " " };
+                let separator = if index == 0 { "" } else { ", " };
+                rest = rest
+                    .and_then(|rest| strip_all(rest, &[separator, qualifier, c, pointer, name]));
+            }
+            assert_eq!(
+                Some(");"),
+                rest,
+                "{name} differs from include/map_to_range.h"
+            );
+            functions += 1;
+        }
+        let prototypes = HEADER.lines().filter(|line| line.ends_with(");")).count();
+        assert_eq!((6, 6), (functions, prototypes));
+
+        let fields = SOURCE
+            .split_once("pub struct MrMapper {")
+            .and_then(|(_, rest)| rest.split_once('}'))
+            .map(|(fields, _)| fields)
+            .unwrap_or_default();
+        let mut header_fields = HEADER
+            .split_once("typedef struct MrMapper {")
+            .and_then(|(_, rest)| rest.split_once('}'))
+            .map(|(fields, _)| fields)
+            .unwrap_or_default()
+            .lines()
+            .map(str::trim)
+            .filter(|line| line.ends_with(';'));
+        for field in fields
+            .lines()
+            .filter_map(|line| line.trim().strip_prefix("pub "))
+        {
+            let (name, rust) = field.split_once(": ").unwrap_or_default();
+            let (_, c) = c_type(rust.trim_end_matches(','));
+            let header_field = header_fields.next();
+            assert_eq!(
+                Some(Some(";")),
+                header_field.map(|line| strip_all(line, &[c, " ", name])),
+                "MrMapper.{name} differs from include/map_to_range.h"
+            );
+        }
+        assert_eq!(None, header_fields.next());
+    }
+}
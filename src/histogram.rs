@@ -0,0 +1,277 @@
+//! Counting values into bins, to find percentiles of recorded data.
+
+use crate::piecewise::PiecewiseLinear;
+use crate::policy::{self, OutOfRangePolicy};
+use crate::MapRange;
+
+/// Counts of values in `N` equally wide bins over a range.
+///
+/// Values below or above the range are counted separately, and treated as lying on the nearest
+/// end of the range by quantile queries.
+///
+/// ```
+/// use map_to_range::histogram::Histogram;
+///
+/// let mut histogram = Histogram::<10>::new((0., 100.)).unwrap();
+/// for value in 0..100 {
+///     histogram.record(f64::from(value));
+/// }
+/// histogram.record(5000.);
+/// assert_eq!(Some(50.5), histogram.quantile(0.5));
+/// assert_eq!(Some(100.), histogram.quantile(1.));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Histogram<const N: usize> {
+    range: (f64, f64),
+    bins: [u32; N],
+    below: u32,
+    above: u32,
+}
+
+impl<const N: usize> Histogram<N> {
+    /// An empty histogram over `range`.
+    ///
+    /// Returns `None` if `N` is zero or `range` is empty, reversed or not finite.
+    #[must_use]
+    pub fn new(range: (f64, f64)) -> Option<Self> {
+        let ascending = range.0.partial_cmp(&range.1) == Some(core::cmp::Ordering::Less);
+        if N == 0 || !ascending || !(range.1 - range.0).is_finite() {
+            return None;
+        }
+        Some(Self {
+            range,
+            bins: [0; N],
+            below: 0,
+            above: 0,
+        })
+    }
+    /// Counts `value`. `NaN` is ignored and counts saturate.
+    pub fn record(&mut self, value: f64) {
+        let counter = if value.is_nan() {
+            return;
+        } else if value < self.range.0 {
+            &mut self.below
+        } else if value > self.range.1 {
+            &mut self.above
+        } else {
+            let position = (value - self.range.0) / self.width();
+            // The upper end of the range belongs into the last bin.
+            let index = (position as usize).min(N - 1);
+            let Some(bin) = self.bins.get_mut(index) else {
+                return;
+            };
+            bin
+        };
+        *counter = counter.saturating_add(1);
+    }
+    /// The range the bins span.
+    #[must_use]
+    pub const fn range(&self) -> (f64, f64) {
+        self.range
+    }
+    /// The number of values in every bin.
+    #[must_use]
+    pub const fn bins(&self) -> &[u32; N] {
+        &self.bins
+    }
+    /// The number of values below and above the range.
+    #[must_use]
+    pub const fn outside(&self) -> (u32, u32) {
+        (self.below, self.above)
+    }
+    /// The number of values recorded.
+    #[must_use]
+    pub fn count(&self) -> u64 {
+        self.bins.iter().map(|count| u64::from(*count)).sum::<u64>()
+            + u64::from(self.below)
+            + u64::from(self.above)
+    }
+    /// Forgets all recorded values.
+    pub fn clear(&mut self) {
+        self.bins = [0; N];
+        self.below = 0;
+        self.above = 0;
+    }
+    /// The value below which a fraction `q` of the recorded values lies, assuming values are
+    /// spread evenly inside every bin.
+    ///
+    /// Returns `None` if nothing was recorded or `q` is not inside `0..=1`.
+    #[must_use]
+    pub fn quantile(&self, q: f64) -> Option<f64> {
+        let total = self.count();
+        if total == 0 || !(0. ..=1.).contains(&q) {
+            return None;
+        }
+        let target = q * total as f64;
+        let mut cumulative = f64::from(self.below);
+        if self.below > 0 && target <= cumulative {
+            return Some(self.range.0);
+        }
+        let width = self.width();
+        for (left, count) in (0..)
+            .map(|index| self.range.0 + f64::from(index) * width)
+            .zip(self.bins)
+        {
+            let count = f64::from(count);
+            if count > 0. && target <= cumulative + count {
+                return Some(left + (target - cumulative) / count * width);
+            }
+            cumulative += count;
+        }
+        Some(self.range.1)
+    }
+    /// The range between the `lower` and `upper` quantile, like `(0.05, 0.95)` for the 5th to
+    /// 95th percentile.
+    ///
+    /// Returns `None` if nothing was recorded or a quantile is not inside `0..=1`.
+    #[must_use]
+    pub fn quantile_range(&self, lower: f64, upper: f64) -> Option<(f64, f64)> {
+        Some((self.quantile(lower)?, self.quantile(upper)?))
+    }
+    /// Maps `value` from the range between the `quantiles` of the recorded values onto
+    /// `to_range`, clamping values beyond them.
+    ///
+    /// Unlike mapping from the smallest to the largest value, a few outliers do not squeeze the
+    /// bulk of the data into a tiny part of `to_range`.
+    ///
+    /// Returns `None` if nothing was recorded, the quantile range is empty or the result does
+    /// not fit into `U`.
+    ///
+    /// ```
+    /// use map_to_range::histogram::Histogram;
+    ///
+    /// let mut histogram = Histogram::<100>::new((0., 100.)).unwrap();
+    /// for value in 0..100 {
+    ///     histogram.record(f64::from(value));
+    /// }
+    /// histogram.record(1000.);
+    /// assert_eq!(Some(127_u8), histogram.map_range_robust(50.5, (0.05, 0.95), (0, 255)));
+    /// assert_eq!(Some(255_u8), histogram.map_range_robust(1000., (0.05, 0.95), (0, 255)));
+    /// ```
+    #[must_use]
+    pub fn map_range_robust<T: MapRange, U: MapRange>(
+        &self,
+        value: T,
+        quantiles: (f64, f64),
+        to_range: (U, U),
+    ) -> Option<U> {
+        let from_range = self.quantile_range(quantiles.0, quantiles.1)?;
+        let to_range = (
+            to_range.0.checked_f64_cast()?,
+            to_range.1.checked_f64_cast()?,
+        );
+        let value = value.checked_f64_cast()?;
+        let mapped = policy::map_f64(value, from_range, to_range, OutOfRangePolicy::Clamp)?;
+        // Land clamped tails exactly on the ends, which the division may miss by a rounding step.
+        U::checked_cast_back(if value >= from_range.1 {
+            to_range.1
+        } else if value <= from_range.0 {
+            to_range.0
+        } else {
+            mapped
+        })
+    }
+    /// A table mapping values onto the fraction of recorded values below them, in `0..=1`.
+    ///
+    /// Mapping through it spreads the recorded values evenly over the output, which is known as
+    /// histogram equalization. The table has `N + 1` points, one per bin edge.
+    ///
+    /// Returns `None` if nothing was recorded or `M` is less than `N + 1`.
+    ///
+    /// ```
+    /// use map_to_range::histogram::Histogram;
+    ///
+    /// let mut histogram = Histogram::<2>::new((0., 10.)).unwrap();
+    /// for value in [1., 2., 3., 8.] {
+    ///     histogram.record(value);
+    /// }
+    /// let equalize = histogram.cumulative::<3>().unwrap();
+    /// assert_eq!(Some(0.75), equalize.map_f64(5.));
+    /// assert_eq!(Some(0.875), equalize.map_f64(7.5));
+    /// ```
+    #[must_use]
+    pub fn cumulative<const M: usize>(&self) -> Option<PiecewiseLinear<M>> {
+        let total = self.count() as f64;
+        if total == 0. || M < N + 1 {
+            return None;
+        }
+        let mut points = [(0., 0.); M];
+        let mut cumulative = f64::from(self.below);
+        *points.first_mut()? = (self.range.0, cumulative / total);
+        let width = self.width();
+        for ((index, count), point) in (1..).zip(self.bins).zip(points.iter_mut().skip(1)) {
+            cumulative += f64::from(count);
+            *point = (self.range.0 + f64::from(index) * width, cumulative / total);
+        }
+        // Avoid rounding the last edge away from the end of the range.
+        if let Some(last) = points.get_mut(N) {
+            last.0 = self.range.1;
+        }
+        PiecewiseLinear::new(points.get(..=N)?)
+    }
+    fn width(&self) -> f64 {
+        (self.range.1 - self.range.0) / N as f64
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_histogram() {
+        assert_eq!(None, Histogram::<0>::new((0., 1.)));
+        assert_eq!(None, Histogram::<4>::new((1., 1.)));
+        assert_eq!(None, Histogram::<4>::new((0., f64::INFINITY)));
+        let mut histogram = Histogram {
+            range: (0., 4.),
+            bins: [0; 4],
+            below: 0,
+            above: 0,
+        };
+        assert_eq!(Some(histogram), Histogram::new((0., 4.)));
+        assert_eq!(None, histogram.quantile(0.5));
+        for value in [-1., 0., 0.5, 3.5, 4., 9., f64::NAN] {
+            histogram.record(value);
+        }
+        assert_eq!(&[2, 0, 0, 2], histogram.bins());
+        assert_eq!((1, 1), histogram.outside());
+        assert_eq!(6, histogram.count());
+        assert_eq!(Some(0.), histogram.quantile(0.));
+        assert_eq!(Some(0.25), histogram.quantile(0.25));
+        assert_eq!(Some(1.), histogram.quantile(0.5));
+        assert_eq!(Some(4.), histogram.quantile(1.));
+        assert_eq!(None, histogram.quantile(1.5));
+        assert_eq!(None, histogram.cumulative::<4>());
+        let table = histogram.cumulative::<5>();
+        assert_eq!(Some(Some(0.5)), table.map(|t| t.map_f64(2.)));
+        histogram.clear();
+        assert_eq!(0, histogram.count());
+    }
+    #[test]
+    fn test_map_range_robust() {
+        let mut histogram = Histogram {
+            range: (0., 10.),
+            bins: [0; 10],
+            below: 0,
+            above: 0,
+        };
+        assert_eq!(None, histogram.map_range_robust(1., (0.1, 0.9), (0., 1.)));
+        for value in [5., 5., 5., 5., -100., 100.] {
+            histogram.record(value);
+        }
+        assert_eq!(Some((0., 10.)), histogram.quantile_range(0., 1.));
+        // Both quantiles fall into the bin of the fives.
+        let robust = histogram.quantile_range(0.25, 0.75);
+        assert_eq!(Some((5.125, 5.875)), robust);
+        assert_eq!(
+            Some(0.),
+            histogram.map_range_robust(-50., (0.25, 0.75), (0., 1.))
+        );
+        assert_eq!(
+            Some(0.5),
+            histogram.map_range_robust(5.5, (0.25, 0.75), (0., 1.))
+        );
+        assert_eq!(None, histogram.map_range_robust(5., (0.5, 0.5), (0., 1.)));
+    }
+}
@@ -0,0 +1,47 @@
+//! Numeric integration of mappings and curves.
+
+/// Integrates `function` over `range` with the trapezoidal rule, using `steps` evenly spaced
+/// trapezoids. More steps give a more precise result.
+///
+/// Returns `None` if `steps` is zero or `function` fails for any of the evaluated inputs.
+///
+/// The function returns an `Option`, so a mapping can be integrated directly:
+/// ```
+/// use map_to_range::{integrate, MapRange};
+///
+/// // Flow rate in l/s, linearly rising from 0 at 0s to 2 at 10s.
+/// let flow = |seconds: f64| seconds.map_range((0., 10.), (0., 2.));
+/// let litres = integrate::trapezoidal(flow, (0., 5.), 100).unwrap();
+/// assert!((litres - 2.5).abs() < 1e-9);
+/// assert_eq!(None, integrate::trapezoidal(flow, (0., 20.), 100));
+/// ```
+pub fn trapezoidal<F>(function: F, range: (f64, f64), steps: usize) -> Option<f64>
+where
+    F: Fn(f64) -> Option<f64>,
+{
+    if steps == 0 {
+        return None;
+    }
+    let width = (range.1 - range.0) / steps as f64;
+    let mut sum = f64::midpoint(function(range.0)?, function(range.1)?);
+    for step in 1..steps {
+        sum += function(range.0 + width * step as f64)?;
+    }
+    Some(sum * width)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_trapezoidal() {
+        let square = |x: f64| Some(x * x);
+        let area = trapezoidal(square, (0., 3.), 1000);
+        assert!(area.is_some_and(|area| (area - 9.).abs() < 1e-4));
+        let reversed = trapezoidal(square, (3., 0.), 1000);
+        assert!(reversed.is_some_and(|area| (area + 9.).abs() < 1e-4));
+        assert_eq!(Some(0.), trapezoidal(square, (2., 2.), 10));
+        assert_eq!(None, trapezoidal(square, (0., 3.), 0));
+    }
+}
@@ -0,0 +1,150 @@
+//! Operations on whole LED frames of 8 bit channel values.
+//!
+//! RGB frames of `[u8; 3]` pixels can be passed in with
+//! [`as_flattened`](slice::as_flattened) and [`as_flattened_mut`](slice::as_flattened_mut).
+
+use crate::fast8::{lerp8by8, scale8};
+
+/// How channel values are blended.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Blend {
+    /// Blend the raw channel values. This is the fastest, but the middle of a fade looks too dark,
+    /// because LEDs are driven linearly while the eye perceives brightness roughly as a square root.
+    #[default]
+    Linear,
+    /// Blend the light output, approximating the gamma of the values with 2.
+    /// The fade then has a constant perceived brightness.
+    GammaCorrect,
+}
+
+/// Crossfades between the frames `from` and `to` into `output`, where `t` of 0 produces `from`
+/// and `t` of 255 produces `to`.
+///
+/// Returns `None` if the frames differ in length.
+///
+/// ```
+/// use map_to_range::led::{crossfade, Blend};
+///
+/// let from = [[255, 0, 0]; 8];
+/// let to = [[0, 0, 255]; 8];
+/// let mut output = [[0; 3]; 8];
+/// crossfade(from.as_flattened(), to.as_flattened(), 128, Blend::Linear, output.as_flattened_mut());
+/// assert_eq!([127, 0, 128], output[0]);
+/// crossfade(from.as_flattened(), to.as_flattened(), 128, Blend::GammaCorrect, output.as_flattened_mut());
+/// assert_eq!([179, 0, 180], output[0]);
+/// ```
+pub fn crossfade(from: &[u8], to: &[u8], t: u8, blend: Blend, output: &mut [u8]) -> Option<()> {
+    if from.len() != to.len() || from.len() != output.len() {
+        return None;
+    }
+    let pixels = from.iter().zip(to).zip(output);
+    match blend {
+        Blend::Linear => {
+            for ((from, to), output) in pixels {
+                *output = lerp8by8(*from, *to, t);
+            }
+        }
+        Blend::GammaCorrect => {
+            for ((from, to), output) in pixels {
+                let from = *from as u32 * *from as u32;
+                let to = *to as u32 * *to as u32;
+                let linear = (from * (255 - t as u32) + to * t as u32) / 255;
+                *output = linear.isqrt() as u8;
+            }
+        }
+    }
+    Some(())
+}
+
+/// The relation between a channel value and its light output.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum BrightnessCurve<'a> {
+    /// Values are used as they are.
+    #[default]
+    Linear,
+    /// A gamma of 2, `v² / 255`.
+    Gamma2,
+    /// A gamma of 3, `v³ / 255²`.
+    Gamma3,
+    /// A lookup table with the output for every value, e.g. a precomputed gamma of 2.2.
+    Table(&'a [u8; 256]),
+}
+
+impl BrightnessCurve<'_> {
+    /// Applies the curve to a single channel value.
+    ///
+    /// ```
+    /// use map_to_range::led::BrightnessCurve;
+    ///
+    /// assert_eq!(64, BrightnessCurve::Gamma2.apply(128));
+    /// assert_eq!(255, BrightnessCurve::Gamma3.apply(255));
+    /// ```
+    #[must_use]
+    pub fn apply(self, value: u8) -> u8 {
+        match self {
+            BrightnessCurve::Linear => value,
+            BrightnessCurve::Gamma2 => scale8(value, value),
+            BrightnessCurve::Gamma3 => scale8(scale8(value, value), value),
+            BrightnessCurve::Table(table) => table.get(value as usize).copied().unwrap_or(value),
+        }
+    }
+}
+
+/// Scales every channel of `frame` by the master fader `level` and then applies `curve` to it.
+///
+/// ```
+/// use map_to_range::led::{apply_master, BrightnessCurve};
+///
+/// let mut frame = [255, 128, 0];
+/// apply_master(&mut frame, 128, BrightnessCurve::Gamma2);
+/// assert_eq!([64, 16, 0], frame);
+/// ```
+pub fn apply_master(frame: &mut [u8], level: u8, curve: BrightnessCurve) {
+    for value in frame {
+        *value = curve.apply(scale8(*value, level));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_crossfade_endpoints() {
+        let from = [0, 10, 128, 255];
+        let to = [255, 200, 128, 0];
+        let mut output = [0; 4];
+        for blend in [Blend::Linear, Blend::GammaCorrect] {
+            assert_eq!(Some(()), crossfade(&from, &to, 0, blend, &mut output));
+            assert_eq!(from, output);
+            assert_eq!(Some(()), crossfade(&from, &to, 255, blend, &mut output));
+            assert_eq!(to, output);
+        }
+    }
+    #[test]
+    fn test_apply_master() {
+        let mut frame = [0, 1, 100, 255];
+        apply_master(&mut frame, 255, BrightnessCurve::Linear);
+        assert_eq!([0, 1, 100, 255], frame);
+        let mut inverted = [0; 256];
+        for (value, slot) in (0..=u8::MAX).rev().zip(inverted.iter_mut()) {
+            *slot = value;
+        }
+        apply_master(&mut frame, 255, BrightnessCurve::Table(&inverted));
+        assert_eq!([255, 254, 155, 0], frame);
+        apply_master(&mut frame, 0, BrightnessCurve::Gamma3);
+        assert_eq!([0; 4], frame);
+    }
+    #[test]
+    fn test_crossfade_rejects_mismatched_frames() {
+        let mut output = [0; 2];
+        assert_eq!(
+            None,
+            crossfade(&[0; 2], &[0; 3], 0, Blend::Linear, &mut output)
+        );
+        assert_eq!(
+            None,
+            crossfade(&[0; 3], &[0; 3], 0, Blend::Linear, &mut output)
+        );
+    }
+}
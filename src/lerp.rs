@@ -0,0 +1,174 @@
+//! Linear interpolation between two values of the same type.
+
+/// Values that can be linearly interpolated.
+///
+/// ```
+/// use map_to_range::lerp::Lerp;
+///
+/// assert_eq!(15., 10_f64.lerp(20., 0.5));
+/// assert_eq!(25., 10_f64.lerp(20., 1.5));
+/// assert_eq!([0.5, 5.], [0_f32, 10.].lerp([1., 0.], 0.5));
+/// ```
+pub trait Lerp: Copy {
+    /// Interpolates from `self` at `t = 0` to `other` at `t = 1`.
+    /// Values of `t` outside of `0..=1` extrapolate along the same line.
+    #[must_use]
+    fn lerp(self, other: Self, t: f64) -> Self;
+    /// The weighted sum `self * gain + other * other_gain`, where `Self::default()` is zero.
+    /// Both gains are expected to be zero or positive.
+    ///
+    /// By default this blends first and then scales the blend, so only a result that does not
+    /// fit saturates. The number types compute it in `f64` and round once.
+    ///
+    /// ```
+    /// use core::f64::consts::FRAC_1_SQRT_2;
+    /// use map_to_range::lerp::Lerp;
+    ///
+    /// assert_eq!(21_213, 30_000_i16.mix(FRAC_1_SQRT_2, 0, FRAC_1_SQRT_2));
+    /// assert_eq!(i16::MAX, 30_000_i16.mix(1., 30_000, 1.));
+    /// ```
+    #[must_use]
+    fn mix(self, gain: f64, other: Self, other_gain: f64) -> Self
+    where
+        Self: Default,
+    {
+        let sum = gain + other_gain;
+        if sum <= 0. {
+            return Self::default();
+        }
+        Self::default().lerp(self.lerp(other, other_gain / sum), sum)
+    }
+}
+
+impl Lerp for f64 {
+    fn lerp(self, other: Self, t: f64) -> Self {
+        self + (other - self) * t
+    }
+    fn mix(self, gain: f64, other: Self, other_gain: f64) -> Self {
+        self * gain + other * other_gain
+    }
+}
+
+impl Lerp for f32 {
+    fn lerp(self, other: Self, t: f64) -> Self {
+        (self as f64).lerp(other as f64, t) as f32
+    }
+    fn mix(self, gain: f64, other: Self, other_gain: f64) -> Self {
+        f64::from(self).mix(gain, f64::from(other), other_gain) as f32
+    }
+}
+
+impl Lerp for i16 {
+    /// Rounds half away from zero and saturates at the ends of `i16`.
+    fn lerp(self, other: Self, t: f64) -> Self {
+        crate::math::round_half_away(f64::from(self).lerp(f64::from(other), t)) as i16
+    }
+    /// Rounds half away from zero and saturates at the ends of `i16`.
+    fn mix(self, gain: f64, other: Self, other_gain: f64) -> Self {
+        crate::math::round_half_away(f64::from(self).mix(gain, f64::from(other), other_gain)) as i16
+    }
+}
+
+impl<T: Lerp + Default, const N: usize> Lerp for [T; N] {
+    fn lerp(mut self, other: Self, t: f64) -> Self {
+        for (value, other) in self.iter_mut().zip(other) {
+            *value = value.lerp(other, t);
+        }
+        self
+    }
+    /// Mixes every element on its own, so integers round once like their scalar `mix`.
+    fn mix(mut self, gain: f64, other: Self, other_gain: f64) -> Self
+    where
+        Self: Default,
+    {
+        for (value, other) in self.iter_mut().zip(other) {
+            *value = value.mix(gain, other, other_gain);
+        }
+        self
+    }
+}
+
+/// How values between two keyframes or samples are produced.
+///
+/// ```
+/// use map_to_range::lerp::Interpolation;
+///
+/// assert_eq!(17.5, Interpolation::Linear.interpolate(10., 20., 0.75));
+/// assert_eq!(10., Interpolation::Hold.interpolate(10., 20., 0.75));
+/// assert_eq!(20., Interpolation::Nearest.interpolate(10., 20., 0.75));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum Interpolation {
+    /// Interpolates linearly, see [`Lerp`].
+    #[default]
+    Linear,
+    /// Holds the first value until the second one is reached, for stepped automation.
+    Hold,
+    /// Uses the closer of both values, the second one from halfway on.
+    Nearest,
+}
+
+impl Interpolation {
+    /// The value at `t` between `a` at 0 and `b` at 1.
+    #[must_use]
+    pub fn interpolate<T: Lerp>(self, a: T, b: T, t: f64) -> T {
+        match self {
+            Self::Linear => a.lerp(b, t),
+            Self::Hold => {
+                if t >= 1. {
+                    b
+                } else {
+                    a
+                }
+            }
+            Self::Nearest => {
+                if t >= 0.5 {
+                    b
+                } else {
+                    a
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_lerp() {
+        assert_eq!(Some(-10.), Some(10_f64.lerp(20., -2.)));
+        assert_eq!(Some(20.), Some(10_f32.lerp(20., 1.)));
+        assert_eq!(-2, 0_i16.lerp(-3, 0.5));
+        assert_eq!(i16::MAX, 0_i16.lerp(i16::MAX, 2.));
+        assert_eq!([[2., 4.]], [[0_f64, 0.]].lerp([[4., 8.]], 0.5));
+    }
+    #[test]
+    fn test_mix() {
+        use core::f64::consts::FRAC_1_SQRT_2;
+        assert_eq!(Some(7.), Some(1_f64.mix(3., 2., 2.)));
+        assert_eq!(-32_768, (-30_000_i16).mix(1., -30_000, 1.));
+        assert_eq!(
+            [21_213, 0],
+            [30_000_i16, 0].mix(FRAC_1_SQRT_2, [0, 0], FRAC_1_SQRT_2)
+        );
+        assert_eq!([0, 0], [5_i16, 5].mix(0., [7, 7], 0.));
+        // Frames round every channel once, exactly like the scalar mix.
+        for a in (-30_000_i16..30_000).step_by(777) {
+            for b in (-30_000_i16..30_000).step_by(1_001) {
+                let frame = [a, b].mix(FRAC_1_SQRT_2, [b, a], 0.3);
+                let scalar = [a.mix(FRAC_1_SQRT_2, b, 0.3), b.mix(FRAC_1_SQRT_2, a, 0.3)];
+                assert_eq!(scalar, frame);
+            }
+        }
+    }
+    #[test]
+    fn test_interpolation() {
+        assert_eq!(3, Interpolation::Hold.interpolate(3_i16, 9, 0.99));
+        assert_eq!(9, Interpolation::Hold.interpolate(3_i16, 9, 1.));
+        assert_eq!(3, Interpolation::Nearest.interpolate(3_i16, 9, 0.49));
+        assert_eq!(9, Interpolation::Nearest.interpolate(3_i16, 9, 0.5));
+        assert_eq!(6, Interpolation::default().interpolate(3_i16, 9, 0.5));
+    }
+}
@@ -1,11 +1,90 @@
-#![no_std]
-use core::fmt::Display;
+#![cfg_attr(not(feature = "std"), no_std)]
+
+#[cfg(feature = "alloc")]
+extern crate alloc;
+
+pub mod adc;
+pub mod affine;
+pub mod audio;
+pub mod battery;
+pub mod branchless;
+pub mod calibration;
+pub mod clock;
+pub mod const_map;
+pub mod curve;
+pub mod dmx;
+pub mod easing;
+pub mod encoder;
+mod error;
+pub mod fast8;
+#[cfg(feature = "ffi")]
+pub mod ffi;
+pub mod histogram;
+pub mod integrate;
+pub mod led;
+pub mod lerp;
+pub mod mapper;
+mod math;
+pub mod motion;
+pub mod noise;
+pub mod palette;
+pub mod piecewise;
+pub mod policy;
+pub mod polynomial;
+pub mod progress;
+pub mod pwm;
+#[cfg(feature = "random")]
+pub mod random;
+pub mod range;
+pub mod rect;
+pub mod resample;
+pub mod series;
+pub mod servo;
+pub mod slice;
+pub mod snapshot;
+pub mod taper;
+pub mod thermistor;
+pub mod time;
+pub mod timestep;
+pub mod trace;
+pub mod tracker;
+pub mod tween;
+pub mod unit;
+pub mod validation;
+pub mod viewport;
+pub mod wave;
+
+pub use error::MapRangeError;
+
+// x87 floats keep extra precision between operations, so results would depend on register allocation.
+#[cfg(all(
+    feature = "deterministic",
+    target_arch = "x86",
+    not(target_feature = "sse2")
+))]
+compile_error!(
+    "the `deterministic` feature needs SSE2 on 32 bit x86, as x87 floats round differently"
+);
 
 /// This holds a function that maps a number from one range to another.
 /// This is designed to work in `no_std` environments
+///
+/// For floats, a `NaN` or infinite value or range end always makes the mapping fail,
+/// instead of producing a `NaN` result. Likewise, float mappings fail exactly when a step
+/// of the calculation overflows to infinity. Results close to `MAX`, and mappings between
+/// negative ranges, are computed normally.
+///
+/// Float calculations in this crate are written as individually rounded IEEE 754 operations in
+/// a fixed order, which rust never fuses into multiply-adds, and use the crate's own `sqrt`,
+/// `ln` and `exp` instead of the platform's math library. On targets whose hardware follows
+/// IEEE 754, like x86 with SSE2, ARM and wasm, results are the same on each of them.
+/// There is no soft-float path: targets that round differently, or code that changes the
+/// floating point environment, e.g. to flush subnormals to zero, can still change results.
+/// The `deterministic` feature only refuses to build for 32 bit x86 without SSE2, whose x87
+/// floats keep extra precision between operations.
 #[allow(private_bounds)]
 pub trait MapRange:
-    Sized + Copy + PartialOrd + CheckedNumberArithmetics + Display + CheckedNumberCastsToFloat
+    Sized + Copy + PartialOrd + CheckedNumberArithmetics + CheckedNumberCastsToFloat
 {
     /// Maps the value over the given ranges.
     ///
@@ -36,6 +115,322 @@ pub trait MapRange:
         let result = value.map_range_uncasted(from_range, to_range)?;
         Self::checked_cast_back(result)
     }
+    /// Maps the value over the given ranges like `map_range`, but tells why the mapping failed.
+    ///
+    /// ```
+    /// use map_to_range::{MapRange, MapRangeError};
+    ///
+    /// assert_eq!(Ok(15), 5_u8.try_map_range((0, 10), (10, 20)));
+    /// assert_eq!(Err(MapRangeError::OutOfRange), 5_u8.try_map_range((10, 20), (20, 30)));
+    /// assert_eq!(Err(MapRangeError::EmptyRange), 5_u8.try_map_range((5, 5), (0, 10)));
+    /// assert_eq!(Err(MapRangeError::Overflow), 1_f64.try_map_range((0., 1.), (-f64::MAX, f64::MAX)));
+    /// ```
+    ///
+    /// The error implements `core::error::Error`, so it can be propagated with `?` into
+    /// boxed or `anyhow` errors in `std` callers.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the value or a range end is `NaN` or infinite, the value is outside
+    /// of `from_range`, `from_range` is empty, or the result does not fit into `Self`.
+    fn try_map_range(
+        &self,
+        from_range: (Self, Self),
+        to_range: (Self, Self),
+    ) -> Result<Self, MapRangeError> {
+        let all_finite = [self, &from_range.0, &from_range.1, &to_range.0, &to_range.1]
+            .iter()
+            .all(|value| value.is_finite_mr());
+        if !all_finite {
+            return Err(MapRangeError::NotFinite);
+        }
+        if *self < from_range.0 || *self > from_range.1 {
+            return Err(MapRangeError::OutOfRange);
+        }
+        if from_range.0 == from_range.1 {
+            return Err(MapRangeError::EmptyRange);
+        }
+        self.map_range(from_range, to_range)
+            .ok_or(MapRangeError::Overflow)
+    }
+    /// Maps the value like `try_map_range`, and also returns the intermediate values.
+    ///
+    /// ```
+    /// use map_to_range::{MapRange, MapRangeError};
+    ///
+    /// let trace = 1030_u16.map_range_traced((0, 1023), (0, 100));
+    /// assert_eq!(Err(MapRangeError::OutOfRange), trace.result);
+    /// assert!(trace.position.is_some_and(|position| position > 1.));
+    ///
+    /// let trace = 0.3_f64.map_range_traced((0., 1.), (0., 50.));
+    /// assert_eq!(Some(15.), trace.unrounded);
+    /// ```
+    fn map_range_traced(
+        &self,
+        from_range: (Self, Self),
+        to_range: (Self, Self),
+    ) -> trace::MapTrace<Self> {
+        let steps = || {
+            let value = self.checked_f64_cast()?;
+            let from = (
+                from_range.0.checked_f64_cast()?,
+                from_range.1.checked_f64_cast()?,
+            );
+            let to = (
+                to_range.0.checked_f64_cast()?,
+                to_range.1.checked_f64_cast()?,
+            );
+            let finite = |x: f64| x.is_finite().then_some(x);
+            let position = finite((value - from.0) / (from.1 - from.0));
+            let unrounded = finite(to.0 + (value - from.0) * (to.1 - to.0) / (from.1 - from.0));
+            Some((position, unrounded))
+        };
+        let (position, unrounded) = steps().unwrap_or_default();
+        trace::MapTrace {
+            position,
+            unrounded,
+            result: self.try_map_range(from_range, to_range),
+        }
+    }
+    /// Maps the value like `map_range_uncasted`, but without any checks.
+    ///
+    /// This is for hot loops over ranges that were validated beforehand. Debug builds still
+    /// assert that the value is inside of `from_range`.
+    ///
+    /// ```
+    /// use map_to_range::MapRange;
+    ///
+    /// // SAFETY: the value is inside the non-empty from range, and no step overflows.
+    /// assert_eq!(15, unsafe { 5_u8.map_range_unchecked((0, 10), (10, 20)) });
+    /// ```
+    ///
+    /// # Safety
+    ///
+    /// The value must be inside of `from_range`, `from_range` must not be empty,
+    /// no value or range end may be `NaN` or infinite,
+    /// and no intermediate step of `map_range_uncasted` may overflow `Self`.
+    /// For integers, this includes `(value - from.0) * (to.1 - to.0)`.
+    #[must_use]
+    unsafe fn map_range_unchecked(&self, from_range: (Self, Self), to_range: (Self, Self)) -> Self {
+        debug_assert!(from_range.0 <= *self && *self <= from_range.1);
+        // SAFETY: the caller guarantees that none of these steps fail.
+        unsafe {
+            let diff_self_from = self.checked_sub_mr(from_range.0).unwrap_unchecked();
+            let diff_to = to_range.1.checked_sub_mr(to_range.0).unwrap_unchecked();
+            let diff_from = from_range.1.checked_sub_mr(from_range.0).unwrap_unchecked();
+            let product = diff_self_from.checked_mul_mr(diff_to).unwrap_unchecked();
+            let quotient = product.checked_div_mr(diff_from).unwrap_unchecked();
+            to_range.0.checked_add_mr(quotient).unwrap_unchecked()
+        }
+    }
+    /// Maps the value over the given ranges, handling values outside of `from_range` by `policy`.
+    ///
+    /// See [`OutOfRangePolicy`](policy::OutOfRangePolicy) for the available policies.
+    /// Returns `None` if `from_range` is empty or reversed, or the result does not fit into `Self`.
+    fn map_range_with_policy(
+        &self,
+        from_range: (Self, Self),
+        to_range: (Self, Self),
+        policy: policy::OutOfRangePolicy,
+    ) -> Option<Self> {
+        let from_range = (
+            from_range.0.checked_f64_cast()?,
+            from_range.1.checked_f64_cast()?,
+        );
+        let to_range = (
+            to_range.0.checked_f64_cast()?,
+            to_range.1.checked_f64_cast()?,
+        );
+        let mapped = policy::map_f64(self.checked_f64_cast()?, from_range, to_range, policy)?;
+        Self::checked_cast_back(mapped)
+    }
+    /// Maps the value over the given ranges, wrapping values outside of `from_range` around first.
+    ///
+    /// The range is treated as half open, so `from_range.1` wraps around to `from_range.0`,
+    /// which suits angles, phases and looping timelines.
+    ///
+    /// ```
+    /// use map_to_range::MapRange;
+    ///
+    /// assert_eq!(Some(10), 370_i16.map_range_wrapped((0, 360), (0, 360)));
+    /// assert_eq!(Some(350), (-10_i16).map_range_wrapped((0, 360), (0, 360)));
+    /// assert_eq!(Some(0.25), 1.25_f32.map_range_wrapped((0., 1.), (0., 1.)));
+    /// ```
+    ///
+    /// Returns `None` if `from_range` is empty or reversed.
+    fn map_range_wrapped(&self, from_range: (Self, Self), to_range: (Self, Self)) -> Option<Self> {
+        self.map_range_with_policy(from_range, to_range, policy::OutOfRangePolicy::Wrap)
+    }
+    /// Maps the value over the given ranges, reflecting values outside of `from_range` back into it.
+    ///
+    /// Values move back and forth between the ends like a triangle wave, which suits scanning
+    /// effects and ping-pong animations.
+    ///
+    /// ```
+    /// use map_to_range::MapRange;
+    ///
+    /// assert_eq!(Some(90), 11_u8.map_range_mirrored((0, 10), (0, 100)));
+    /// assert_eq!(Some(10), (-1_i8).map_range_mirrored((0, 10), (0, 100)));
+    /// ```
+    ///
+    /// Returns `None` if `from_range` is empty or reversed.
+    fn map_range_mirrored(&self, from_range: (Self, Self), to_range: (Self, Self)) -> Option<Self> {
+        self.map_range_with_policy(from_range, to_range, policy::OutOfRangePolicy::Mirror)
+    }
+    /// Maps the value over the given ranges like `map_range`, falling back to `default` if that fails.
+    ///
+    /// ```
+    /// use map_to_range::MapRange;
+    ///
+    /// assert_eq!(50, 5_u8.map_range_or((0, 10), (0, 100), 0));
+    /// assert_eq!(0, 11_u8.map_range_or((0, 10), (0, 100), 0));
+    /// ```
+    #[must_use]
+    fn map_range_or(
+        &self,
+        from_range: (Self, Self),
+        to_range: (Self, Self),
+        default: Self,
+    ) -> Self {
+        self.map_range(from_range, to_range).unwrap_or(default)
+    }
+    /// Maps the value over the given ranges, clamping values outside of `from_range` first.
+    ///
+    /// If the mapping still fails, because the value is `NaN` or `from_range` is empty or
+    /// reversed, this returns `to_range.0`.
+    ///
+    /// ```
+    /// use map_to_range::MapRange;
+    ///
+    /// assert_eq!(100, 11_u8.map_range_or_clamp((0, 10), (0, 100)));
+    /// assert_eq!(0., f32::NAN.map_range_or_clamp((0., 1.), (0., 100.)));
+    /// ```
+    #[must_use]
+    fn map_range_or_clamp(&self, from_range: (Self, Self), to_range: (Self, Self)) -> Self {
+        self.map_range_with_policy(from_range, to_range, policy::OutOfRangePolicy::Clamp)
+            .unwrap_or(to_range.0)
+    }
+    /// Maps the value over the given ranges like `map_range`, with an explicit contract for the ranges.
+    ///
+    /// `NaN` and infinite values are rejected by both validations.
+    ///
+    /// ```
+    /// use map_to_range::{validation::Validation, MapRange};
+    ///
+    /// assert_eq!(None, 2_u8.map_range_validated((10, 0), (0, 100), Validation::Strict));
+    /// assert_eq!(Some(80), 2_u8.map_range_validated((10, 0), (0, 100), Validation::Lenient));
+    /// assert_eq!(None, 5_u8.map_range_validated((5, 5), (7, 9), Validation::Strict));
+    /// assert_eq!(Some(7), 5_u8.map_range_validated((5, 5), (7, 9), Validation::Lenient));
+    /// ```
+    fn map_range_validated(
+        &self,
+        from_range: (Self, Self),
+        to_range: (Self, Self),
+        validation: validation::Validation,
+    ) -> Option<Self> {
+        match validation {
+            validation::Validation::Strict => {
+                if from_range.0 >= from_range.1 || to_range.0 > to_range.1 {
+                    return None;
+                }
+                self.map_range(from_range, to_range)
+            }
+            validation::Validation::Lenient => {
+                let (from_range, to_range) = if from_range.0 > from_range.1 {
+                    ((from_range.1, from_range.0), (to_range.1, to_range.0))
+                } else {
+                    (from_range, to_range)
+                };
+                if from_range.0 == from_range.1 && *self == from_range.0 && self.is_finite_mr() {
+                    return to_range.0.is_finite_mr().then_some(to_range.0);
+                }
+                self.map_range(from_range, to_range)
+            }
+        }
+    }
+    /// Maps the value over the given ranges like `map_range`, but accepts values up to `epsilon`
+    /// outside of `from_range`, treating them as the nearest end of the range.
+    ///
+    /// Accumulated rounding, like the last frame of an animation ending at `10.000000000000002`
+    /// instead of `10`, then still maps to the end of `to_range`.
+    ///
+    /// ```
+    /// use map_to_range::MapRange;
+    ///
+    /// let t = 0.1 + 0.2;
+    /// assert_eq!(None, t.map_range((0., 0.3), (0., 100.)));
+    /// assert_eq!(Some(100.), t.map_range_with_epsilon((0., 0.3), (0., 100.), 1e-9));
+    /// ```
+    fn map_range_with_epsilon(
+        &self,
+        from_range: (Self, Self),
+        to_range: (Self, Self),
+        epsilon: Self,
+    ) -> Option<Self> {
+        let value = if *self < from_range.0 && from_range.0.checked_sub_mr(*self)? <= epsilon {
+            from_range.0
+        } else if *self > from_range.1 && self.checked_sub_mr(from_range.1)? <= epsilon {
+            from_range.1
+        } else {
+            *self
+        };
+        value.map_range(from_range, to_range)
+    }
+    /// Multiplies the value by `factor`, for mappings that are only a scale.
+    ///
+    /// Returns `None` if the result overflows or is not finite.
+    ///
+    /// ```
+    /// use map_to_range::MapRange;
+    ///
+    /// assert_eq!(Some(200), 20_u8.scale_by(10));
+    /// assert_eq!(None, 30_u8.scale_by(10));
+    /// assert_eq!(Some(-1.5), 0.5_f32.scale_by(-3.));
+    /// ```
+    fn scale_by(&self, factor: Self) -> Option<Self> {
+        self.checked_mul_mr(factor)
+    }
+    /// Adds `delta` to the value, for mappings that are only an offset.
+    ///
+    /// Returns `None` if the result overflows or is not finite.
+    ///
+    /// ```
+    /// use map_to_range::MapRange;
+    ///
+    /// assert_eq!(Some(-5), 15_i8.offset_by(-20));
+    /// assert_eq!(None, 250_u8.offset_by(10));
+    /// ```
+    fn offset_by(&self, delta: Self) -> Option<Self> {
+        self.checked_add_mr(delta)
+    }
+    /// Maps the value over the given ranges like `map_range`, but panics instead of returning `None`.
+    ///
+    /// Meant for tests and prototypes, where a clear panic is more useful than `Option` handling.
+    ///
+    /// ```
+    /// use map_to_range::MapRange;
+    ///
+    /// assert_eq!(15, 5_u8.map_range_or_panic((0, 10), (10, 20)));
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics with the value, both ranges and the reason if the mapping fails.
+    #[cfg(feature = "std")]
+    #[allow(clippy::panic)]
+    #[must_use]
+    #[track_caller]
+    fn map_range_or_panic(&self, from_range: (Self, Self), to_range: (Self, Self)) -> Self
+    where
+        Self: core::fmt::Debug,
+    {
+        match self.try_map_range(from_range, to_range) {
+            Ok(result) => result,
+            Err(error) => {
+                panic!("cannot map {self:?} from {from_range:?} to {to_range:?}: {error}")
+            }
+        }
+    }
     /// Maps the value over the given ranges.
     ///
     /// The `inputvalue` must be inside the `from_range`,
@@ -53,7 +448,10 @@ pub trait MapRange:
     /// possible unexpected results. To be safe, just call `map_range`. That will handle the
     /// casting for you and ensures, that you get correct results.
     fn map_range_uncasted(&self, from_range: (Self, Self), to_range: (Self, Self)) -> Option<Self> {
-        if *self < from_range.0 || *self > from_range.1 {
+        let all_finite = [self, &from_range.0, &from_range.1, &to_range.0, &to_range.1]
+            .iter()
+            .all(|value| value.is_finite_mr());
+        if !all_finite || *self < from_range.0 || *self > from_range.1 {
             return None;
         }
 
@@ -70,7 +468,14 @@ pub trait MapRange:
 /// This exists to fit different primitives in the `MapRange` trait.
 trait CheckedNumberCastsToFloat: Sized {
     fn checked_f64_cast(&self) -> Option<f64>;
+    /// Casts back, rejecting `NaN` and values outside of `Self`.
     fn checked_cast_back(other: f64) -> Option<Self>;
+    /// Only floats can be `NaN` or infinite.
+    fn is_finite_mr(&self) -> bool {
+        true
+    }
+    /// Floats keep the fraction of a result, integers truncate it when casting back.
+    const IS_FLOAT: bool = false;
 }
 /// Wrapper for arithmetics on primitives.
 /// This exists to fit different primitives in the `MapRange` trait
@@ -81,75 +486,55 @@ trait CheckedNumberArithmetics: Sized {
     fn checked_div_mr(&self, other: Self) -> Option<Self>;
 }
 
+/// Float arithmetic overflows to infinity instead of wrapping, so a step fails exactly
+/// when its result is not finite.
+fn finite<F: CheckedNumberCastsToFloat>(result: F) -> Option<F> {
+    result.is_finite_mr().then_some(result)
+}
+
 impl MapRange for f32 {}
 #[rustfmt::skip]
 impl CheckedNumberCastsToFloat for f32 {
+    const IS_FLOAT: bool = true;
     fn checked_f64_cast(&self) -> Option<f64> { Some(*self as f64) }
+    fn is_finite_mr(&self) -> bool { self.is_finite() }
     fn checked_cast_back(other: f64) -> Option<Self> {
-        if other > f32::MAX as f64 || other < f32::MIN as f64 {
+        if other.is_nan() || other > f32::MAX as f64 || other < f32::MIN as f64 {
             return None;
         }
         Some(other as f32)
     }
 }
+#[rustfmt::skip]
 impl CheckedNumberArithmetics for f32 {
-    fn checked_add_mr(&self, other: Self) -> Option<Self> {
-        if Self::MAX - self <= other || Self::MAX - other <= *self {
-            None
-        } else {
-            Some(self + other)
-        }
-    }
-    fn checked_sub_mr(&self, other: Self) -> Option<Self> {
-        Some(self - other)
-    }
-    fn checked_mul_mr(&self, other: Self) -> Option<Self> {
-        if (*self != 0. || other != 0.)
-            && ((Self::MAX / self) <= other && (Self::MAX / other) <= *self)
-        {
-            None
-        } else {
-            Some(*self * other)
-        }
-    }
+    fn checked_add_mr(&self, other: Self) -> Option<Self> { finite(self + other) }
+    fn checked_sub_mr(&self, other: Self) -> Option<Self> { finite(self - other) }
+    fn checked_mul_mr(&self, other: Self) -> Option<Self> { finite(self * other) }
     fn checked_div_mr(&self, other: Self) -> Option<Self> {
         if other == 0. {
             return None;
         }
-        Some(self / other)
+        finite(self / other)
     }
 }
 impl MapRange for f64 {}
 #[rustfmt::skip]
 impl CheckedNumberCastsToFloat for f64 {
+    const IS_FLOAT: bool = true;
     fn checked_f64_cast(&self) -> Option<f64> { Some(*self) }
-    fn checked_cast_back(other: f64) -> Option<Self> { Some(other) }
+    fn is_finite_mr(&self) -> bool { self.is_finite() }
+    fn checked_cast_back(other: f64) -> Option<Self> { (!other.is_nan()).then_some(other) }
 }
+#[rustfmt::skip]
 impl CheckedNumberArithmetics for f64 {
-    fn checked_add_mr(&self, other: Self) -> Option<Self> {
-        if Self::MAX - self <= other || Self::MAX - other <= *self {
-            None
-        } else {
-            Some(self + other)
-        }
-    }
-    fn checked_sub_mr(&self, other: Self) -> Option<Self> {
-        Some(self - other)
-    }
-    fn checked_mul_mr(&self, other: Self) -> Option<Self> {
-        if (*self != 0. || other != 0.)
-            && ((Self::MAX / self) <= other && (Self::MAX / other) <= *self)
-        {
-            None
-        } else {
-            Some(*self * other)
-        }
-    }
+    fn checked_add_mr(&self, other: Self) -> Option<Self> { finite(self + other) }
+    fn checked_sub_mr(&self, other: Self) -> Option<Self> { finite(self - other) }
+    fn checked_mul_mr(&self, other: Self) -> Option<Self> { finite(self * other) }
     fn checked_div_mr(&self, other: Self) -> Option<Self> {
         if other == 0. {
             return None;
         }
-        Some(self / other)
+        finite(self / other)
     }
 }
 impl MapRange for u8 {}
@@ -157,7 +542,7 @@ impl CheckedNumberCastsToFloat for u8 {
     #[rustfmt::skip]
     fn checked_f64_cast(&self) -> Option<f64> { Some((*self) as f64) }
     fn checked_cast_back(other: f64) -> Option<Self> {
-        if other > u8::MAX as f64 || other < u8::MIN as f64 {
+        if other.is_nan() || other > u8::MAX as f64 || other < u8::MIN as f64 {
             return None;
         }
         Some(other as u8)
@@ -176,7 +561,7 @@ impl CheckedNumberCastsToFloat for u16 {
         Some(*self as f64)
     }
     fn checked_cast_back(other: f64) -> Option<Self> {
-        if other > u16::MAX as f64 || other < u16::MIN as f64 {
+        if other.is_nan() || other > u16::MAX as f64 || other < u16::MIN as f64 {
             return None;
         }
         Some(other as u16)
@@ -195,7 +580,7 @@ impl CheckedNumberCastsToFloat for u32 {
         Some(*self as f64)
     }
     fn checked_cast_back(other: f64) -> Option<Self> {
-        if other > u32::MAX as f64 || other < u32::MIN as f64 {
+        if other.is_nan() || other > u32::MAX as f64 || other < u32::MIN as f64 {
             return None;
         }
         Some(other as u32)
@@ -214,7 +599,7 @@ impl CheckedNumberCastsToFloat for u64 {
         Some(*self as f64)
     }
     fn checked_cast_back(other: f64) -> Option<Self> {
-        if other > u64::MAX as f64 || other < u64::MIN as f64 {
+        if other.is_nan() || other > u64::MAX as f64 || other < u64::MIN as f64 {
             return None;
         }
         Some(other as u64)
@@ -233,7 +618,7 @@ impl CheckedNumberCastsToFloat for usize {
         Some(*self as f64)
     }
     fn checked_cast_back(other: f64) -> Option<Self> {
-        if other > usize::MAX as f64 || other < usize::MIN as f64 {
+        if other.is_nan() || other > usize::MAX as f64 || other < usize::MIN as f64 {
             return None;
         }
         Some(other as usize)
@@ -252,7 +637,7 @@ impl CheckedNumberCastsToFloat for i8 {
         Some(*self as f64)
     }
     fn checked_cast_back(other: f64) -> Option<Self> {
-        if other > i8::MAX as f64 || other < i8::MIN as f64 {
+        if other.is_nan() || other > i8::MAX as f64 || other < i8::MIN as f64 {
             return None;
         }
         Some(other as i8)
@@ -271,7 +656,7 @@ impl CheckedNumberCastsToFloat for i16 {
         Some(*self as f64)
     }
     fn checked_cast_back(other: f64) -> Option<Self> {
-        if other > i16::MAX as f64 || other < i16::MIN as f64 {
+        if other.is_nan() || other > i16::MAX as f64 || other < i16::MIN as f64 {
             return None;
         }
         Some(other as i16)
@@ -290,7 +675,7 @@ impl CheckedNumberCastsToFloat for i32 {
         Some(*self as f64)
     }
     fn checked_cast_back(other: f64) -> Option<Self> {
-        if other > i32::MAX as f64 || other < i32::MIN as f64 {
+        if other.is_nan() || other > i32::MAX as f64 || other < i32::MIN as f64 {
             return None;
         }
         Some(other as i32)
@@ -309,7 +694,7 @@ impl CheckedNumberCastsToFloat for i64 {
         Some(*self as f64)
     }
     fn checked_cast_back(other: f64) -> Option<Self> {
-        if other > i64::MAX as f64 || other < i64::MIN as f64 {
+        if other.is_nan() || other > i64::MAX as f64 || other < i64::MIN as f64 {
             return None;
         }
         Some(other as i64)
@@ -328,7 +713,7 @@ impl CheckedNumberCastsToFloat for isize {
         Some(*self as f64)
     }
     fn checked_cast_back(other: f64) -> Option<Self> {
-        if other > isize::MAX as f64 || other < isize::MIN as f64 {
+        if other.is_nan() || other > isize::MAX as f64 || other < isize::MIN as f64 {
             return None;
         }
         Some(other as isize)
@@ -381,4 +766,202 @@ mod tests {
         assert_eq!(Some(15), u8::checked_cast_back(15_f64));
         assert_eq!(Some(15.), f64::checked_cast_back(15_f64));
     }
+    #[test]
+    #[cfg(feature = "std")]
+    #[should_panic(
+        expected = "cannot map 11 from (0, 10) to (0, 100): value is outside of the range it is mapped from"
+    )]
+    fn test_map_range_or_panic() {
+        assert_eq!(50, 5_u8.map_range_or_panic((0, 10), (0, 100)));
+        let _ = 11_u8.map_range_or_panic((0, 10), (0, 100));
+    }
+    #[test]
+    fn test_not_finite() {
+        assert_eq!(None, f64::NAN.map_range((0., 1.), (0., 1.)));
+        assert_eq!(None, 0.5_f32.map_range((0., f32::INFINITY), (0., 1.)));
+        assert_eq!(
+            None,
+            0.5_f64.map_range_uncasted((0., 1.), (f64::NEG_INFINITY, 1.))
+        );
+        assert_eq!(None, u8::checked_cast_back(f64::NAN));
+        assert_eq!(None, i64::checked_cast_back(f64::NAN));
+        assert_eq!(None, f32::checked_cast_back(f64::NAN));
+        assert_eq!(
+            Err(MapRangeError::NotFinite),
+            0.5_f64.try_map_range((0., 1.), (0., f64::NAN))
+        );
+    }
+    #[test]
+    #[rustfmt::skip]
+    fn test_float_arithmetics() {
+        assert_eq!(Some(f64::MAX), (f64::MAX - 1.).checked_add_mr(1.));
+        assert_eq!(Some(f64::MAX), f64::MAX.checked_add_mr(-0.));
+        assert_eq!(Some(0.), f64::MAX.checked_add_mr(-f64::MAX));
+        assert_eq!(Some(-4.), (-2_f64).checked_add_mr(-2.));
+        assert_eq!(None, f64::MAX.checked_add_mr(f64::MAX));
+        assert_eq!(None, f64::MIN.checked_add_mr(f64::MIN));
+        assert_eq!(Some(0.), f64::MIN.checked_sub_mr(f64::MIN));
+        assert_eq!(None, f64::MAX.checked_sub_mr(f64::MIN));
+        assert_eq!(Some(6.), (-2_f64).checked_mul_mr(-3.));
+        assert_eq!(Some(-6.), (-2_f64).checked_mul_mr(3.));
+        assert_eq!(Some(f64::MAX), f64::MAX.checked_mul_mr(1.));
+        assert_eq!(Some(-f64::MAX), f64::MAX.checked_mul_mr(-1.));
+        assert_eq!(Some(0.), 0_f64.checked_mul_mr(f64::MAX));
+        assert_eq!(None, f64::MAX.checked_mul_mr(-2.));
+        assert_eq!(None, f64::MIN.checked_mul_mr(f64::MIN));
+        assert_eq!(None, 1_f64.checked_div_mr(0.));
+        assert_eq!(None, f64::MAX.checked_div_mr(0.5));
+        assert_eq!(Some(-2.), 4_f64.checked_div_mr(-2.));
+        assert_eq!(Some(f32::MAX), (f32::MAX / 2.).checked_add_mr(f32::MAX / 2.));
+        assert_eq!(None, f32::MAX.checked_mul_mr(2.));
+        assert_eq!(Some(-1.), (-0.5_f32).checked_sub_mr(0.5));
+        assert_eq!(Some(f64::MAX), 1_f64.map_range_uncasted((0., 1.), (0., f64::MAX)));
+        assert_eq!(Some(-5.), (-1_f64).map_range_uncasted((-2., 0.), (-10., 0.)));
+    }
+    #[test]
+    fn test_bit_exact_results() {
+        let mapped = 0.3_f64.map_range((0.1, 0.7), (-3.3, 9.1));
+        assert_eq!(Some(0x3fea_aaaa_aaaa_aaa8), mapped.map(f64::to_bits));
+        let mapped = 1.7_f32.map_range((-2.2, 5.3), (0.01, 1e7));
+        assert_eq!(Some(0x4a9e_b100), mapped.map(f32::to_bits));
+        assert_eq!(0x4024_ddd5_ec46_2485, math::exp(2.345).to_bits());
+        assert_eq!(0x4000_66f4_0b3f_cc43, math::ln(7.77).to_bits());
+        assert_eq!(0x3ffb_b67a_e858_4caa, math::sqrt(3.).to_bits());
+        let eased = easing::Easing::SmootherStep.apply(0.3);
+        assert_eq!(0x3fc4_dfce_3150_dae5, eased.to_bits());
+    }
+    /// Calls the checked mappings with every combination of `edges`, which must not panic.
+    fn map_all_combinations<T: MapRange>(edges: &[T]) {
+        use policy::OutOfRangePolicy::{Clamp, Extrapolate, Mirror, Reject, Wrap};
+        for &value in edges {
+            for from_range in edges
+                .iter()
+                .flat_map(|&a| edges.iter().map(move |&b| (a, b)))
+            {
+                for to_range in edges
+                    .iter()
+                    .flat_map(|&a| edges.iter().map(move |&b| (a, b)))
+                {
+                    let _ = value.map_range(from_range, to_range);
+                    let _ = value.map_range_uncasted(from_range, to_range);
+                    let _ = value.try_map_range(from_range, to_range);
+                    let _ = value.map_range_traced(from_range, to_range);
+                    let _ = value.map_range_or_clamp(from_range, to_range);
+                    for policy in [Reject, Clamp, Wrap, Mirror, Extrapolate] {
+                        let _ = value.map_range_with_policy(from_range, to_range, policy);
+                    }
+                    let _ = mapper::RangeMapper::new(from_range, to_range).map(|m| m.map(value));
+                }
+            }
+        }
+    }
+    #[test]
+    fn test_extreme_inputs_do_not_panic() {
+        map_all_combinations(&[u8::MIN, 1, u8::MAX - 1, u8::MAX]);
+        map_all_combinations(&[i8::MIN, -1, 0, 1, i8::MAX]);
+        map_all_combinations(&[u64::MIN, 1, u64::MAX / 2, u64::MAX]);
+        map_all_combinations(&[i64::MIN, -1, 0, i64::MAX - 1, i64::MAX]);
+        map_all_combinations(&[isize::MIN, 0, isize::MAX]);
+        map_all_combinations(&[f32::MIN, -0., f32::MIN_POSITIVE, f32::MAX, f32::NAN]);
+        map_all_combinations(&[
+            f64::NEG_INFINITY,
+            f64::MIN,
+            -1e-310,
+            0.,
+            f64::EPSILON,
+            f64::MAX,
+            f64::INFINITY,
+            f64::NAN,
+        ]);
+    }
+    #[test]
+    fn test_map_range_unchecked() {
+        for value in -100_i32..=100 {
+            let checked = value.map_range_uncasted((-100, 100), (0, 1000));
+            // SAFETY: the value is inside of the range and all steps fit into i32.
+            let unchecked = unsafe { value.map_range_unchecked((-100, 100), (0, 1000)) };
+            assert_eq!(checked, Some(unchecked));
+        }
+        // SAFETY: as above.
+        let unchecked = unsafe { 0.25_f32.map_range_unchecked((0., 1.), (-1., 1.)) };
+        assert_eq!(Some(-0.5), Some(unchecked));
+    }
+    #[test]
+    #[rustfmt::skip]
+    fn test_map_range_validated() {
+        use validation::Validation::{Lenient, Strict};
+        assert_eq!(Some(25), 5_i8.map_range_validated((0, 10), (0, 50), Strict));
+        assert_eq!(None,     5_i8.map_range_validated((0, 10), (50, 0), Strict));
+        assert_eq!(Some(25), 5_i8.map_range_validated((0, 10), (50, 0), Lenient));
+        assert_eq!(Some(40), 8_i8.map_range_validated((10, 0), (50, 0), Lenient));
+        assert_eq!(None,     11_i8.map_range_validated((10, 0), (50, 0), Lenient));
+        assert_eq!(None,     4_i8.map_range_validated((5, 5), (7, 9), Lenient));
+        assert_eq!(None,     f32::NAN.map_range_validated((0., 0.), (7., 9.), Lenient));
+    }
+    #[test]
+    fn test_map_range_with_epsilon() {
+        let end = 10. + 2. * f64::EPSILON * 10.;
+        assert_eq!(None, end.map_range((0., 10.), (0., 1.)));
+        assert_eq!(
+            Some(1.),
+            end.map_range_with_epsilon((0., 10.), (0., 1.), 1e-9)
+        );
+        assert_eq!(
+            Some(0.),
+            (-1e-10_f32).map_range_with_epsilon((0., 10.), (0., 1.), 1e-9)
+        );
+        assert_eq!(
+            None,
+            (-1e-8_f32).map_range_with_epsilon((0., 10.), (0., 1.), 1e-9)
+        );
+        assert_eq!(Some(20), 11_u8.map_range_with_epsilon((0, 10), (0, 20), 1));
+    }
+    #[test]
+    fn test_map_range_traced() {
+        let trace = 5_u8.map_range_traced((5, 5), (0, 10));
+        assert_eq!((None, None), (trace.position, trace.unrounded));
+        assert_eq!(Err(MapRangeError::EmptyRange), trace.result);
+        let trace = 250_u8.map_range_traced((0, 10), (0, 10));
+        assert_eq!(Some(250.), trace.unrounded);
+        assert_eq!(Err(MapRangeError::OutOfRange), trace.result);
+        let trace = 3_i8.map_range_traced((0, 4), (0, -100));
+        assert_eq!(Some(0.75), trace.position);
+        assert_eq!(Some(-75.), trace.unrounded);
+        assert_eq!(Ok(-75), trace.result);
+        let trace = f32::NAN.map_range_traced((0., 1.), (0., 1.));
+        assert_eq!(Err(MapRangeError::NotFinite), trace.result);
+        assert_eq!(None, trace.position);
+    }
+    #[test]
+    fn test_map_range_or() {
+        assert_eq!(7, 3_i32.map_range_or((5, 5), (0, 10), 7));
+        assert_eq!(255, 300_u16.map_range_or_clamp((0, 256), (0, 255)));
+        assert_eq!(0, (-5_i16).map_range_or_clamp((0, 10), (0, 100)));
+        assert_eq!(100, 5_i16.map_range_or_clamp((10, 0), (100, 0)));
+    }
+    #[test]
+    fn test_scale_and_offset() {
+        assert_eq!(Some(-128), (-64_i8).scale_by(2));
+        assert_eq!(None, i8::MIN.scale_by(-1));
+        assert_eq!(None, f64::MAX.scale_by(2.));
+        assert_eq!(None, f32::NAN.offset_by(1.));
+        assert_eq!(None, f64::INFINITY.offset_by(-1.));
+        assert_eq!(Some(u64::MAX), (u64::MAX - 1).offset_by(1));
+    }
+    #[test]
+    fn test_try_map_range() {
+        assert_eq!(Ok(150), 50_i16.try_map_range((0, 100), (100, 200)));
+        assert_eq!(
+            Err(MapRangeError::OutOfRange),
+            (-1_i16).try_map_range((0, 100), (100, 200))
+        );
+        assert_eq!(
+            Err(MapRangeError::EmptyRange),
+            3_u32.try_map_range((3, 3), (0, 1))
+        );
+        assert_eq!(
+            Err(MapRangeError::Overflow),
+            0.5_f64.try_map_range((0., 1.), (-f64::MAX, f64::MAX))
+        );
+    }
 }
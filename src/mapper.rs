@@ -0,0 +1,677 @@
+//! Reusable mappers between two ranges, configured through a builder.
+//!
+//! ```
+//! use map_to_range::mapper::Mapper;
+//!
+//! let mapper = Mapper::from(0..=1023).to(0..=255).clamped().rounded().build().unwrap();
+//! assert_eq!(Some(128), mapper.map(512));
+//! assert_eq!(Some(255), mapper.map(5000));
+//! ```
+//!
+//! The builder tracks its progress in its type, so a mapper without a target range
+//! cannot be built:
+//!
+//! ```compile_fail
+//! use map_to_range::mapper::Mapper;
+//!
+//! let mapper = Mapper::from(0..=1023).clamped().build();
+//! ```
+
+use core::marker::PhantomData;
+use core::ops::RangeInclusive;
+
+use crate::lerp::Lerp;
+use crate::policy::{self, OutOfRangePolicy};
+use crate::taper::Taper;
+use crate::{math, MapRange};
+
+/// The entry point of the mapper builder.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Mapper;
+
+impl Mapper {
+    /// Starts building a mapper for values inside of `range`.
+    #[allow(clippy::should_implement_trait)]
+    #[must_use]
+    pub fn from<T: MapRange>(range: RangeInclusive<T>) -> MapperBuilder<T, NeedsTarget> {
+        let (lo, hi) = range.into_inner();
+        MapperBuilder {
+            config: RangeMapper {
+                from_range: (lo, hi),
+                to_range: (lo, hi),
+                policy: OutOfRangePolicy::Reject,
+                taper: Taper::Linear,
+                rounded: false,
+                precomputed: Precomputed::default(),
+            },
+            state: PhantomData,
+        }
+    }
+}
+
+/// Builder state before the target range is known.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NeedsTarget {}
+/// Builder state once both ranges are known.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Ready {}
+
+/// Builds a [`RangeMapper`], see [`Mapper::from`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MapperBuilder<T, State> {
+    config: RangeMapper<T>,
+    state: PhantomData<State>,
+}
+
+impl<T: MapRange> MapperBuilder<T, NeedsTarget> {
+    /// Sets the range values are mapped into.
+    #[must_use]
+    pub fn to(self, range: RangeInclusive<T>) -> MapperBuilder<T, Ready> {
+        let (lo, hi) = range.into_inner();
+        MapperBuilder {
+            config: RangeMapper {
+                to_range: (lo, hi),
+                ..self.config
+            },
+            state: PhantomData,
+        }
+    }
+}
+
+impl<T: MapRange> MapperBuilder<T, Ready> {
+    /// Sets how values outside of the from range are handled. They are rejected by default.
+    #[must_use]
+    pub fn policy(mut self, policy: OutOfRangePolicy) -> Self {
+        self.config.policy = policy;
+        self
+    }
+    /// Clamps values outside of the from range instead of rejecting them.
+    ///
+    /// This is a shorthand for `policy(OutOfRangePolicy::Clamp)`.
+    #[must_use]
+    pub fn clamped(self) -> Self {
+        self.policy(OutOfRangePolicy::Clamp)
+    }
+    /// Shapes the mapping with a potentiometer taper. It is linear by default.
+    ///
+    /// Tapers other than [`Taper::Linear`] clamp their input, so with them
+    /// [`OutOfRangePolicy::Extrapolate`] behaves like [`OutOfRangePolicy::Clamp`].
+    #[must_use]
+    pub fn taper(mut self, taper: Taper) -> Self {
+        self.config.taper = taper;
+        self
+    }
+    /// Rounds results to the nearest whole number, halves away from zero, instead of truncating.
+    #[must_use]
+    pub fn rounded(mut self) -> Self {
+        self.config.rounded = true;
+        self
+    }
+    /// Finishes the mapper.
+    ///
+    /// Returns `None` if the from range is empty or reversed, or a range is not finite.
+    #[must_use]
+    pub fn build(mut self) -> Option<RangeMapper<T>> {
+        let (lo, hi) = self.config.from_range;
+        if lo >= hi {
+            return None;
+        }
+        self.config.precomputed = Precomputed::new(self.config.from_range, self.config.to_range)?;
+        Some(self.config)
+    }
+}
+
+/// The ranges of a [`RangeMapper`] as `f64`, with the division done once up front,
+/// so mapping a value is a single multiply-add.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+struct Precomputed {
+    from: (f64, f64),
+    to: (f64, f64),
+    /// The width of the target range divided by the width of the from range.
+    scale: f64,
+    /// The ranges as whole numbers, for integer types.
+    whole: Option<WholeRanges>,
+}
+
+/// The start of the target range and the widths of both ranges of an integer mapper.
+///
+/// A multiply-add with the rounded scale can land just below a whole number and truncate one
+/// step low, so integer results are checked against these. Whole numbers below 2^53 multiply
+/// exactly in `f64`, so the check needs no division and no wide integers.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+struct WholeRanges {
+    to_start: f64,
+    from_width: f64,
+    to_width: f64,
+}
+
+/// Below this, sums of whole `f64` numbers and their doubles stay exact.
+const EXACT_LIMIT: f64 = (1_u64 << 51) as f64;
+
+impl WholeRanges {
+    /// The exact result for the whole number `offset` into the from range, truncated towards zero
+    /// or rounded half away from zero. `guess` is the result of the multiply-add.
+    ///
+    /// Returns `None` if an intermediate step does not fit into `i128`.
+    fn map(self, offset: f64, guess: f64, rounded: bool) -> Option<f64> {
+        // Mapping is (to_start * from_width + offset * to_width) / from_width.
+        let start = self.to_start * self.from_width;
+        let scaled = offset * self.to_width;
+        let numerator = start + scaled;
+        let exact = [start, scaled, numerator, self.from_width]
+            .iter()
+            .all(|x| x.abs() < EXACT_LIMIT);
+        if !exact {
+            return self.map_wide(offset, rounded);
+        }
+        let (dividend, divisor, guess) = if rounded {
+            let doubled = 2. * self.from_width;
+            (
+                2. * numerator.abs() + self.from_width,
+                doubled,
+                guess.abs() + 0.5,
+            )
+        } else {
+            (numerator.abs(), self.from_width, guess.abs())
+        };
+        let mut quotient = math::floor(guess);
+        for _ in 0..2 {
+            if quotient * divisor > dividend {
+                quotient -= 1.;
+            } else if (quotient + 1.) * divisor <= dividend {
+                quotient += 1.;
+            } else {
+                return Some(if numerator < 0. { -quotient } else { quotient });
+            }
+        }
+        self.map_wide(offset, rounded)
+    }
+    /// Like [`WholeRanges::map`], with `i128` arithmetic and a division for huge ranges.
+    fn map_wide(self, offset: f64, rounded: bool) -> Option<f64> {
+        let from_width = self.from_width as i128;
+        let numerator = (self.to_start as i128)
+            .checked_mul(from_width)?
+            .checked_add((offset as i128).checked_mul(self.to_width as i128)?)?;
+        let magnitude = numerator.checked_abs()?;
+        let quotient = if rounded {
+            magnitude
+                .checked_mul(2)?
+                .checked_add(from_width)?
+                .checked_div(from_width.checked_mul(2)?)?
+        } else {
+            magnitude.checked_div(from_width)?
+        };
+        let quotient = quotient as f64;
+        Some(if numerator < 0 { -quotient } else { quotient })
+    }
+}
+
+impl Precomputed {
+    fn new<T: MapRange>(from_range: (T, T), to_range: (T, T)) -> Option<Self> {
+        let from = (
+            from_range.0.checked_f64_cast()?,
+            from_range.1.checked_f64_cast()?,
+        );
+        let to = (
+            to_range.0.checked_f64_cast()?,
+            to_range.1.checked_f64_cast()?,
+        );
+        let scale = (to.1 - to.0) / (from.1 - from.0);
+        let finite = [from.0, from.1, to.0, to.1, scale]
+            .iter()
+            .all(|x| x.is_finite());
+        // Integers convert to whole `f64` values, which `as i128` keeps exactly.
+        let whole = if T::IS_FLOAT {
+            None
+        } else {
+            // Beyond 2^53 these round, like the `f64` steps of `map_range` do.
+            Some(WholeRanges {
+                to_start: to.0,
+                from_width: from.1 - from.0,
+                to_width: to.1 - to.0,
+            })
+        };
+        finite.then_some(Self {
+            from,
+            to,
+            scale,
+            whole,
+        })
+    }
+    /// Applies `policy` to `value`, returning its offset from the start of the from range.
+    fn offset(&self, value: f64, policy: OutOfRangePolicy) -> Option<f64> {
+        let value = match policy {
+            OutOfRangePolicy::Reject => {
+                if !(self.from.0..=self.from.1).contains(&value) {
+                    return None;
+                }
+                value
+            }
+            OutOfRangePolicy::Clamp => value.clamp(self.from.0, self.from.1),
+            OutOfRangePolicy::Wrap => policy::wrap(value, self.from),
+            OutOfRangePolicy::Mirror => policy::mirror(value, self.from),
+            OutOfRangePolicy::Extrapolate => value,
+        };
+        Some(value - self.from.0)
+    }
+}
+
+/// A mapping between two ranges, with its options.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RangeMapper<T> {
+    from_range: (T, T),
+    to_range: (T, T),
+    policy: OutOfRangePolicy,
+    taper: Taper,
+    rounded: bool,
+    precomputed: Precomputed,
+}
+
+impl<T: MapRange> RangeMapper<T> {
+    /// A plain mapper from `from_range` to `to_range`, like [`MapRange::map_range`].
+    ///
+    /// Returns `None` if `from_range` is empty or reversed, or a range is not finite.
+    #[must_use]
+    pub fn new(from_range: (T, T), to_range: (T, T)) -> Option<Self> {
+        Mapper::from(from_range.0..=from_range.1)
+            .to(to_range.0..=to_range.1)
+            .build()
+    }
+    /// Maps `value` into the target range.
+    ///
+    /// Returns `None` if `value` is outside of the from range and the policy rejects it,
+    /// or the result does not fit into `T`.
+    #[must_use]
+    pub fn map(&self, value: T) -> Option<T> {
+        let Precomputed {
+            from,
+            to,
+            scale,
+            whole,
+        } = self.precomputed;
+        let offset = self
+            .precomputed
+            .offset(value.checked_f64_cast()?, self.policy)?;
+        if self.taper != Taper::Linear {
+            // Tapers are expensive anyway, so this divides to hit the ends exactly.
+            let t = self.taper.apply(offset / (from.1 - from.0));
+            return Self::cast_back(self.round(to.0.lerp(to.1, t)));
+        }
+        let mapped = to.0 + offset * scale;
+        if let Some(exact) = whole.and_then(|whole| whole.map(offset, mapped, self.rounded)) {
+            return Self::cast_back(exact);
+        }
+        Self::cast_back(self.round(mapped))
+    }
+    /// Converts `mapped` back into `T`, rejecting results that overflowed while extrapolating.
+    fn cast_back(mapped: f64) -> Option<T> {
+        T::checked_cast_back(mapped.is_finite().then_some(mapped)?)
+    }
+    /// Rounds `mapped` if the mapper is rounded.
+    fn round(&self, mapped: f64) -> f64 {
+        if self.rounded {
+            math::round_half_away(mapped)
+        } else {
+            mapped
+        }
+    }
+    /// The range values are mapped from.
+    #[must_use]
+    pub fn from_range(&self) -> (T, T) {
+        self.from_range
+    }
+    /// The range values are mapped into.
+    #[must_use]
+    pub fn to_range(&self) -> (T, T) {
+        self.to_range
+    }
+    /// How values outside of the from range are handled.
+    #[must_use]
+    pub fn policy(&self) -> OutOfRangePolicy {
+        self.policy
+    }
+    /// The taper shaping the mapping.
+    #[must_use]
+    pub fn taper(&self) -> Taper {
+        self.taper
+    }
+    /// Returns `true` if results are rounded instead of truncated.
+    #[must_use]
+    pub fn is_rounded(&self) -> bool {
+        self.rounded
+    }
+}
+
+/// The reason a [`RoundTrip`] could not be built.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RoundTripError<T> {
+    /// The from range is empty or reversed, or the target range is empty.
+    InvalidRange,
+    /// The target range is narrower than the from range, so some values cannot be told apart
+    /// after mapping. `first` is the lowest of them.
+    Lossy { first: T },
+    /// The ranges are of a float type, where the mappers round to whole numbers,
+    /// so only whole numbers could survive the round trip.
+    Float,
+}
+
+/// A pair of rounding mappers, where mapping a whole number and mapping it back
+/// always returns the original value.
+///
+/// This holds exactly when the target range is at least as wide as the from range.
+/// Values are stepped through as whole numbers, so only integer types are accepted.
+///
+/// ```
+/// use map_to_range::mapper::{RoundTrip, RoundTripError};
+///
+/// let percent_to_raw = RoundTrip::new((0_u16, 100), (0, 1023)).unwrap();
+/// for percent in 0..=100 {
+///     let raw = percent_to_raw.map(percent).unwrap();
+///     assert_eq!(Some(percent), percent_to_raw.unmap(raw));
+/// }
+/// assert_eq!(Err(RoundTripError::Lossy { first: 1 }), RoundTrip::new((0_u16, 1023), (0, 100)));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RoundTrip<T> {
+    forward: RangeMapper<T>,
+    backward: RangeMapper<T>,
+}
+
+impl<T: MapRange> RoundTrip<T> {
+    /// Builds the pair of mappers between `from_range` and `to_range`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`RoundTripError::Lossy`] with the lowest value that does not survive the round trip,
+    /// [`RoundTripError::InvalidRange`] if a range is empty or `from_range` is reversed,
+    /// or [`RoundTripError::Float`] for `f32` and `f64`.
+    pub fn new(from_range: (T, T), to_range: (T, T)) -> Result<Self, RoundTripError<T>> {
+        if T::IS_FLOAT {
+            return Err(RoundTripError::Float);
+        }
+        // The backward mapper needs an ascending from range, so reversed ranges are flipped.
+        let (backward_from, backward_to) = if to_range.0 < to_range.1 {
+            (to_range, from_range)
+        } else {
+            ((to_range.1, to_range.0), (from_range.1, from_range.0))
+        };
+        let forward = Mapper::from(from_range.0..=from_range.1)
+            .to(to_range.0..=to_range.1)
+            .rounded()
+            .build();
+        let backward = Mapper::from(backward_from.0..=backward_from.1)
+            .to(backward_to.0..=backward_to.1)
+            .rounded()
+            .build();
+        let (Some(forward), Some(backward)) = (forward, backward) else {
+            return Err(RoundTripError::InvalidRange);
+        };
+        let round_trip = Self { forward, backward };
+        match round_trip.failures().next() {
+            Some(first) => Err(RoundTripError::Lossy { first }),
+            None => Ok(round_trip),
+        }
+    }
+    /// Maps `value` from the from range into the target range.
+    #[must_use]
+    pub fn map(&self, value: T) -> Option<T> {
+        self.forward.map(value)
+    }
+    /// Maps `value` from the target range back into the from range.
+    #[must_use]
+    pub fn unmap(&self, value: T) -> Option<T> {
+        self.backward.map(value)
+    }
+    /// The whole numbers of the from range that do not survive the round trip, lowest first.
+    ///
+    /// This is empty whenever the target range is at least as wide as the from range,
+    /// without stepping through the values.
+    #[must_use]
+    pub fn failures(&self) -> RoundTripFailures<T> {
+        let (lo, hi) = self.forward.from_range;
+        let (to_lo, to_hi) = self.backward.from_range;
+        let lossless = lo
+            .checked_f64_cast()
+            .zip(hi.checked_f64_cast())
+            .zip(to_lo.checked_f64_cast().zip(to_hi.checked_f64_cast()))
+            .is_some_and(|((lo, hi), (to_lo, to_hi))| to_hi - to_lo >= hi - lo);
+        RoundTripFailures {
+            round_trip: *self,
+            next: (!lossless).then_some(lo),
+        }
+    }
+}
+
+/// The values that do not survive a round trip, see [`RoundTrip::failures`].
+#[derive(Debug, Clone)]
+pub struct RoundTripFailures<T> {
+    round_trip: RoundTrip<T>,
+    next: Option<T>,
+}
+
+impl<T: MapRange> Iterator for RoundTripFailures<T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        let one = T::checked_cast_back(1.)?;
+        let hi = self.round_trip.forward.from_range.1;
+        while let Some(value) = self.next {
+            self.next = if value < hi {
+                value.checked_add_mr(one)
+            } else {
+                None
+            };
+            let back = self
+                .round_trip
+                .map(value)
+                .and_then(|mapped| self.round_trip.unmap(mapped));
+            if back.and_then(|back| back.checked_f64_cast()) != value.checked_f64_cast() {
+                return Some(value);
+            }
+        }
+        None
+    }
+}
+
+/// `N` independent mappers, applied together to frames of `N` channels.
+///
+/// ```
+/// use map_to_range::mapper::MultiMapper;
+///
+/// // An RGBW fixture whose white channel only goes up to 200.
+/// let fixture = MultiMapper::new([
+///     ((0, 255), (0, 255)),
+///     ((0, 255), (0, 255)),
+///     ((0, 255), (0, 255)),
+///     ((0, 255), (0, 200)),
+/// ])
+/// .unwrap();
+/// assert_eq!(Some([255, 0, 10, 200]), fixture.map([255, 0, 10, 255]));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MultiMapper<T, const N: usize> {
+    channels: [RangeMapper<T>; N],
+}
+
+impl<T: MapRange, const N: usize> MultiMapper<T, N> {
+    /// Plain mappers for every channel, from its `(from_range, to_range)` pair.
+    ///
+    /// Returns `None` if any from range is empty or reversed, or there are no channels.
+    #[must_use]
+    pub fn new(ranges: [((T, T), (T, T)); N]) -> Option<Self> {
+        let (from_range, to_range) = ranges.first().copied()?;
+        let mut channels = [RangeMapper::new(from_range, to_range)?; N];
+        for (channel, (from_range, to_range)) in channels.iter_mut().zip(ranges) {
+            *channel = RangeMapper::new(from_range, to_range)?;
+        }
+        Some(Self { channels })
+    }
+    /// Uses a configured mapper for every channel, e.g. to clamp or round some channels only.
+    #[must_use]
+    pub const fn from_mappers(channels: [RangeMapper<T>; N]) -> Self {
+        Self { channels }
+    }
+    /// Maps every channel of `frame` with its own mapper.
+    ///
+    /// Returns `None` if any channel fails to map.
+    #[must_use]
+    pub fn map(&self, mut frame: [T; N]) -> Option<[T; N]> {
+        for (value, channel) in frame.iter_mut().zip(&self.channels) {
+            *value = channel.map(*value)?;
+        }
+        Some(frame)
+    }
+    /// The mappers of the channels.
+    #[must_use]
+    pub const fn channels(&self) -> &[RangeMapper<T>; N] {
+        &self.channels
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_plain_matches_map_range() {
+        let mapper = RangeMapper::new((-50_i16, 50), (0, 999));
+        for value in -50..=50 {
+            assert_eq!(
+                value.map_range((-50, 50), (0, 999)),
+                mapper.and_then(|m| m.map(value))
+            );
+        }
+        assert_eq!(None, mapper.and_then(|m| m.map(51)));
+        assert_eq!(None, RangeMapper::new((1., 1.), (0., 1.)));
+    }
+    #[test]
+    fn test_precomputed_scale_matches_map_range() {
+        for from_width in 1_u16..=300 {
+            for to_range in [(0, from_width), (0, 255), (1000, 7), (3, 60_000), (9, 9)] {
+                let from_range = (17, 17 + from_width);
+                let truncated = RangeMapper::new(from_range, to_range);
+                let rounded = Mapper::from(from_range.0..=from_range.1)
+                    .to(to_range.0..=to_range.1)
+                    .rounded()
+                    .build();
+                for value in from_range.0..=from_range.1 {
+                    let expected = f64::from(value)
+                        .map_range(
+                            (f64::from(from_range.0), f64::from(from_range.1)),
+                            (f64::from(to_range.0), f64::from(to_range.1)),
+                        )
+                        .map(math::round_half_away);
+                    assert_eq!(
+                        value.map_range(from_range, to_range),
+                        truncated.and_then(|m| m.map(value))
+                    );
+                    assert_eq!(
+                        expected.and_then(crate::CheckedNumberCastsToFloat::checked_cast_back),
+                        rounded.and_then(|m| m.map(value))
+                    );
+                }
+            }
+        }
+        for from_width in 1_u8..=255 {
+            let identity = RangeMapper::new((0, from_width), (0, from_width));
+            for value in 0..=from_width {
+                assert_eq!(Some(value), identity.and_then(|m| m.map(value)));
+            }
+        }
+        let mapper = RangeMapper::new((i32::MIN, i32::MAX), (i32::MAX, i32::MIN));
+        assert_eq!(Some(i32::MIN), mapper.and_then(|m| m.map(i32::MAX)));
+        assert_eq!(Some(-1), mapper.and_then(|m| m.map(0)));
+        assert_eq!(None, RangeMapper::new((0., f64::INFINITY), (0., 1.)));
+        assert_eq!(None, RangeMapper::new((0., 1.), (f64::NEG_INFINITY, 1.)));
+        assert_eq!(None, RangeMapper::new((0., 1e-320), (0., 1e300)));
+    }
+    #[test]
+    fn test_options() {
+        let rounded = Mapper::from(0_u8..=3).to(0..=10).rounded().build();
+        assert_eq!(Some(3), rounded.and_then(|m| m.map(1)));
+        assert_eq!(Some(7), rounded.and_then(|m| m.map(2)));
+        assert_eq!(None, rounded.and_then(|m| m.map(4)));
+        let clamped = Mapper::from(-1_f32..=1.).to(0. ..=1.).clamped().build();
+        assert_eq!(Some(0.), clamped.and_then(|m| m.map(-3.)));
+        assert_eq!(Some(1.), clamped.and_then(|m| m.map(3.)));
+        assert!(clamped.is_some_and(|m| m.policy() == OutOfRangePolicy::Clamp && !m.is_rounded()));
+        let wrapped = Mapper::from(0_u16..=360)
+            .to(0..=3600)
+            .policy(OutOfRangePolicy::Wrap)
+            .build();
+        assert_eq!(Some(100), wrapped.and_then(|m| m.map(370)));
+        let extrapolated = Mapper::from(0_f64..=1.)
+            .to(0. ..=10.)
+            .policy(OutOfRangePolicy::Extrapolate)
+            .build();
+        assert_eq!(Some(Some(20.)), extrapolated.map(|m| m.map(2.)));
+        for huge in [f64::INFINITY, f64::MAX, f64::NAN] {
+            assert_eq!(
+                huge.map_range_with_policy((0., 1.), (0., 10.), OutOfRangePolicy::Extrapolate),
+                extrapolated.and_then(|m| m.map(huge))
+            );
+            assert_eq!(None, extrapolated.and_then(|m| m.map(huge)));
+        }
+        let tapered = Mapper::from(0_u8..=200)
+            .to(0..=255)
+            .taper(Taper::ReverseAudio)
+            .rounded()
+            .build();
+        assert_eq!(Some(172), tapered.and_then(|m| m.map(50)));
+        assert_eq!(None, tapered.and_then(|m| m.map(201)));
+        assert!(tapered.is_some_and(|m| m.taper() == Taper::ReverseAudio));
+        let reversed = (3, 1);
+        assert_eq!(
+            None,
+            Mapper::from(reversed.0..=reversed.1).to(0..=1).build()
+        );
+    }
+    #[test]
+    fn test_round_trip() {
+        let round_trip = RoundTrip::new((-10_i8, 10), (100, -100));
+        assert!(round_trip.is_ok());
+        for value in -10..=10 {
+            let back = round_trip
+                .ok()
+                .and_then(|r| r.map(value).and_then(|mapped| r.unmap(mapped)));
+            assert_eq!(Some(value), back);
+        }
+        let lossy = Mapper::from(0_u8..=10)
+            .to(0..=4)
+            .rounded()
+            .build()
+            .zip(Mapper::from(0_u8..=4).to(0..=10).rounded().build())
+            .map(|(forward, backward)| RoundTrip { forward, backward });
+        let mut failures = lossy.iter().flat_map(RoundTrip::failures);
+        assert_eq!(Some(1), failures.next());
+        assert_eq!(Some(2), failures.next());
+        assert_eq!(Some(4), failures.next());
+        assert_eq!(
+            Err(RoundTripError::Lossy { first: 1 }),
+            RoundTrip::new((0_u8, 10), (0, 4))
+        );
+        assert_eq!(
+            Err(RoundTripError::InvalidRange),
+            RoundTrip::new((0_u8, 10), (4, 4))
+        );
+        assert_eq!(
+            Err(RoundTripError::Float),
+            RoundTrip::new((0_f32, 1.), (0., 100.))
+        );
+    }
+    #[test]
+    fn test_multi_mapper() {
+        let clamped = Mapper::from(-1_f32..=1.).to(0. ..=100.).clamped().build();
+        let strict = RangeMapper::new((-1_f32, 1.), (100., 0.));
+        let joystick = clamped
+            .zip(strict)
+            .map(|(x, y)| MultiMapper::from_mappers([x, y]));
+        assert_eq!(Some([100., 75.]), joystick.and_then(|j| j.map([3., -0.5])));
+        assert_eq!(None, joystick.and_then(|j| j.map([0., 3.])));
+        assert_eq!(None, MultiMapper::<u8, 0>::new([]));
+        assert_eq!(
+            None,
+            MultiMapper::new([((0_u8, 1), (0, 1)), ((1, 1), (0, 1))])
+        );
+    }
+}
@@ -0,0 +1,215 @@
+//! Floating point functions that are missing from `core`.
+//!
+//! These are small software implementations so the crate stays `no_std` without pulling in `libm`.
+
+/// Square root of `x`, `NaN` for negative inputs.
+pub(crate) fn sqrt(x: f64) -> f64 {
+    if x.is_nan() || x < 0. {
+        return f64::NAN;
+    }
+    if x == 0. || x.is_infinite() {
+        return x;
+    }
+    // Halving the exponent bits gives a guess close enough for newton to converge quickly.
+    let mut guess = f64::from_bits((x.to_bits() >> 1) + (1023 << 51));
+    for _ in 0..64 {
+        let next = 0.5 * (guess + x / guess);
+        if next.to_bits() == guess.to_bits() {
+            break;
+        }
+        guess = next;
+    }
+    guess
+}
+
+/// Natural logarithm of `x`, `NaN` for negative inputs.
+pub(crate) fn ln(x: f64) -> f64 {
+    if x.is_nan() || x < 0. {
+        return f64::NAN;
+    }
+    if x == 0. {
+        return f64::NEG_INFINITY;
+    }
+    if x.is_infinite() {
+        return x;
+    }
+    // Split x into mantissa and exponent, normalizing subnormals first.
+    let (x, mut exponent) = if x.is_normal() {
+        (x, 0)
+    } else {
+        (x * TWO_POW_54, -54)
+    };
+    let bits = x.to_bits();
+    exponent += ((bits >> 52) & 0x7ff) as i32 - 1023;
+    let mut mantissa = f64::from_bits((bits & 0x000f_ffff_ffff_ffff) | 0x3ff0_0000_0000_0000);
+    if mantissa > core::f64::consts::SQRT_2 {
+        mantissa /= 2.;
+        exponent += 1;
+    }
+    // ln(m) = 2 * atanh(s) with s = (m - 1) / (m + 1), and |s| < 0.172 here.
+    let s = (mantissa - 1.) / (mantissa + 1.);
+    let s2 = s * s;
+    let mut term = s;
+    let mut series = 0.;
+    for n in 0..16 {
+        series += term / (2 * n + 1) as f64;
+        term *= s2;
+    }
+    2. * series + exponent as f64 * core::f64::consts::LN_2
+}
+
+/// `e` raised to the power of `x`.
+pub(crate) fn exp(x: f64) -> f64 {
+    if x.is_nan() {
+        return x;
+    }
+    if x > 709.8 {
+        return f64::INFINITY;
+    }
+    if x < -745.2 {
+        return 0.;
+    }
+    // e^x = 2^k * e^r with |r| <= ln(2) / 2.
+    let k = round_half_away(x / core::f64::consts::LN_2);
+    let r = x - k * core::f64::consts::LN_2;
+    let mut term = 1.;
+    let mut series = 1.;
+    for n in 1..20 {
+        term *= r / n as f64;
+        series += term;
+    }
+    // Scale in two steps so that neither factor over- or underflows on its own.
+    let k = k as i32;
+    let half = k / 2;
+    series * power_of_two(half) * power_of_two(k - half)
+}
+
+/// The largest integer less than or equal to `x`.
+pub(crate) fn floor(x: f64) -> f64 {
+    // Beyond 2^52 every float is an integer already.
+    if !x.is_finite() || x.abs() >= 4_503_599_627_370_496. {
+        return x;
+    }
+    let truncated = x as i64 as f64;
+    if truncated > x {
+        truncated - 1.
+    } else {
+        truncated
+    }
+}
+
+/// `2^exponent` for exponents inside the normal range of `f64`.
+fn power_of_two(exponent: i32) -> f64 {
+    f64::from_bits(((exponent + 1023) as u64) << 52)
+}
+
+/// Rounds half away from zero, without the `std` only `f64::round`.
+pub(crate) fn round_half_away(x: f64) -> f64 {
+    let truncated = (x.abs() + 0.5) as i64 as f64;
+    if x < 0. {
+        -truncated
+    } else {
+        truncated
+    }
+}
+
+const TWO_POW_54: f64 = 18_014_398_509_481_984.;
+
+/// Solves the linear system `matrix * x = rhs` with gaussian elimination and partial pivoting.
+///
+/// Returns `None` if the matrix is singular.
+pub(crate) fn solve<const N: usize>(
+    mut matrix: [[f64; N]; N],
+    mut rhs: [f64; N],
+) -> Option<[f64; N]> {
+    for column in 0..N {
+        let magnitude = |row: &usize| {
+            matrix
+                .get(*row)
+                .and_then(|row| row.get(column))
+                .map_or(0., |value| value.abs())
+        };
+        let pivot = (column..N).max_by(|a, b| magnitude(a).total_cmp(&magnitude(b)))?;
+        matrix.swap(column, pivot);
+        rhs.swap(column, pivot);
+        let (upper, lower) = matrix.split_at_mut(column + 1);
+        let (upper_rhs, lower_rhs) = rhs.split_at_mut(column + 1);
+        let pivot_row = upper.last()?;
+        let pivot_rhs = *upper_rhs.last()?;
+        let pivot_value = *pivot_row.get(column)?;
+        if !pivot_value.is_normal() {
+            return None;
+        }
+        for (row, value) in lower.iter_mut().zip(lower_rhs) {
+            let factor = *row.get(column)? / pivot_value;
+            for (entry, pivot_entry) in row.iter_mut().zip(pivot_row) {
+                *entry -= factor * pivot_entry;
+            }
+            *value -= factor * pivot_rhs;
+        }
+    }
+    let mut solution = [0.; N];
+    for row in (0..N).rev() {
+        let coefficients = matrix.get(row)?;
+        let known: f64 = coefficients
+            .iter()
+            .zip(&solution)
+            .skip(row + 1)
+            .map(|(coefficient, x)| coefficient * x)
+            .sum();
+        *solution.get_mut(row)? = (rhs.get(row)? - known) / coefficients.get(row)?;
+    }
+    Some(solution)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sqrt() {
+        assert_eq!(Some(3.), Some(sqrt(9.)));
+        assert_eq!(Some(0.), Some(sqrt(0.)));
+        assert_eq!(Some(f64::INFINITY), Some(sqrt(f64::INFINITY)));
+        assert!(sqrt(-1.).is_nan());
+        assert!((sqrt(2.) - core::f64::consts::SQRT_2).abs() < 1e-15);
+        assert!((sqrt(1e-300) - 1e-150).abs() < 1e-160);
+    }
+    #[test]
+    fn test_ln_exp() {
+        use core::f64::consts::{E, LN_10};
+        assert!((ln(E) - 1.).abs() < 1e-15);
+        assert!((ln(10.) - LN_10).abs() < 1e-15);
+        assert!((ln(1e-310) + 713.801_378_828_154_2).abs() < 1e-12);
+        assert_eq!(Some(0.), Some(ln(1.)));
+        assert_eq!(Some(f64::NEG_INFINITY), Some(ln(0.)));
+        assert!(ln(-1.).is_nan());
+        assert!((exp(1.) - E).abs() < 1e-15);
+        assert!((exp(-3.5) - 0.030_197_383_422_318_5).abs() < 1e-17);
+        assert!((exp(700.) / 1.014_232_054_735_004_9e304 - 1.).abs() < 1e-13);
+        assert_eq!(Some(1.), Some(exp(0.)));
+        assert_eq!(Some(f64::INFINITY), Some(exp(1000.)));
+        assert_eq!(Some(0.), Some(exp(-1000.)));
+        for x in [-20., -1., 0.3, 5., 42.] {
+            assert!((ln(exp(x)) - x).abs() < 1e-13);
+        }
+    }
+    #[test]
+    fn test_floor() {
+        assert_eq!(Some(1.), Some(floor(1.5)));
+        assert_eq!(Some(-2.), Some(floor(-1.5)));
+        assert_eq!(Some(-3.), Some(floor(-3.)));
+        assert_eq!(Some(1e300), Some(floor(1e300)));
+        assert!(floor(f64::NAN).is_nan());
+    }
+    #[test]
+    fn test_solve() {
+        let matrix = [[0., 2., 1.], [1., 1., 1.], [2., 1., 3.]];
+        let solution = solve(matrix, [5., 4., 7.]);
+        let expected = [1., 2., 1.];
+        assert!(
+            solution.is_some_and(|x| x.iter().zip(expected).all(|(x, e)| (x - e).abs() < 1e-12))
+        );
+        assert_eq!(None, solve([[1., 2.], [2., 4.]], [1., 2.]));
+    }
+}
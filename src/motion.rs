@@ -0,0 +1,422 @@
+//! Motion profiles that move a value from one position to another within physical limits.
+//!
+//! Unlike a plain tween, the duration follows from the limits, and the velocity and
+//! acceleration never exceed them. This suits stepper and servo moves.
+
+use crate::{curve::Curve, math};
+
+/// A trapezoidal velocity profile: accelerating, cruising at the maximum velocity, then decelerating.
+///
+/// Short moves never reach the maximum velocity, and the cruise phase disappears.
+///
+/// ```
+/// use map_to_range::motion::Trapezoidal;
+///
+/// let mv = Trapezoidal::new(0., 100., 50., 100.).unwrap();
+/// assert_eq!(2.5, mv.duration());
+/// assert_eq!(12.5, mv.position(0.5));
+/// assert_eq!(50., mv.velocity(1.));
+/// assert_eq!(100., mv.position(10.));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Trapezoidal {
+    start: f64,
+    end: f64,
+    peak_velocity: f64,
+    acceleration: f64,
+    ramp_time: f64,
+    cruise_time: f64,
+}
+
+impl Trapezoidal {
+    /// A move from `start` to `end` under the given limits, which are magnitudes in units per second.
+    ///
+    /// Returns `None` if a limit is not positive or a value is not finite.
+    #[must_use]
+    pub fn new(start: f64, end: f64, max_velocity: f64, max_acceleration: f64) -> Option<Self> {
+        let limits_valid = max_velocity > 0. && max_acceleration > 0.;
+        let finite = [start, end, max_velocity, max_acceleration]
+            .iter()
+            .all(|value| value.is_finite());
+        if !limits_valid || !finite {
+            return None;
+        }
+        let distance = (end - start).abs();
+        let ramp_distance = max_velocity * max_velocity / (2. * max_acceleration);
+        let (peak_velocity, cruise_time) = if 2. * ramp_distance > distance {
+            (math::sqrt(distance * max_acceleration), 0.)
+        } else {
+            (max_velocity, (distance - 2. * ramp_distance) / max_velocity)
+        };
+        Some(Self {
+            start,
+            end,
+            peak_velocity,
+            acceleration: max_acceleration,
+            ramp_time: peak_velocity / max_acceleration,
+            cruise_time,
+        })
+    }
+    /// The total time of the move in seconds.
+    #[must_use]
+    pub fn duration(&self) -> f64 {
+        2. * self.ramp_time + self.cruise_time
+    }
+    /// The position `t` seconds after the start. Before the start and after the end, the
+    /// position rests at `start` and `end`.
+    #[must_use]
+    pub fn position(&self, t: f64) -> f64 {
+        let travelled = self.distance_at(t);
+        if self.end < self.start {
+            self.start - travelled
+        } else {
+            self.start + travelled
+        }
+    }
+    /// The signed velocity `t` seconds after the start.
+    #[must_use]
+    pub fn velocity(&self, t: f64) -> f64 {
+        let speed = if t <= 0. || t >= self.duration() {
+            0.
+        } else if t < self.ramp_time {
+            self.acceleration * t
+        } else if t <= self.ramp_time + self.cruise_time {
+            self.peak_velocity
+        } else {
+            self.acceleration * (self.duration() - t)
+        };
+        self.signed(speed)
+    }
+    /// The signed acceleration `t` seconds after the start.
+    #[must_use]
+    pub fn acceleration(&self, t: f64) -> f64 {
+        let acceleration = if t <= 0. || t >= self.duration() {
+            0.
+        } else if t < self.ramp_time {
+            self.acceleration
+        } else if t <= self.ramp_time + self.cruise_time {
+            0.
+        } else {
+            -self.acceleration
+        };
+        self.signed(acceleration)
+    }
+    /// The highest velocity the move reaches, which is below the limit for short moves.
+    #[must_use]
+    pub fn peak_velocity(&self) -> f64 {
+        self.peak_velocity
+    }
+    fn distance_at(&self, t: f64) -> f64 {
+        let distance = (self.end - self.start).abs();
+        let ramp_distance = 0.5 * self.acceleration * self.ramp_time * self.ramp_time;
+        if t.is_nan() || t <= 0. {
+            0.
+        } else if t < self.ramp_time {
+            0.5 * self.acceleration * t * t
+        } else if t <= self.ramp_time + self.cruise_time {
+            ramp_distance + self.peak_velocity * (t - self.ramp_time)
+        } else if t < self.duration() {
+            let remaining = self.duration() - t;
+            distance - 0.5 * self.acceleration * remaining * remaining
+        } else {
+            distance
+        }
+    }
+    fn signed(&self, value: f64) -> f64 {
+        if self.end < self.start {
+            -value
+        } else {
+            value
+        }
+    }
+}
+
+impl Curve for Trapezoidal {
+    type Output = f64;
+    fn domain(&self) -> (f64, f64) {
+        (0., self.duration())
+    }
+    fn evaluate(&self, t: f64) -> Option<Self::Output> {
+        Some(self.position(t))
+    }
+    fn derivative_at(&self, t: f64) -> Option<f64> {
+        Some(self.velocity(t))
+    }
+}
+
+/// A jerk limited S-curve profile, in seven phases: the acceleration ramps up, holds, and ramps
+/// down again, the velocity cruises, and the same happens mirrored while decelerating.
+///
+/// The acceleration changes continuously, which avoids exciting resonances in mechanics
+/// the way the sudden acceleration steps of a [`Trapezoidal`] profile do.
+///
+/// ```
+/// use map_to_range::motion::SCurve;
+///
+/// let mv = SCurve::new(0., 100., 50., 100., 1000.).unwrap();
+/// assert!((mv.duration() - 2.6).abs() < 1e-12);
+/// assert_eq!(50., mv.position(1.3));
+/// assert_eq!(0., mv.acceleration(0.));
+/// assert_eq!(100., mv.position(10.));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SCurve {
+    start: f64,
+    end: f64,
+    jerk: f64,
+    /// The lengths of the seven phases.
+    phases: [f64; 7],
+}
+
+/// The sign of the jerk during each phase.
+const S_CURVE_JERK: [f64; 7] = [1., 0., -1., 0., -1., 0., 1.];
+
+/// The position, velocity and acceleration of a motion.
+#[derive(Clone, Copy)]
+struct MotionState {
+    position: f64,
+    velocity: f64,
+    acceleration: f64,
+}
+
+impl MotionState {
+    fn after(self, jerk: f64, t: f64) -> Self {
+        Self {
+            position: self.position
+                + self.velocity * t
+                + self.acceleration * t * t / 2.
+                + jerk * t * t * t / 6.,
+            velocity: self.velocity + self.acceleration * t + jerk * t * t / 2.,
+            acceleration: self.acceleration + jerk * t,
+        }
+    }
+}
+
+impl SCurve {
+    /// A move from `start` to `end` under the given limits, which are magnitudes in units per
+    /// second, per second squared and per second cubed.
+    ///
+    /// Returns `None` if a limit is not positive or a value is not finite.
+    #[must_use]
+    pub fn new(
+        start: f64,
+        end: f64,
+        max_velocity: f64,
+        max_acceleration: f64,
+        max_jerk: f64,
+    ) -> Option<Self> {
+        let limits_valid = max_velocity > 0. && max_acceleration > 0. && max_jerk > 0.;
+        let finite = [start, end, max_velocity, max_acceleration, max_jerk]
+            .iter()
+            .all(|value| value.is_finite());
+        if !limits_valid || !finite {
+            return None;
+        }
+        let distance = (end - start).abs();
+        // The time to accelerate from rest to `velocity`, which is symmetric, so the distance
+        // covered meanwhile is half of `velocity * time`.
+        let ramp = |velocity: f64| {
+            if velocity * max_jerk < max_acceleration * max_acceleration {
+                let jerk_time = math::sqrt(velocity / max_jerk);
+                (jerk_time, 2. * jerk_time)
+            } else {
+                let jerk_time = max_acceleration / max_jerk;
+                (jerk_time, jerk_time + velocity / max_acceleration)
+            }
+        };
+        let (jerk_time, ramp_time) = ramp(max_velocity);
+        let (velocity, cruise_time) = if max_velocity * ramp_time <= distance {
+            (
+                max_velocity,
+                (distance - max_velocity * ramp_time) / max_velocity,
+            )
+        } else {
+            // Solve `velocity * ramp_time(velocity) = distance` for the lower peak velocity.
+            let jerk_time = max_acceleration / max_jerk;
+            let reaching_max_acceleration = max_acceleration
+                * (math::sqrt(jerk_time * jerk_time + 4. * distance / max_acceleration)
+                    - jerk_time)
+                / 2.;
+            let velocity =
+                if reaching_max_acceleration * max_jerk >= max_acceleration * max_acceleration {
+                    reaching_max_acceleration
+                } else if distance > 0. {
+                    math::exp(math::ln(distance * distance * max_jerk / 4.) / 3.)
+                } else {
+                    0.
+                };
+            (velocity, 0.)
+        };
+        let (jerk_time, ramp_time) = if velocity < max_velocity {
+            ramp(velocity)
+        } else {
+            (jerk_time, ramp_time)
+        };
+        let hold_time = (ramp_time - 2. * jerk_time).max(0.);
+        let peak_acceleration = if hold_time > 0. {
+            max_acceleration
+        } else {
+            max_jerk * jerk_time
+        };
+        let jerk = if jerk_time > 0. {
+            peak_acceleration / jerk_time
+        } else {
+            0.
+        };
+        Some(Self {
+            start,
+            end,
+            jerk,
+            phases: [
+                jerk_time,
+                hold_time,
+                jerk_time,
+                cruise_time,
+                jerk_time,
+                hold_time,
+                jerk_time,
+            ],
+        })
+    }
+    /// The total time of the move in seconds.
+    #[must_use]
+    pub fn duration(&self) -> f64 {
+        self.phases.iter().sum()
+    }
+    /// The position `t` seconds after the start. Before the start and after the end, the
+    /// position rests at `start` and `end`.
+    #[must_use]
+    pub fn position(&self, t: f64) -> f64 {
+        if t >= self.duration() {
+            return self.end;
+        }
+        let travelled = self.state_at(t).position;
+        if self.end < self.start {
+            self.start - travelled
+        } else {
+            self.start + travelled
+        }
+    }
+    /// The signed velocity `t` seconds after the start.
+    #[must_use]
+    pub fn velocity(&self, t: f64) -> f64 {
+        self.signed(self.state_at(t).velocity)
+    }
+    /// The signed acceleration `t` seconds after the start.
+    #[must_use]
+    pub fn acceleration(&self, t: f64) -> f64 {
+        self.signed(self.state_at(t).acceleration)
+    }
+    fn state_at(&self, t: f64) -> MotionState {
+        let mut state = MotionState {
+            position: 0.,
+            velocity: 0.,
+            acceleration: 0.,
+        };
+        if t.is_nan() || t <= 0. || t >= self.duration() {
+            return state;
+        }
+        let mut remaining = t;
+        for (phase, sign) in self.phases.iter().zip(S_CURVE_JERK) {
+            if remaining <= *phase {
+                return state.after(sign * self.jerk, remaining);
+            }
+            state = state.after(sign * self.jerk, *phase);
+            remaining -= phase;
+        }
+        state
+    }
+    fn signed(&self, value: f64) -> f64 {
+        if self.end < self.start {
+            -value
+        } else {
+            value
+        }
+    }
+}
+
+impl Curve for SCurve {
+    type Output = f64;
+    fn domain(&self) -> (f64, f64) {
+        (0., self.duration())
+    }
+    fn evaluate(&self, t: f64) -> Option<Self::Output> {
+        Some(self.position(t))
+    }
+    fn derivative_at(&self, t: f64) -> Option<f64> {
+        Some(self.velocity(t))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_trapezoid() {
+        let mv = Trapezoidal::new(10., -10., 10., 20.);
+        assert!(mv.is_some_and(|mv| (mv.duration() - 2.5).abs() < 1e-12));
+        assert_eq!(Some(10.), mv.map(|mv| mv.position(-1.)));
+        assert_eq!(Some(-10.), mv.map(|mv| mv.position(3.)));
+        assert_eq!(Some(0.), mv.map(|mv| mv.position(1.25)));
+        assert_eq!(Some(-10.), mv.map(|mv| mv.velocity(1.)));
+        assert_eq!(Some(-20.), mv.map(|mv| mv.acceleration(0.1)));
+        assert_eq!(Some(20.), mv.map(|mv| mv.acceleration(2.4)));
+    }
+    #[test]
+    fn test_triangle() {
+        let mv = Trapezoidal::new(0., 1., 10., 4.);
+        assert_eq!(Some(2.), mv.map(|mv| mv.peak_velocity()));
+        assert_eq!(Some(1.), mv.map(|mv| mv.duration()));
+        assert_eq!(Some(0.5), mv.map(|mv| mv.position(0.5)));
+        assert_eq!(Some(0.), mv.map(|mv| mv.velocity(1.)));
+        assert_eq!(None, Trapezoidal::new(0., 1., 0., 4.));
+        assert_eq!(None, Trapezoidal::new(0., f64::NAN, 1., 4.));
+    }
+    #[test]
+    fn test_respects_limits() {
+        let mv = Trapezoidal::new(-3., 7., 2., 1.5);
+        let mut previous = -3.;
+        for step in 0..=200 {
+            let t = mv.map_or(0., |mv| mv.duration()) * f64::from(step) / 200.;
+            assert!(mv.is_some_and(|mv| mv.velocity(t).abs() <= 2.));
+            let position = mv.map_or(f64::NAN, |mv| mv.position(t));
+            assert!(position >= previous);
+            previous = position;
+        }
+        assert_eq!(Some(7.), Some(previous));
+    }
+    #[test]
+    fn test_s_curve_limits_and_end() {
+        let moves = [
+            SCurve::new(0., 100., 50., 100., 1000.),
+            SCurve::new(5., -3., 50., 100., 1000.),
+            SCurve::new(0., 0.01, 50., 100., 1000.),
+            SCurve::new(0., 30., 50., 100., 1000.),
+        ];
+        for mv in moves.iter().flatten() {
+            let steps = 1000;
+            let mut previous = mv.position(0.);
+            for step in 0..=steps {
+                let t = mv.duration() * f64::from(step) / f64::from(steps);
+                assert!(mv.velocity(t).abs() <= 50. + 1e-9);
+                assert!(mv.acceleration(t).abs() <= 100. + 1e-9);
+                let position = mv.position(t);
+                assert!(
+                    (position - previous).abs() <= 50. * mv.duration() / f64::from(steps) + 1e-9
+                );
+                previous = position;
+            }
+            let before_end = mv.state_at(mv.duration() * (1. - 1e-12));
+            assert!(before_end.velocity.abs() < 1e-6);
+            assert!((before_end.position - (mv.end - mv.start).abs()).abs() < 1e-6);
+        }
+        assert_eq!(None, SCurve::new(0., 1., 1., 1., 0.));
+    }
+    #[test]
+    fn test_s_curve_zero_distance() {
+        let mv = SCurve::new(2., 2., 1., 1., 1.);
+        assert_eq!(Some(0.), mv.map(|mv| mv.duration()));
+        assert_eq!(Some(2.), mv.map(|mv| mv.position(1.)));
+    }
+}
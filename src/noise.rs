@@ -0,0 +1,122 @@
+//! Deterministic smooth 1D noise, for organic flicker and drift effects.
+
+use crate::{easing::Easing, math, MapRange};
+
+/// A seeded 1D noise generator.
+///
+/// The same seed always produces the same noise, on every platform.
+/// Noise is smooth between the integer lattice points, so scale `x` to control
+/// how fast it changes.
+///
+/// ```
+/// use map_to_range::noise::Noise;
+///
+/// let noise = Noise::new(42);
+/// let flicker: Option<u8> = noise.map_value(1.3, (180, 255));
+/// assert!(flicker.is_some_and(|brightness| brightness >= 180));
+/// assert_eq!(noise.value(1.3), Noise::new(42).value(1.3));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Noise {
+    seed: u32,
+}
+
+impl Noise {
+    /// A noise generator with the given seed.
+    #[must_use]
+    pub const fn new(seed: u32) -> Self {
+        Self { seed }
+    }
+    /// Value noise at `x`, in `-1..=1`.
+    ///
+    /// Every lattice point gets a random value, and the values in between are smoothly
+    /// interpolated.
+    #[must_use]
+    pub fn value(&self, x: f64) -> f64 {
+        let lattice = math::floor(x);
+        let fraction = x - lattice;
+        let cell = lattice as i64;
+        let from = self.random(cell);
+        let to = self.random(cell.wrapping_add(1));
+        from + (to - from) * Easing::SmoothStep.apply(fraction)
+    }
+    /// Perlin style gradient noise at `x`, in `-1..=1`.
+    ///
+    /// Every lattice point gets a random slope and the noise is zero there,
+    /// which looks less blocky than value noise.
+    #[must_use]
+    pub fn gradient(&self, x: f64) -> f64 {
+        let lattice = math::floor(x);
+        let fraction = x - lattice;
+        let cell = lattice as i64;
+        let from = self.random(cell) * fraction;
+        let to = self.random(cell.wrapping_add(1)) * (fraction - 1.);
+        // The extremes of 1D gradient noise are ±0.5, scale them to ±1.
+        2. * (from + (to - from) * Easing::SmootherStep.apply(fraction))
+    }
+    /// [`Noise::value`] at `x`, mapped onto `to_range`.
+    ///
+    /// Returns `None` if `x` is not finite or the result does not fit into `T`.
+    #[must_use]
+    pub fn map_value<T: MapRange>(&self, x: f64, to_range: (T, T)) -> Option<T> {
+        Self::map(self.value(x), to_range)
+    }
+    /// [`Noise::gradient`] at `x`, mapped onto `to_range`.
+    ///
+    /// Returns `None` if `x` is not finite or the result does not fit into `T`.
+    #[must_use]
+    pub fn map_gradient<T: MapRange>(&self, x: f64, to_range: (T, T)) -> Option<T> {
+        Self::map(self.gradient(x), to_range)
+    }
+    fn map<T: MapRange>(noise: f64, to_range: (T, T)) -> Option<T> {
+        if noise.is_nan() {
+            return None;
+        }
+        let to_range = (
+            to_range.0.checked_f64_cast()?,
+            to_range.1.checked_f64_cast()?,
+        );
+        T::checked_cast_back(noise.clamp(-1., 1.).map_range((-1., 1.), to_range)?)
+    }
+    /// A random value in `-1..=1` for a lattice point.
+    fn random(self, cell: i64) -> f64 {
+        let mut hash = (cell as u64 ^ (self.seed as u64) << 32).wrapping_mul(0x9e37_79b9_7f4a_7c15);
+        hash ^= hash >> 31;
+        hash = hash.wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        hash ^= hash >> 27;
+        (hash >> 11) as f64 / (1_u64 << 53) as f64 * 2. - 1.
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bounds_and_determinism() {
+        let noise = Noise::new(7);
+        for step in -1000..1000 {
+            let x = step as f64 * 0.037;
+            assert!((-1. ..=1.).contains(&noise.value(x)));
+            assert!((-1. ..=1.).contains(&noise.gradient(x)));
+            assert_eq!(Some(noise.value(x)), Some(Noise::new(7).value(x)));
+        }
+        assert_ne!(Some(Noise::new(1).value(0.)), Some(Noise::new(2).value(0.)));
+    }
+    #[test]
+    fn test_smoothness() {
+        let noise = Noise::new(3);
+        for step in 0..1000 {
+            let x = step as f64 * 0.01;
+            assert!((noise.value(x + 0.001) - noise.value(x)).abs() < 0.01);
+            assert!((noise.gradient(x + 0.001) - noise.gradient(x)).abs() < 0.01);
+        }
+        assert_eq!(Some(0.), Some(noise.gradient(5.)));
+    }
+    #[test]
+    fn test_map() {
+        let noise = Noise::new(0);
+        assert!(noise.map_gradient(0.5, (0_u8, 255)).is_some());
+        assert_eq!(None, noise.map_value(f64::NAN, (0_u8, 255)));
+    }
+}
@@ -0,0 +1,208 @@
+//! Color palettes for mapping values to colors.
+//!
+//! A palette is a list of evenly spaced RGB colors, sampled by linearly interpolating between
+//! the two nearest colors. The built-in palettes are static tables, so they cost no RAM.
+
+use crate::{curve::Curve, fast8::lerp8by8, MapRange};
+
+/// Evenly spaced RGB colors with interpolated sampling.
+///
+/// ```
+/// use map_to_range::palette::Palette;
+///
+/// assert_eq!([0, 0, 0], Palette::HEAT.sample(0));
+/// assert_eq!([255, 255, 255], Palette::HEAT.sample(255));
+///
+/// // A temperature from 10°C to 40°C mapped onto the palette.
+/// let color = Palette::VIRIDIS.sample_range(25_i16, (10, 40));
+/// assert_eq!(Some([33, 145, 140]), color);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Palette<'a> {
+    colors: &'a [[u8; 3]],
+}
+
+impl<'a> Palette<'a> {
+    /// Black over red and yellow to white.
+    pub const HEAT: Palette<'static> = Palette {
+        colors: &[[0, 0, 0], [255, 0, 0], [255, 255, 0], [255, 255, 255]],
+    };
+    /// The colors of the rainbow, from red to violet.
+    pub const RAINBOW: Palette<'static> = Palette {
+        colors: &[
+            [255, 0, 0],
+            [255, 127, 0],
+            [255, 255, 0],
+            [0, 255, 0],
+            [0, 0, 255],
+            [75, 0, 130],
+            [148, 0, 211],
+        ],
+    };
+    /// Deep blue over turquoise to white foam.
+    pub const OCEAN: Palette<'static> = Palette {
+        colors: &[
+            [0, 0, 32],
+            [0, 0, 128],
+            [0, 64, 192],
+            [0, 160, 224],
+            [128, 224, 255],
+        ],
+    };
+    /// An approximation of the perceptually uniform viridis palette, from purple over teal to yellow.
+    pub const VIRIDIS: Palette<'static> = Palette {
+        colors: &[
+            [68, 1, 84],
+            [59, 82, 139],
+            [33, 145, 140],
+            [94, 201, 98],
+            [253, 231, 37],
+        ],
+    };
+    /// A custom palette of evenly spaced colors.
+    ///
+    /// Returns `None` if there are no colors.
+    #[must_use]
+    pub const fn new(colors: &'a [[u8; 3]]) -> Option<Self> {
+        if colors.is_empty() {
+            return None;
+        }
+        Some(Self { colors })
+    }
+    /// The colors of this palette.
+    #[must_use]
+    pub const fn colors(&self) -> &'a [[u8; 3]] {
+        self.colors
+    }
+    /// The color at `position`, where 0 is the first and 255 the last color.
+    #[must_use]
+    pub fn sample(&self, position: u8) -> [u8; 3] {
+        let segments = self.colors.len().saturating_sub(1) as u32;
+        let scaled = position as u32 * segments;
+        let index = (scaled / 255) as usize;
+        let fraction = ((scaled % 255) * 256 / 255) as u8;
+        let from = self.colors.get(index).copied().unwrap_or_default();
+        let to = self.colors.get(index + 1).copied().unwrap_or(from);
+        [
+            lerp8by8(from[0], to[0], fraction),
+            lerp8by8(from[1], to[1], fraction),
+            lerp8by8(from[2], to[2], fraction),
+        ]
+    }
+    /// The color for `value` inside of `from_range`, where `from_range.0` is the first and
+    /// `from_range.1` the last color.
+    ///
+    /// Returns `None` if `value` is outside of `from_range`.
+    #[must_use]
+    pub fn sample_range<T: MapRange>(&self, value: T, from_range: (T, T)) -> Option<[u8; 3]> {
+        let from_range = (
+            from_range.0.checked_f64_cast()?,
+            from_range.1.checked_f64_cast()?,
+        );
+        let position = value
+            .checked_f64_cast()?
+            .map_range(from_range, (0., 255.))?;
+        Some(self.sample((position + 0.5) as u8))
+    }
+}
+
+impl Curve for Palette<'_> {
+    type Output = [u8; 3];
+    fn domain(&self) -> (f64, f64) {
+        (0., 1.)
+    }
+    fn evaluate(&self, t: f64) -> Option<Self::Output> {
+        self.sample_range(t, (0., 1.))
+    }
+}
+
+/// An owned palette, for palettes that are loaded or edited at runtime.
+///
+/// ```
+/// use map_to_range::palette::PaletteBuf;
+///
+/// let mut palette = PaletteBuf::new(vec![[0, 0, 0]]).unwrap();
+/// palette.push([255, 0, 0]);
+/// assert_eq!([255, 0, 0], palette.palette().sample(255));
+/// ```
+#[cfg(feature = "alloc")]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PaletteBuf {
+    colors: alloc::vec::Vec<[u8; 3]>,
+}
+
+#[cfg(feature = "alloc")]
+impl PaletteBuf {
+    /// An owned palette of evenly spaced colors.
+    ///
+    /// Returns `None` if there are no colors.
+    #[must_use]
+    pub fn new(colors: alloc::vec::Vec<[u8; 3]>) -> Option<Self> {
+        (!colors.is_empty()).then_some(Self { colors })
+    }
+    /// Appends a color at the end, moving the other colors closer together.
+    pub fn push(&mut self, color: [u8; 3]) {
+        self.colors.push(color);
+    }
+    /// Borrows the colors as a [`Palette`], for sampling.
+    #[must_use]
+    pub fn palette(&self) -> Palette<'_> {
+        Palette {
+            colors: &self.colors,
+        }
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl From<Palette<'_>> for PaletteBuf {
+    fn from(palette: Palette<'_>) -> Self {
+        Self {
+            colors: palette.colors.into(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_builtin_endpoints() {
+        for palette in [
+            Palette::HEAT,
+            Palette::RAINBOW,
+            Palette::OCEAN,
+            Palette::VIRIDIS,
+        ] {
+            assert_eq!(palette.colors().first(), Some(&palette.sample(0)));
+            assert_eq!(palette.colors().last(), Some(&palette.sample(255)));
+        }
+    }
+    #[test]
+    fn test_sample() {
+        let palette = Palette::new(&[[0, 0, 0], [200, 100, 255]]);
+        assert_eq!(Some([100, 50, 128]), palette.map(|p| p.sample(128)));
+        let single = Palette::new(&[[1, 2, 3]]);
+        assert_eq!(Some([1, 2, 3]), single.map(|p| p.sample(77)));
+        assert_eq!(None, Palette::new(&[]));
+        assert_eq!(None, Palette::HEAT.sample_range(2., (0., 1.)));
+    }
+    #[test]
+    fn test_sample_into() {
+        let mut strip = [[0; 3]; 4];
+        assert_eq!(Some(()), Palette::HEAT.sample_into(&mut strip));
+        assert_eq!(
+            [[0, 0, 0], [255, 0, 0], [255, 255, 0], [255, 255, 255]],
+            strip
+        );
+    }
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn test_palette_buf() {
+        let mut palette = PaletteBuf::from(Palette::HEAT);
+        palette.push([0, 0, 255]);
+        assert_eq!(5, palette.palette().colors().len());
+        assert_eq!([0, 0, 255], palette.palette().sample(255));
+        assert_eq!(None, PaletteBuf::new(alloc::vec::Vec::new()));
+    }
+}
@@ -0,0 +1,221 @@
+//! Mapping through tables of points, linearly interpolating between them.
+//!
+//! A table is a slice of `(input, output)` points with strictly increasing inputs.
+
+use crate::lerp::Interpolation;
+use crate::MapRange;
+
+/// Looks up `x` in `table`, linearly interpolating between the two surrounding points.
+///
+/// Returns `None` if `x` lies outside of the table.
+/// The table is expected to be valid (see [`is_valid`]), otherwise the result is meaningless.
+///
+/// ```
+/// use map_to_range::piecewise::interpolate;
+///
+/// let table = [(0., 0.), (10., 100.), (20., 150.)];
+/// assert_eq!(Some(50.), interpolate(&table, 5.));
+/// assert_eq!(Some(125.), interpolate(&table, 15.));
+/// assert_eq!(None, interpolate(&table, 25.));
+/// ```
+#[must_use]
+pub fn interpolate(table: &[(f64, f64)], x: f64) -> Option<f64> {
+    let segment = table.partition_point(|(input, _)| *input < x).max(1);
+    let from = table.get(segment - 1)?;
+    let Some(to) = table.get(segment) else {
+        // Only reachable with a single point table.
+        return x.total_cmp(&from.0).is_eq().then_some(from.1);
+    };
+    x.map_range((from.0, to.0), (from.1, to.1))
+}
+
+/// Looks up `x` in `table` like [`interpolate`], producing values between points by `interpolation`.
+///
+/// ```
+/// use map_to_range::{lerp::Interpolation, piecewise::interpolate_with};
+///
+/// let presets = [(0., 1.), (4., 2.), (8., 3.)];
+/// assert_eq!(Some(1.), interpolate_with(&presets, 3.9, Interpolation::Hold));
+/// assert_eq!(Some(2.), interpolate_with(&presets, 4., Interpolation::Hold));
+/// assert_eq!(Some(3.), interpolate_with(&presets, 6., Interpolation::Nearest));
+/// ```
+#[must_use]
+pub fn interpolate_with(table: &[(f64, f64)], x: f64, interpolation: Interpolation) -> Option<f64> {
+    if interpolation == Interpolation::Linear {
+        return interpolate(table, x);
+    }
+    let segment = table.partition_point(|(input, _)| *input < x).max(1);
+    let from = table.get(segment - 1)?;
+    let Some(to) = table.get(segment) else {
+        return x.total_cmp(&from.0).is_eq().then_some(from.1);
+    };
+    let t = x.map_range((from.0, to.0), (0., 1.))?;
+    Some(interpolation.interpolate(from.1, to.1, t))
+}
+
+/// Looks up `x` in `table` like [`interpolate`], but clamps `x` into the table first.
+///
+/// Returns `None` if the table is empty or `x` is `NaN`.
+#[must_use]
+pub fn interpolate_clamped(table: &[(f64, f64)], x: f64) -> Option<f64> {
+    let first = table.first()?.0;
+    let last = table.last()?.0;
+    if x.is_nan() {
+        return None;
+    }
+    interpolate(table, x.clamp(first, last))
+}
+
+/// Returns `true` if `table` has at least one point, only finite values and strictly increasing
+/// inputs.
+#[must_use]
+pub fn is_valid(table: &[(f64, f64)]) -> bool {
+    !table.is_empty()
+        && table
+            .iter()
+            .all(|(input, output)| input.is_finite() && output.is_finite())
+        && table.windows(2).all(|pair| match pair {
+            [a, b] => a.0 < b.0,
+            _ => false,
+        })
+}
+
+/// A validated table of up to `N` points, mapping by interpolating between them.
+///
+/// Use [`Calibration::from_points`](crate::calibration::Calibration::from_points) to build one
+/// from measured points in any order.
+///
+/// ```
+/// use map_to_range::piecewise::PiecewiseLinear;
+///
+/// let table = PiecewiseLinear::<4>::new(&[(0., 0.), (10., 100.), (20., 150.)]).unwrap();
+/// assert_eq!(Some(125_u8), table.map(15_u8));
+/// assert_eq!(None, table.map::<u8, u8>(25));
+/// assert_eq!(Some(150.), table.map_clamped(25.));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PiecewiseLinear<const N: usize> {
+    points: [(f64, f64); N],
+    len: usize,
+}
+
+impl<const N: usize> PiecewiseLinear<N> {
+    /// Copies `table` into a mapper.
+    ///
+    /// Returns `None` if the table is longer than `N` or not valid, see [`is_valid`].
+    #[must_use]
+    pub fn new(table: &[(f64, f64)]) -> Option<Self> {
+        if table.len() > N || !is_valid(table) {
+            return None;
+        }
+        let mut points = [(0., 0.); N];
+        points.get_mut(..table.len())?.copy_from_slice(table);
+        Some(Self {
+            points,
+            len: table.len(),
+        })
+    }
+    /// The points of the table, with strictly increasing inputs.
+    #[must_use]
+    pub fn points(&self) -> &[(f64, f64)] {
+        self.points.get(..self.len).unwrap_or_default()
+    }
+    /// Maps `x` by interpolating between the surrounding points, like [`interpolate`].
+    ///
+    /// Returns `None` if `x` lies outside of the table.
+    #[must_use]
+    pub fn map_f64(&self, x: f64) -> Option<f64> {
+        interpolate(self.points(), x)
+    }
+    /// Maps `x` like [`map_f64`](Self::map_f64), producing values between points by
+    /// `interpolation`, see [`interpolate_with`].
+    #[must_use]
+    pub fn map_with(&self, x: f64, interpolation: Interpolation) -> Option<f64> {
+        interpolate_with(self.points(), x, interpolation)
+    }
+    /// Maps `x` like [`map_f64`](Self::map_f64), but clamps it into the table first.
+    ///
+    /// Returns `None` if `x` is `NaN`.
+    #[must_use]
+    pub fn map_clamped(&self, x: f64) -> Option<f64> {
+        interpolate_clamped(self.points(), x)
+    }
+    /// Maps `x` like [`map_f64`](Self::map_f64), casting from `T` and into `U`.
+    ///
+    /// Returns `None` if `x` lies outside of the table or the result does not fit into `U`.
+    #[must_use]
+    pub fn map<T: MapRange, U: MapRange>(&self, x: T) -> Option<U> {
+        U::checked_cast_back(self.map_f64(x.checked_f64_cast()?)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_interpolate() {
+        let table = [(-1., 10.), (0., 0.), (4., 2.)];
+        assert_eq!(Some(10.), interpolate(&table, -1.));
+        assert_eq!(Some(5.), interpolate(&table, -0.5));
+        assert_eq!(Some(0.), interpolate(&table, 0.));
+        assert_eq!(Some(1.), interpolate(&table, 2.));
+        assert_eq!(Some(2.), interpolate(&table, 4.));
+        assert_eq!(None, interpolate(&table, -2.));
+        assert_eq!(None, interpolate(&table, 5.));
+        assert_eq!(None, interpolate(&[], 0.));
+        assert_eq!(Some(3.), interpolate(&[(1., 3.)], 1.));
+        assert_eq!(None, interpolate(&[(1., 3.)], 2.));
+    }
+    #[test]
+    fn test_interpolate_with() {
+        let table = [(0., 0.), (2., 10.), (3., 30.)];
+        assert_eq!(Some(0.), interpolate_with(&table, 1.9, Interpolation::Hold));
+        assert_eq!(Some(30.), interpolate_with(&table, 3., Interpolation::Hold));
+        assert_eq!(
+            Some(0.),
+            interpolate_with(&table, 0.9, Interpolation::Nearest)
+        );
+        assert_eq!(
+            Some(10.),
+            interpolate_with(&table, 1., Interpolation::Nearest)
+        );
+        assert_eq!(
+            Some(20.),
+            interpolate_with(&table, 2.5, Interpolation::Linear)
+        );
+        assert_eq!(None, interpolate_with(&table, -1., Interpolation::Hold));
+        assert_eq!(None, interpolate_with(&table, 3.5, Interpolation::Nearest));
+        assert_eq!(
+            Some(4.),
+            interpolate_with(&[(1., 4.)], 1., Interpolation::Hold)
+        );
+    }
+    #[test]
+    fn test_interpolate_clamped() {
+        let table = [(0., 0.), (4., 2.)];
+        assert_eq!(Some(0.), interpolate_clamped(&table, -10.));
+        assert_eq!(Some(2.), interpolate_clamped(&table, 10.));
+        assert_eq!(None, interpolate_clamped(&table, f64::NAN));
+        assert_eq!(None, interpolate_clamped(&[], 1.));
+    }
+    #[test]
+    fn test_is_valid() {
+        assert!(is_valid(&[(0., 0.)]));
+        assert!(is_valid(&[(0., 0.), (1., -5.)]));
+        assert!(!is_valid(&[]));
+        assert!(!is_valid(&[(0., 0.), (0., 1.)]));
+        assert!(!is_valid(&[(1., 0.), (0., 1.)]));
+        assert!(!is_valid(&[(0., f64::NAN)]));
+    }
+    #[test]
+    fn test_piecewise_linear() {
+        assert_eq!(None, PiecewiseLinear::<1>::new(&[(0., 0.), (1., 1.)]));
+        assert_eq!(None, PiecewiseLinear::<2>::new(&[(1., 0.), (0., 1.)]));
+        let table = PiecewiseLinear::<3>::new(&[(-1., 10.), (1., 0.)]);
+        assert_eq!(Some(2), table.map(|t| t.points().len()));
+        assert_eq!(Some(Some(5.)), table.map(|t| t.map_f64(0.)));
+        assert_eq!(Some(Some(10.)), table.map(|t| t.map_clamped(-5.)));
+        assert_eq!(Some(None), table.map(|t| t.map::<i8, u8>(2)));
+    }
+}
@@ -0,0 +1,125 @@
+//! Bringing values outside of a range back into it.
+
+use crate::{math, MapRange};
+
+/// What happens to values outside of the range they are mapped from.
+///
+/// ```
+/// use map_to_range::{policy::OutOfRangePolicy, MapRange};
+///
+/// let value = 12_i16;
+/// assert_eq!(None, value.map_range_with_policy((0, 10), (0, 100), OutOfRangePolicy::Reject));
+/// assert_eq!(Some(100), value.map_range_with_policy((0, 10), (0, 100), OutOfRangePolicy::Clamp));
+/// assert_eq!(Some(20), value.map_range_with_policy((0, 10), (0, 100), OutOfRangePolicy::Wrap));
+/// assert_eq!(Some(80), value.map_range_with_policy((0, 10), (0, 100), OutOfRangePolicy::Mirror));
+/// assert_eq!(Some(120), value.map_range_with_policy((0, 10), (0, 100), OutOfRangePolicy::Extrapolate));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum OutOfRangePolicy {
+    /// Fails the mapping, like [`MapRange::map_range`].
+    #[default]
+    Reject,
+    /// Uses the nearest end of the range.
+    Clamp,
+    /// Wraps around like a modulo, see [`MapRange::map_range_wrapped`].
+    Wrap,
+    /// Reflects back into the range, see [`MapRange::map_range_mirrored`].
+    Mirror,
+    /// Continues the linear mapping beyond the ends of both ranges.
+    Extrapolate,
+}
+
+/// Maps `value` between the ranges, handling values outside of `from_range` by `policy`.
+///
+/// Returns `None` if anything is not finite or `from_range` is empty or reversed.
+pub(crate) fn map_f64(
+    value: f64,
+    from_range: (f64, f64),
+    to_range: (f64, f64),
+    policy: OutOfRangePolicy,
+) -> Option<f64> {
+    if from_range.0.partial_cmp(&from_range.1) != Some(core::cmp::Ordering::Less) {
+        return None;
+    }
+    let value = match policy {
+        OutOfRangePolicy::Reject => value,
+        OutOfRangePolicy::Clamp => value.clamp(from_range.0, from_range.1),
+        OutOfRangePolicy::Wrap => wrap(value, from_range),
+        OutOfRangePolicy::Mirror => mirror(value, from_range),
+        OutOfRangePolicy::Extrapolate => {
+            let result = to_range.0
+                + (value - from_range.0) * (to_range.1 - to_range.0)
+                    / (from_range.1 - from_range.0);
+            return result.is_finite().then_some(result);
+        }
+    };
+    value.map_range(from_range, to_range)
+}
+
+/// Wraps `value` into `range.0..range.1`, like a modulo that also works for negative values.
+///
+/// Returns `NaN` if the range is empty or reversed.
+pub(crate) fn wrap(value: f64, range: (f64, f64)) -> f64 {
+    let width = range.1 - range.0;
+    if width.is_nan() || width <= 0. {
+        return f64::NAN;
+    }
+    let offset = value - range.0;
+    let wrapped = offset - math::floor(offset / width) * width;
+    // Tiny negative offsets can round up to the full width.
+    if wrapped >= width {
+        range.0
+    } else {
+        range.0 + wrapped
+    }
+}
+
+/// Reflects `value` back into `range.0..=range.1`, like a triangle wave bouncing between the ends.
+///
+/// Returns `NaN` if the range is empty or reversed.
+pub(crate) fn mirror(value: f64, range: (f64, f64)) -> f64 {
+    let width = range.1 - range.0;
+    let phase = wrap(value, (range.0, range.0 + 2. * width)) - range.0;
+    if phase > width {
+        range.0 + 2. * width - phase
+    } else {
+        range.0 + phase
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_wrap() {
+        assert_eq!(Some(10.), Some(wrap(370., (0., 360.))));
+        assert_eq!(Some(350.), Some(wrap(-10., (0., 360.))));
+        assert_eq!(Some(0.), Some(wrap(360., (0., 360.))));
+        assert_eq!(Some(-1.), Some(wrap(3., (-2., 2.))));
+        assert_eq!(Some(0.), Some(wrap(-1e-20, (0., 1.))));
+        assert!(wrap(1., (1., 1.)).is_nan());
+    }
+    #[test]
+    fn test_map_f64() {
+        use OutOfRangePolicy::{Clamp, Extrapolate, Reject};
+        assert_eq!(Some(-50.), map_f64(-0.5, (0., 1.), (0., 100.), Extrapolate));
+        assert_eq!(Some(0.), map_f64(-0.5, (0., 1.), (0., 100.), Clamp));
+        assert_eq!(None, map_f64(-0.5, (0., 1.), (0., 100.), Reject));
+        assert_eq!(None, map_f64(0.5, (1., 0.), (0., 100.), Clamp));
+        assert_eq!(None, map_f64(f64::NAN, (0., 1.), (0., 100.), Clamp));
+        assert_eq!(
+            None,
+            map_f64(f64::INFINITY, (0., 1.), (0., 100.), Extrapolate)
+        );
+    }
+    #[test]
+    fn test_mirror() {
+        assert_eq!(Some(9.), Some(mirror(11., (0., 10.))));
+        assert_eq!(Some(10.), Some(mirror(10., (0., 10.))));
+        assert_eq!(Some(1.), Some(mirror(-1., (0., 10.))));
+        assert_eq!(Some(4.), Some(mirror(24., (0., 10.))));
+        assert_eq!(Some(0.), Some(mirror(20., (0., 10.))));
+        assert!(mirror(1., (2., 1.)).is_nan());
+    }
+}
@@ -0,0 +1,213 @@
+//! Polynomial interpolation through a small number of points.
+//!
+//! This is meant for quick calibrations from three or four measured points,
+//! where building a full spline would be overkill.
+
+/// Evaluates the Lagrange polynomial through `points` at `x`.
+///
+/// Returns `None` when `points` is empty or two points share the same x value.
+///
+/// ```
+/// use map_to_range::polynomial::lagrange;
+///
+/// let points = [(0., 1.), (1., 3.), (2., 7.)];
+/// assert_eq!(Some(13.), lagrange(&points, 3.));
+/// assert_eq!(None, lagrange(&[], 3.));
+/// ```
+#[must_use]
+pub fn lagrange(points: &[(f64, f64)], x: f64) -> Option<f64> {
+    if points.is_empty() {
+        return None;
+    }
+    let mut result = 0.;
+    for (i, &(xi, yi)) in points.iter().enumerate() {
+        let mut basis = 1.;
+        for (j, &(xj, _)) in points.iter().enumerate() {
+            if i == j {
+                continue;
+            }
+            let denominator = xi - xj;
+            if denominator == 0. {
+                return None;
+            }
+            basis *= (x - xj) / denominator;
+        }
+        result += yi * basis;
+    }
+    Some(result)
+}
+
+/// A polynomial in Newton form, built incrementally from up to `N` points
+/// using divided differences.
+///
+/// Adding a point does not require recomputing the existing coefficients,
+/// and no allocation is needed.
+///
+/// ```
+/// use map_to_range::polynomial::NewtonPolynomial;
+///
+/// let mut polynomial = NewtonPolynomial::<4>::new();
+/// polynomial.push((0., 1.));
+/// polynomial.push((1., 3.));
+/// assert_eq!(Some(5.), polynomial.evaluate(2.));
+/// polynomial.push((2., 7.));
+/// assert_eq!(Some(13.), polynomial.evaluate(3.));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct NewtonPolynomial<const N: usize> {
+    xs: [f64; N],
+    coefficients: [f64; N],
+    /// The last row of the divided difference table, `row[j] = f[x_j, ..., x_(len-1)]`.
+    row: [f64; N],
+    len: usize,
+}
+
+impl<const N: usize> Default for NewtonPolynomial<N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<const N: usize> NewtonPolynomial<N> {
+    /// Creates a polynomial without any points.
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            xs: [0.; N],
+            coefficients: [0.; N],
+            row: [0.; N],
+            len: 0,
+        }
+    }
+    /// Builds the polynomial through all given points.
+    ///
+    /// Returns `None` if there are more than `N` points or two points share the same x value.
+    #[must_use]
+    pub fn from_points(points: &[(f64, f64)]) -> Option<Self> {
+        let mut polynomial = Self::new();
+        for &point in points {
+            polynomial.push(point)?;
+        }
+        Some(polynomial)
+    }
+    /// Adds a point to the polynomial, raising its degree by one.
+    ///
+    /// Returns `None` and leaves the polynomial untouched if it is already full
+    /// or the x value is already present.
+    pub fn push(&mut self, point: (f64, f64)) -> Option<()> {
+        let (x, y) = point;
+        if self.len >= N || self.xs().contains(&x) {
+            return None;
+        }
+        let mut row = self.row;
+        *row.get_mut(self.len)? = y;
+        for j in (0..self.len).rev() {
+            let next = *row.get(j + 1)?;
+            let xj = *self.xs.get(j)?;
+            let previous = row.get_mut(j)?;
+            *previous = (next - *previous) / (x - xj);
+        }
+        *self.coefficients.get_mut(self.len)? = *row.first()?;
+        *self.xs.get_mut(self.len)? = x;
+        self.row = row;
+        self.len += 1;
+        Some(())
+    }
+    /// Evaluates the polynomial at `x`.
+    ///
+    /// Returns `None` if no points were added yet.
+    #[must_use]
+    pub fn evaluate(&self, x: f64) -> Option<f64> {
+        let (last, rest) = self.coefficients().split_last()?;
+        let mut result = *last;
+        for (coefficient, xi) in rest.iter().zip(self.xs()).rev() {
+            result = result * (x - xi) + coefficient;
+        }
+        Some(result)
+    }
+    /// Evaluates the first derivative of the polynomial at `x`.
+    ///
+    /// Returns `None` if no points were added yet.
+    ///
+    /// ```
+    /// use map_to_range::polynomial::NewtonPolynomial;
+    ///
+    /// let square = NewtonPolynomial::<3>::from_points(&[(0., 0.), (1., 1.), (2., 4.)]).unwrap();
+    /// assert_eq!(Some(6.), square.derivative(3.));
+    /// ```
+    #[must_use]
+    pub fn derivative(&self, x: f64) -> Option<f64> {
+        let (last, rest) = self.coefficients().split_last()?;
+        let mut value = *last;
+        let mut derivative = 0.;
+        for (coefficient, xi) in rest.iter().zip(self.xs()).rev() {
+            derivative = derivative * (x - xi) + value;
+            value = value * (x - xi) + coefficient;
+        }
+        Some(derivative)
+    }
+    /// The x values of the points added so far.
+    #[must_use]
+    pub fn xs(&self) -> &[f64] {
+        self.xs.get(..self.len).unwrap_or_default()
+    }
+    /// The Newton coefficients `f[x_0]`, `f[x_0, x_1]`, ... of the polynomial.
+    #[must_use]
+    pub fn coefficients(&self) -> &[f64] {
+        self.coefficients.get(..self.len).unwrap_or_default()
+    }
+    /// The number of points added so far.
+    #[must_use]
+    pub const fn len(&self) -> usize {
+        self.len
+    }
+    /// Returns `true` if no points were added yet.
+    #[must_use]
+    pub const fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_lagrange() {
+        let points = [(0., 0.), (1., 1.), (2., 4.), (3., 9.)];
+        assert_eq!(Some(6.25), lagrange(&points, 2.5));
+        assert_eq!(Some(4.), lagrange(&[(1., 4.)], 10.));
+        assert_eq!(None, lagrange(&[(1., 1.), (1., 2.)], 0.));
+    }
+    #[test]
+    fn test_newton_matches_lagrange() {
+        let points = [(0., 2.), (1., 0.), (3., 5.), (4., 1.)];
+        let polynomial = NewtonPolynomial::<4>::from_points(&points);
+        for x in [-1., 0., 0.5, 2., 3.5, 6.] {
+            let newton = polynomial.and_then(|p| p.evaluate(x));
+            let lagrange = lagrange(&points, x);
+            assert!(newton
+                .zip(lagrange)
+                .is_some_and(|(a, b)| (a - b).abs() < 1e-9));
+        }
+    }
+    #[test]
+    fn test_newton_derivative() {
+        let cubic = NewtonPolynomial::<4>::from_points(&[(-1., -1.), (0., 0.), (1., 1.), (2., 8.)]);
+        assert_eq!(Some(12.), cubic.and_then(|p| p.derivative(2.)));
+        assert_eq!(Some(0.), cubic.and_then(|p| p.derivative(0.)));
+        let constant = NewtonPolynomial::<1>::from_points(&[(3., 7.)]);
+        assert_eq!(Some(0.), constant.and_then(|p| p.derivative(100.)));
+    }
+    #[test]
+    fn test_newton_rejects() {
+        let mut polynomial = NewtonPolynomial::<2>::new();
+        assert_eq!(None, polynomial.evaluate(0.));
+        assert_eq!(Some(()), polynomial.push((0., 1.)));
+        assert_eq!(None, polynomial.push((0., 2.)));
+        assert_eq!(Some(()), polynomial.push((1., 2.)));
+        assert_eq!(None, polynomial.push((2., 3.)));
+        assert_eq!(2, polynomial.len());
+        assert_eq!(Some(3.), polynomial.evaluate(2.));
+    }
+}
@@ -0,0 +1,195 @@
+//! Reporting how far a job got, and estimating how long it still takes.
+
+/// `done` out of `total` units of work.
+///
+/// Whole percentages round down, so a job only shows 100% once it is really done.
+/// A job without any work counts as done, and work beyond the total counts as the total.
+///
+/// ```
+/// use map_to_range::progress::Progress;
+///
+/// let progress = Progress::new(1999, 2000);
+/// assert_eq!(99, progress.percent());
+/// assert_eq!(999, progress.permille());
+/// assert_eq!(100, Progress::new(0, 0).percent());
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Progress {
+    done: u64,
+    total: u64,
+}
+
+impl Progress {
+    /// The progress of `done` out of `total` units of work.
+    #[must_use]
+    pub const fn new(done: u64, total: u64) -> Self {
+        Self { done, total }
+    }
+    /// The units of work done, at most the total.
+    #[must_use]
+    pub const fn done(&self) -> u64 {
+        if self.done < self.total {
+            self.done
+        } else {
+            self.total
+        }
+    }
+    /// The total units of work.
+    #[must_use]
+    pub const fn total(&self) -> u64 {
+        self.total
+    }
+    /// The units of work left.
+    #[must_use]
+    pub const fn remaining(&self) -> u64 {
+        self.total - self.done()
+    }
+    /// Returns `true` if all work is done.
+    #[must_use]
+    pub const fn is_done(&self) -> bool {
+        self.done >= self.total
+    }
+    /// The progress in whole percent, rounded down.
+    #[must_use]
+    pub const fn percent(&self) -> u8 {
+        self.scaled(100) as u8
+    }
+    /// The progress in whole permille, rounded down.
+    #[must_use]
+    pub const fn permille(&self) -> u16 {
+        self.scaled(1000) as u16
+    }
+    /// The progress in `0..=1`.
+    #[must_use]
+    pub fn fraction(&self) -> f64 {
+        if self.total == 0 {
+            return 1.;
+        }
+        self.done() as f64 / self.total as f64
+    }
+    /// The progress scaled to `0..=full`, rounded down and computed without overflow.
+    const fn scaled(&self, full: u64) -> u64 {
+        if self.total == 0 {
+            return full;
+        }
+        (self.done() as u128 * full as u128 / self.total as u128) as u64
+    }
+}
+
+/// Tracks how fast work gets done, smoothed over time, to estimate when it will be finished.
+///
+/// ```
+/// use map_to_range::progress::{Progress, RateTracker};
+///
+/// let mut rate = RateTracker::new(0.5).unwrap();
+/// rate.update(0, 0.);
+/// rate.update(100, 1.);
+/// rate.update(200, 2.);
+/// assert_eq!(Some(100.), rate.rate());
+/// assert_eq!(Some(8.), rate.eta(Progress::new(200, 1000)));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RateTracker {
+    smoothing: f64,
+    last: Option<(u64, f64)>,
+    rate: Option<f64>,
+}
+
+impl RateTracker {
+    /// A tracker that weights every new rate measurement with `smoothing` against the rate
+    /// measured before. A smoothing of 1 only considers the latest measurement.
+    ///
+    /// Returns `None` if `smoothing` is not inside `0..=1` or is zero.
+    #[must_use]
+    pub fn new(smoothing: f64) -> Option<Self> {
+        (smoothing > 0. && smoothing <= 1.).then_some(Self {
+            smoothing,
+            last: None,
+            rate: None,
+        })
+    }
+    /// Records that `done` units of work were done at `time` seconds.
+    ///
+    /// Updates that do not move forward in time are ignored.
+    pub fn update(&mut self, done: u64, time: f64) {
+        if let Some((last_done, last_time)) = self.last {
+            let elapsed = time - last_time;
+            if elapsed.is_nan() || elapsed <= 0. {
+                return;
+            }
+            let measured = (done as f64 - last_done as f64) / elapsed;
+            self.rate = Some(match self.rate {
+                Some(rate) => rate + (measured - rate) * self.smoothing,
+                None => measured,
+            });
+        }
+        self.last = Some((done, time));
+    }
+    /// The smoothed units of work per second, `None` before the second update.
+    #[must_use]
+    pub const fn rate(&self) -> Option<f64> {
+        self.rate
+    }
+    /// The estimated seconds until `progress` is done.
+    ///
+    /// Returns `None` if the rate is not known yet or work does not move forward.
+    #[must_use]
+    pub fn eta(&self, progress: Progress) -> Option<f64> {
+        if progress.is_done() {
+            return Some(0.);
+        }
+        let rate = self.rate.filter(|rate| *rate > 0.)?;
+        Some(progress.remaining() as f64 / rate)
+    }
+    /// Forgets all updates.
+    pub fn reset(&mut self) {
+        self.last = None;
+        self.rate = None;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_progress() {
+        let progress = Progress::new(u64::MAX - 1, u64::MAX);
+        assert_eq!(99, progress.percent());
+        assert!(!progress.is_done());
+        assert_eq!(1, progress.remaining());
+        let beyond = Progress::new(12, 10);
+        assert_eq!(
+            (10, 100, 1000),
+            (beyond.done(), beyond.percent(), beyond.permille())
+        );
+        assert_eq!(Some(0.25), Some(Progress::new(1, 4).fraction()));
+        assert_eq!(Some(1.), Some(Progress::new(0, 0).fraction()));
+        assert_eq!(0, Progress::new(0, 3).percent());
+    }
+    #[test]
+    fn test_rate_tracker() {
+        assert_eq!(None, RateTracker::new(0.));
+        assert_eq!(None, RateTracker::new(1.5));
+        let mut rate = RateTracker {
+            smoothing: 0.5,
+            last: None,
+            rate: None,
+        };
+        assert_eq!(Some(rate), RateTracker::new(0.5));
+        rate.update(0, 0.);
+        assert_eq!(None, rate.eta(Progress::new(0, 10)));
+        rate.update(10, 1.);
+        rate.update(10, 1.);
+        rate.update(40, 2.);
+        assert_eq!(Some(20.), rate.rate());
+        assert_eq!(Some(3.), rate.eta(Progress::new(40, 100)));
+        assert_eq!(Some(0.), rate.eta(Progress::new(100, 100)));
+        rate.update(40, 3.);
+        rate.update(40, 4.);
+        rate.update(40, 100.);
+        assert!(rate.rate().is_some_and(|rate| rate < 3.));
+        rate.reset();
+        assert_eq!(None, rate.rate());
+    }
+}
@@ -0,0 +1,121 @@
+//! Mapping duty cycles to PWM compare values.
+
+use crate::MapRange;
+
+/// Converts duty cycles into compare values for a PWM timer.
+///
+/// The compare value for 100% duty depends on the timer: some reach it at their top value
+/// (e.g. `4095` for 12 bits), others need the overflow value (`4096`) because the output is
+/// only high while the counter is below the compare value. Pick the constructor that matches
+/// your timer, so full scale is neither missed by one step nor overflowed.
+///
+/// ```
+/// use map_to_range::pwm::Pwm;
+///
+/// let pwm = Pwm::from_resolution(12).unwrap();
+/// assert_eq!(Some(4095), pwm.compare_normalized(1.));
+/// assert_eq!(Some(2048), pwm.compare_percent(50_u8));
+///
+/// let pwm = Pwm::from_period(4096).inverted();
+/// assert_eq!(Some(4096), pwm.compare_percent(0_u8));
+/// assert_eq!(Some(0), pwm.compare_percent(100_u8));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Pwm {
+    /// The compare value that produces 100% duty.
+    pub full_duty: u32,
+    /// Inverts the polarity, so 100% duty produces a compare value of zero.
+    pub inverted: bool,
+}
+
+impl Pwm {
+    /// A timer where `full_duty` is the compare value for 100% duty.
+    #[must_use]
+    pub const fn new(full_duty: u32) -> Self {
+        Self {
+            full_duty,
+            inverted: false,
+        }
+    }
+    /// A timer with `bits` of resolution, reaching 100% duty at its top value `2^bits - 1`.
+    ///
+    /// Returns `None` if `bits` is zero or more than 32.
+    #[must_use]
+    pub const fn from_resolution(bits: u8) -> Option<Self> {
+        if bits == 0 || bits > 32 {
+            return None;
+        }
+        Some(Self::new(u32::MAX >> (32 - bits)))
+    }
+    /// A timer counting from zero to `period - 1`, with the output high while the counter is
+    /// below the compare value. 100% duty then needs the compare value `period`.
+    #[must_use]
+    pub const fn from_period(period: u32) -> Self {
+        Self::new(period)
+    }
+    /// The same timer with inverted polarity.
+    #[must_use]
+    pub const fn inverted(self) -> Self {
+        Self {
+            inverted: !self.inverted,
+            ..self
+        }
+    }
+    /// The compare value for a duty cycle in `0..=1`, rounded to the nearest step.
+    ///
+    /// Returns `None` if the duty cycle is outside of `0..=1`.
+    #[must_use]
+    pub fn compare_normalized<T: MapRange>(&self, duty: T) -> Option<u32> {
+        self.compare_value(duty.checked_f64_cast()?, (0., 1.))
+    }
+    /// The compare value for a duty cycle in percent, rounded to the nearest step.
+    ///
+    /// Returns `None` if the duty cycle is outside of `0..=100`.
+    #[must_use]
+    pub fn compare_percent<T: MapRange>(&self, percent: T) -> Option<u32> {
+        self.compare_value(percent.checked_f64_cast()?, (0., 100.))
+    }
+    fn compare_value(self, duty: f64, from_range: (f64, f64)) -> Option<u32> {
+        let full_duty = self.full_duty as f64;
+        let to_range = if self.inverted {
+            (full_duty, 0.)
+        } else {
+            (0., full_duty)
+        };
+        let value = duty.map_range(from_range, to_range)?;
+        Some((value + 0.5) as u32)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_resolution() {
+        assert_eq!(Some(255), Pwm::from_resolution(8).map(|pwm| pwm.full_duty));
+        assert_eq!(
+            Some(u32::MAX),
+            Pwm::from_resolution(32).map(|pwm| pwm.full_duty)
+        );
+        assert_eq!(None, Pwm::from_resolution(0));
+        assert_eq!(None, Pwm::from_resolution(33));
+    }
+    #[test]
+    fn test_full_scale() {
+        let top = Pwm::from_resolution(10);
+        assert_eq!(Some(Some(1023)), top.map(|pwm| pwm.compare_percent(100_u8)));
+        assert_eq!(Some(Some(0)), top.map(|pwm| pwm.compare_percent(0_u8)));
+        let period = Pwm::from_period(1024);
+        assert_eq!(Some(1024), period.compare_normalized(1_f32));
+        assert_eq!(Some(1), period.compare_normalized(0.001_f32));
+        assert_eq!(None, period.compare_normalized(1.01_f32));
+        assert_eq!(None, period.compare_percent(-1_i8));
+    }
+    #[test]
+    fn test_inverted() {
+        let pwm = Pwm::new(200).inverted();
+        assert_eq!(Some(150), pwm.compare_percent(25_u8));
+        assert_eq!(Pwm::new(200), pwm.inverted());
+    }
+}
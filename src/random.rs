@@ -0,0 +1,154 @@
+//! Random values inside of ranges, from a tiny deterministic generator.
+
+use crate::{math, MapRange};
+
+/// A small and fast pseudo random number generator (`SplitMix64`).
+///
+/// It is not cryptographically secure, but good enough for effects,
+/// and the same seed produces the same sequence on every platform.
+///
+/// ```
+/// use map_to_range::random::Rng;
+///
+/// let mut rng = Rng::new(1234);
+/// let hue: u8 = rng.random_in((0, 255)).unwrap();
+/// let brightness: f32 = rng.map_with_jitter(0.5_f32, (0., 1.), (0., 100.), 5.).unwrap();
+/// assert!((45. ..=55.).contains(&brightness));
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Rng {
+    state: u64,
+}
+
+impl Rng {
+    /// A generator starting from `seed`.
+    #[must_use]
+    pub const fn new(seed: u64) -> Self {
+        Self { state: seed }
+    }
+    /// The next random `u64`.
+    pub fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+    /// The next random `f64` in `0..1`.
+    pub fn next_f64(&mut self) -> f64 {
+        (self.next_u64() >> 11) as f64 / (1_u64 << 53) as f64
+    }
+    /// A uniformly distributed random value in `to_range.0..to_range.1`.
+    /// Like ranges in rust, the upper end is exclusive.
+    ///
+    /// Returns `None` if the range is empty, reversed or not finite.
+    pub fn random_in<T: MapRange>(&mut self, to_range: (T, T)) -> Option<T> {
+        if to_range.0 >= to_range.1 {
+            return None;
+        }
+        let start = to_range.0.checked_f64_cast()?;
+        // In `f64`, so signed ranges wider than `T::MAX` do not overflow.
+        let width = to_range.1.checked_f64_cast()? - start;
+        if !width.is_finite() {
+            return None;
+        }
+        // Rounding can land on the exclusive end, mostly for floats, which only draws again.
+        for _ in 0..64 {
+            let offset = self.next_f64() * width;
+            // Rounding the offset down keeps integers uniform.
+            let offset = if T::IS_FLOAT {
+                offset
+            } else {
+                math::floor(offset)
+            };
+            let value = T::checked_cast_back(start + offset)?;
+            if value < to_range.1 {
+                return Some(value);
+            }
+        }
+        Some(to_range.0)
+    }
+    /// Maps `value` like [`MapRange::map_range`] and then moves the result by a random amount
+    /// of up to `jitter` in either direction. The result stays inside of `to_range`.
+    ///
+    /// Returns `None` if `value` is outside of `from_range`.
+    pub fn map_with_jitter<T: MapRange>(
+        &mut self,
+        value: T,
+        from_range: (T, T),
+        to_range: (T, T),
+        jitter: f64,
+    ) -> Option<T> {
+        let from = (
+            from_range.0.checked_f64_cast()?,
+            from_range.1.checked_f64_cast()?,
+        );
+        let to = (
+            to_range.0.checked_f64_cast()?,
+            to_range.1.checked_f64_cast()?,
+        );
+        let mapped = value.checked_f64_cast()?.map_range(from, to)?;
+        let jittered = mapped + jitter * (self.next_f64() * 2. - 1.);
+        T::checked_cast_back(jittered.clamp(to.0.min(to.1), to.0.max(to.1)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_random_in_covers_range() {
+        let mut rng = Rng::new(0);
+        let mut seen = [false; 10];
+        for _ in 0..1000 {
+            let value = rng.random_in((-5_i8, 5));
+            assert!(value.is_some_and(|value| (-5..5).contains(&value)));
+            if let Some(slot) = value.and_then(|value| seen.get_mut((value + 5) as usize)) {
+                *slot = true;
+            }
+        }
+        assert!(seen.iter().all(|seen| *seen));
+        assert_eq!(None, rng.random_in((3_u8, 3)));
+        assert_eq!(None, rng.random_in((3_u8, 2)));
+    }
+    #[test]
+    fn test_random_in_wide_and_float_ranges() {
+        let mut rng = Rng::new(7);
+        let mut extremes = (false, false);
+        for _ in 0..10_000 {
+            let value = rng.random_in((-128_i8, 127));
+            assert!(value.is_some_and(|value| value < 127));
+            extremes.0 |= value == Some(-128);
+            extremes.1 |= value == Some(126);
+            let wide = rng.random_in((-2_000_000_000_i32, 2_000_000_000));
+            assert!(wide.is_some_and(|value| value < 2_000_000_000));
+            assert!(rng.random_in((i64::MIN, i64::MAX)).is_some());
+            let unit = rng.random_in((0_f32, 1.));
+            assert!(unit.is_some_and(|value| (0. ..1.).contains(&value)));
+            // Half of the draws round up to the end in `f32`.
+            let narrow = rng.random_in((1_f32, 1. + f32::EPSILON));
+            assert_eq!(Some(1.), narrow);
+        }
+        assert_eq!((true, true), extremes);
+        assert_eq!(None, rng.random_in((0., f64::INFINITY)));
+        assert_eq!(None, rng.random_in((-f64::MAX, f64::MAX)));
+    }
+    #[test]
+    fn test_map_with_jitter_stays_bounded() {
+        let mut rng = Rng::new(99);
+        for _ in 0..1000 {
+            let value = rng.map_with_jitter(250_u8, (0, 255), (0, 255), 20.);
+            assert!(value.is_some_and(|value| value >= 230));
+        }
+        assert_eq!(None, rng.map_with_jitter(2_u8, (0, 1), (0, 255), 1.));
+    }
+    #[test]
+    fn test_deterministic() {
+        let mut a = Rng::new(5);
+        let mut b = Rng::new(5);
+        for _ in 0..10 {
+            assert_eq!(a.next_u64(), b.next_u64());
+        }
+    }
+}
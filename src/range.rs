@@ -0,0 +1,82 @@
+//! Deriving new ranges from existing ones, like zooming and panning a plot axis.
+
+use crate::MapRange;
+
+/// Methods building derived ranges from a `(start, end)` tuple, as taken by
+/// [`MapRange::map_range`].
+///
+/// ```
+/// use map_to_range::range::RangeExt;
+///
+/// let axis = (0., 100.);
+/// assert_eq!(Some((10., 110.)), axis.shifted(10.));
+/// assert_eq!(Some((25., 75.)), axis.scaled_about(50., 0.5));
+/// assert_eq!(Some((-5., 105.)), axis.expanded(5.));
+/// ```
+pub trait RangeExt<T>: Sized {
+    /// The range moved by `delta`.
+    ///
+    /// Returns `None` if an end overflows or is not finite.
+    #[must_use]
+    fn shifted(self, delta: T) -> Option<Self>;
+    /// The range with both ends moved `factor` times as far from `center`, zooming out for
+    /// factors above one and zooming in below.
+    ///
+    /// Integer ends are truncated toward zero.
+    /// Returns `None` if an end does not fit into `T` or is not finite.
+    #[must_use]
+    fn scaled_about(self, center: T, factor: f64) -> Option<Self>;
+    /// The range grown by `margin` on both ends, or shrunk for negative margins.
+    ///
+    /// Returns `None` if an end overflows or is not finite, or a negative margin would turn the
+    /// range around.
+    #[must_use]
+    fn expanded(self, margin: T) -> Option<Self>;
+}
+
+impl<T: MapRange> RangeExt<T> for (T, T) {
+    fn shifted(self, delta: T) -> Option<Self> {
+        Some((self.0.offset_by(delta)?, self.1.offset_by(delta)?))
+    }
+    fn scaled_about(self, center: T, factor: f64) -> Option<Self> {
+        let center = center.checked_f64_cast()?;
+        let scale = |end: T| {
+            let scaled = center + (end.checked_f64_cast()? - center) * factor;
+            T::checked_cast_back(scaled).filter(T::is_finite_mr)
+        };
+        Some((scale(self.0)?, scale(self.1)?))
+    }
+    fn expanded(self, margin: T) -> Option<Self> {
+        let (start, end) = if self.0 <= self.1 {
+            (
+                self.0.checked_sub_mr(margin)?,
+                self.1.checked_add_mr(margin)?,
+            )
+        } else {
+            (
+                self.0.checked_add_mr(margin)?,
+                self.1.checked_sub_mr(margin)?,
+            )
+        };
+        ((start <= end) == (self.0 <= self.1)).then_some((start, end))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_range_ext() {
+        assert_eq!(Some((250, 255)), (0_u8, 5).shifted(250));
+        assert_eq!(None, (0_u8, 10).shifted(250));
+        assert_eq!(Some((-10, 30)), (0_i16, 20).scaled_about(10, 2.));
+        assert_eq!(None, (0_u8, 200).scaled_about(100, 2.));
+        assert_eq!(None, (0., 1.).scaled_about(0., f64::INFINITY));
+        assert_eq!(Some((12, 8)), (10_u8, 10).expanded(2).map(|(a, b)| (b, a)));
+        assert_eq!(Some((105., -5.)), (100., 0.).expanded(5.));
+        assert_eq!(Some((4., 6.)), (0., 10.).expanded(-4.));
+        assert_eq!(None, (0., 10.).expanded(-6.));
+        assert_eq!(None, (0_u8, 10).expanded(1));
+    }
+}
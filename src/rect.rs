@@ -0,0 +1,231 @@
+//! Mapping 2D points between rectangles, for hit testing and plotting.
+
+use crate::affine::AffineN;
+use crate::policy::{self, OutOfRangePolicy};
+
+/// An axis aligned rectangle, given by its horizontal and vertical range.
+///
+/// Like the target range of [`MapRange::map_range`](crate::MapRange::map_range), a range may go
+/// from the larger to the smaller end, for example to flip a plot with its y axis pointing up
+/// onto a screen pointing down.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct Rect {
+    /// The horizontal range, from the left to the right edge.
+    pub x: (f64, f64),
+    /// The vertical range, from the top to the bottom edge.
+    pub y: (f64, f64),
+}
+
+impl Rect {
+    /// The rectangle spanning `x` horizontally and `y` vertically.
+    #[must_use]
+    pub const fn new(x: (f64, f64), y: (f64, f64)) -> Self {
+        Self { x, y }
+    }
+    /// The rectangle with its top left corner at `origin` and the given `size`.
+    #[must_use]
+    pub fn from_origin_size(origin: [f64; 2], size: [f64; 2]) -> Self {
+        let [x, y] = origin;
+        let [width, height] = size;
+        Self::new((x, x + width), (y, y + height))
+    }
+    /// The horizontal extent, negative if the horizontal range is reversed.
+    #[must_use]
+    pub fn width(&self) -> f64 {
+        self.x.1 - self.x.0
+    }
+    /// The vertical extent, negative if the vertical range is reversed.
+    #[must_use]
+    pub fn height(&self) -> f64 {
+        self.y.1 - self.y.0
+    }
+    /// The point in the middle of the rectangle.
+    #[must_use]
+    pub fn center(&self) -> [f64; 2] {
+        [
+            f64::midpoint(self.x.0, self.x.1),
+            f64::midpoint(self.y.0, self.y.1),
+        ]
+    }
+    /// Returns `true` if `point` lies inside the rectangle or on its edge.
+    #[must_use]
+    pub fn contains(&self, point: [f64; 2]) -> bool {
+        let inside = |value: f64, range: (f64, f64)| {
+            (range.0.min(range.1)..=range.0.max(range.1)).contains(&value)
+        };
+        let [x, y] = point;
+        inside(x, self.x) && inside(y, self.y)
+    }
+    /// The transform mapping every point of `self` onto its counterpart in `to`.
+    ///
+    /// Returns `None` if `self` is empty or not finite.
+    #[must_use]
+    pub fn transform_to(&self, to: &Rect) -> Option<AffineN<2>> {
+        AffineN::from_boxes([self.x, self.y], [to.x, to.y])
+    }
+}
+
+/// Maps `point` from `from_rect` onto `to_rect`, handling points outside of `from_rect` by
+/// `policy` on every axis.
+///
+/// Returns `None` if the point is rejected by the policy, a value is not finite or a range of
+/// `from_rect` is empty or reversed.
+///
+/// ```
+/// use map_to_range::policy::OutOfRangePolicy;
+/// use map_to_range::rect::{map_point, Rect};
+///
+/// // A plot of 0..=10 by 0..=100, with the y axis pointing up, on a 200 by 100 pixel widget.
+/// let plot = Rect::new((0., 10.), (0., 100.));
+/// let widget = Rect::new((0., 200.), (100., 0.));
+/// assert_eq!(Some([50., 75.]), map_point([2.5, 25.], plot, widget, OutOfRangePolicy::Reject));
+/// assert_eq!(Some([200., 0.]), map_point([12., 130.], plot, widget, OutOfRangePolicy::Clamp));
+/// assert_eq!(None, map_point([12., 130.], plot, widget, OutOfRangePolicy::Reject));
+/// ```
+#[must_use]
+pub fn map_point(
+    point: [f64; 2],
+    from_rect: Rect,
+    to_rect: Rect,
+    policy: OutOfRangePolicy,
+) -> Option<[f64; 2]> {
+    let [x, y] = point;
+    Some([
+        policy::map_f64(x, from_rect.x, to_rect.x, policy)?,
+        policy::map_f64(y, from_rect.y, to_rect.y, policy)?,
+    ])
+}
+
+/// How [`fit_rect`] scales one rectangle into another.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum FitMode {
+    /// As large as possible while staying completely inside, leaving letterbox margins.
+    #[default]
+    Contain,
+    /// As small as possible while covering everything, cropping what sticks out.
+    Cover,
+    /// Scales both axes independently to fill exactly, distorting the aspect ratio.
+    Stretch,
+}
+
+/// Where [`fit_rect`] placed the source rectangle.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Fit {
+    /// The area covered by the source, centered in the destination.
+    pub placed: Rect,
+    /// The horizontal and vertical margin between the placed source and the destination on
+    /// every side, negative where the source is cropped.
+    pub margins: [f64; 2],
+    /// The transform mapping points of the source onto the placed source.
+    pub transform: AffineN<2>,
+}
+
+/// Scales `src` into `dst` according to `mode`, keeping its aspect ratio unless stretched.
+///
+/// Returns `None` if one of the rectangles is empty or not finite.
+///
+/// ```
+/// use map_to_range::rect::{fit_rect, FitMode, Rect};
+///
+/// let video = Rect::new((0., 1920.), (0., 1080.));
+/// let screen = Rect::new((0., 800.), (0., 600.));
+/// let fit = fit_rect(video, screen, FitMode::Contain).unwrap();
+/// assert_eq!(Rect::new((0., 800.), (75., 525.)), fit.placed);
+/// assert_eq!([0., 75.], fit.margins);
+/// assert_eq!([400., 300.], fit.transform.apply([960., 540.]));
+/// ```
+#[must_use]
+pub fn fit_rect(src: Rect, dst: Rect, mode: FitMode) -> Option<Fit> {
+    let (src_width, src_height) = (src.width().abs(), src.height().abs());
+    let (dst_width, dst_height) = (dst.width().abs(), dst.height().abs());
+    let extents = [src_width, src_height, dst_width, dst_height];
+    if !extents.iter().all(|extent| extent.is_normal()) {
+        return None;
+    }
+    let (width, height) = match mode {
+        FitMode::Contain | FitMode::Cover => {
+            let horizontal = dst_width / src_width;
+            let vertical = dst_height / src_height;
+            let scale = if mode == FitMode::Contain {
+                horizontal.min(vertical)
+            } else {
+                horizontal.max(vertical)
+            };
+            (src_width * scale, src_height * scale)
+        }
+        FitMode::Stretch => (dst_width, dst_height),
+    };
+    let [center_x, center_y] = dst.center();
+    // Keep the direction of the destination ranges.
+    let half_x = (width / 2.).copysign(dst.width());
+    let half_y = (height / 2.).copysign(dst.height());
+    let placed = Rect::new(
+        (center_x - half_x, center_x + half_x),
+        (center_y - half_y, center_y + half_y),
+    );
+    Some(Fit {
+        placed,
+        margins: [(dst_width - width) / 2., (dst_height - height) / 2.],
+        transform: src.transform_to(&placed)?,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rect() {
+        let rect = Rect::from_origin_size([10., 20.], [30., -40.]);
+        assert_eq!(Rect::new((10., 40.), (20., -20.)), rect);
+        assert_eq!(Some((30., -40.)), Some((rect.width(), rect.height())));
+        assert_eq!(Some([25., 0.]), Some(rect.center()));
+        assert!(rect.contains([10., -20.]));
+        assert!(!rect.contains([9., 0.]));
+        assert!(!rect.contains([f64::NAN, 0.]));
+    }
+    #[test]
+    fn test_map_point() {
+        let from = Rect::new((0., 1.), (0., 1.));
+        let to = Rect::new((0., 640.), (0., 480.));
+        let extrapolated = map_point([1.5, -0.5], from, to, OutOfRangePolicy::Extrapolate);
+        assert_eq!(Some([960., -240.]), extrapolated);
+        let wrapped = map_point([1.5, -0.25], from, to, OutOfRangePolicy::Wrap);
+        assert_eq!(Some([320., 360.]), wrapped);
+        let reversed = Rect::new((1., 0.), (0., 1.));
+        assert_eq!(
+            None,
+            map_point([0.5, 0.5], reversed, to, OutOfRangePolicy::Clamp)
+        );
+        let empty = Rect::new((0., 0.), (0., 1.));
+        assert_eq!(
+            None,
+            map_point([0., 0.5], empty, to, OutOfRangePolicy::Clamp)
+        );
+        let transform = from.transform_to(&to);
+        assert_eq!(Some([320., 120.]), transform.map(|t| t.apply([0.5, 0.25])));
+        let flipped = reversed.transform_to(&to);
+        assert_eq!(Some([480., 120.]), flipped.map(|t| t.apply([0.25, 0.25])));
+        assert_eq!(None, empty.transform_to(&to));
+    }
+    #[test]
+    fn test_fit_rect() {
+        let square = Rect::new((0., 10.), (0., 10.));
+        let wide = Rect::new((0., 40.), (20., 0.));
+        let cover = fit_rect(square, wide, FitMode::Cover);
+        assert_eq!(
+            Some(Rect::new((0., 40.), (30., -10.))),
+            cover.map(|f| f.placed)
+        );
+        assert_eq!(Some([0., -10.]), cover.map(|f| f.margins));
+        let stretch = fit_rect(square, wide, FitMode::Stretch);
+        assert_eq!(Some(wide), stretch.map(|f| f.placed));
+        let corner = stretch.map(|f| f.transform.apply([10., 0.]));
+        assert_eq!(Some([40., 20.]), corner);
+        let contain = fit_rect(square, wide, FitMode::Contain);
+        assert_eq!(Some([10., 0.]), contain.map(|f| f.margins));
+        let empty = Rect::new((0., 0.), (0., 1.));
+        assert_eq!(None, fit_rect(empty, wide, FitMode::Contain));
+        assert_eq!(None, fit_rect(square, empty, FitMode::Stretch));
+    }
+}
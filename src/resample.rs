@@ -0,0 +1,391 @@
+//! Mapping sample positions between two sample rates.
+//!
+//! All conversions use exact integer ratios, so positions deep into a long recording land on
+//! the same sample as near its start, unlike multiplying with a rounded float ratio.
+
+use crate::lerp::Lerp;
+use crate::tracker::MinMaxTracker;
+
+/// Converts sample indices from one sample rate to another.
+///
+/// ```
+/// use map_to_range::resample::RateConverter;
+///
+/// let converter = RateConverter::new(44_100, 48_000).unwrap();
+/// assert_eq!(Some(48_000), converter.map_index(44_100));
+/// // Ten hours into the recording, still exact.
+/// assert_eq!(Some(1_728_000_000), converter.map_index(1_587_600_000));
+/// // Index 1 at 44.1 kHz lies 13/147 of the way from index 1 to 2 at 48 kHz.
+/// assert_eq!(Some((1, 13)), converter.map_position(1));
+/// assert_eq!(147, converter.denominator());
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RateConverter {
+    from: u32,
+    to: u32,
+}
+
+impl RateConverter {
+    /// A converter from indices at `from_hz` to indices at `to_hz`.
+    ///
+    /// Returns `None` if either rate is zero.
+    #[must_use]
+    pub const fn new(from_hz: u32, to_hz: u32) -> Option<Self> {
+        if from_hz == 0 || to_hz == 0 {
+            return None;
+        }
+        let divisor = gcd(from_hz, to_hz);
+        Some(Self {
+            from: from_hz / divisor,
+            to: to_hz / divisor,
+        })
+    }
+    /// The converter for the opposite direction.
+    #[must_use]
+    pub const fn inverse(&self) -> Self {
+        Self {
+            from: self.to,
+            to: self.from,
+        }
+    }
+    /// The reduced ratio `(from, to)`; `from` samples at the source rate span `to` samples at the
+    /// target rate.
+    #[must_use]
+    pub const fn ratio(&self) -> (u32, u32) {
+        (self.from, self.to)
+    }
+    /// The denominator of the fractions returned by [`map_position`](Self::map_position).
+    #[must_use]
+    pub const fn denominator(&self) -> u32 {
+        self.from
+    }
+    /// The exact position of `index` at the target rate, as the index at or before it and the
+    /// numerator of the fraction towards the next index.
+    ///
+    /// Returns `None` if the index does not fit into `u64` at the target rate.
+    #[must_use]
+    pub fn map_position(&self, index: u64) -> Option<(u64, u32)> {
+        let scaled = u128::from(index) * u128::from(self.to);
+        let from = u128::from(self.from);
+        let whole = u64::try_from(scaled / from).ok()?;
+        let fraction = u32::try_from(scaled % from).ok()?;
+        Some((whole, fraction))
+    }
+    /// The index at the target rate at or before `index`.
+    ///
+    /// Returns `None` if the index does not fit into `u64` at the target rate.
+    #[must_use]
+    pub fn map_index(&self, index: u64) -> Option<u64> {
+        self.map_position(index).map(|(whole, _)| whole)
+    }
+    /// The index at the target rate nearest to `index`, rounding halfway positions up.
+    ///
+    /// Returns `None` if the index does not fit into `u64` at the target rate.
+    #[must_use]
+    pub fn map_index_rounded(&self, index: u64) -> Option<u64> {
+        let (whole, fraction) = self.map_position(index)?;
+        if u64::from(fraction) * 2 >= u64::from(self.from) {
+            whole.checked_add(1)
+        } else {
+            Some(whole)
+        }
+    }
+}
+
+/// Resamples `input` into `output` by linear interpolation, with `converter` mapping from the
+/// input rate to the output rate.
+///
+/// Output samples are written until `output` is full or the next one would lie past the last
+/// input sample. Returns the number of samples written.
+///
+/// ```
+/// use map_to_range::resample::{resample_linear, RateConverter};
+///
+/// let converter = RateConverter::new(2, 3).unwrap();
+/// let mut output = [0_i16; 8];
+/// let written = resample_linear(&[0, 300, 600, 0, -300], converter, &mut output);
+/// assert_eq!(7, written);
+/// assert_eq!([0, 200, 400, 600, 200, -100, -300], output[..written]);
+/// ```
+pub fn resample_linear<T: Lerp>(input: &[T], converter: RateConverter, output: &mut [T]) -> usize {
+    let to_input = converter.inverse();
+    let denominator = f64::from(to_input.denominator());
+    let mut written = 0;
+    for (index, sample) in (0..).zip(output.iter_mut()) {
+        let Some((whole, fraction)) = to_input.map_position(index) else {
+            break;
+        };
+        let Ok(whole) = usize::try_from(whole) else {
+            break;
+        };
+        let Some(&current) = input.get(whole) else {
+            break;
+        };
+        *sample = if fraction == 0 {
+            current
+        } else {
+            let Some(&next) = input.get(whole + 1) else {
+                break;
+            };
+            current.lerp(next, f64::from(fraction) / denominator)
+        };
+        written += 1;
+    }
+    written
+}
+
+/// Reduces `input` to one `(min, max)` pair per bucket, so a plot of it keeps every peak.
+///
+/// `input` is split into `output.len()` buckets of nearly equal size, or one per sample if it is
+/// shorter. Values that cannot be compared, like `NaN`, are ignored unless a bucket has nothing
+/// else. Returns the number of buckets written.
+///
+/// ```
+/// use map_to_range::resample::decimate_min_max;
+///
+/// let waveform = [0, 1, 0, -1, 0, 9, 0, -1, 0];
+/// let mut columns = [(0, 0); 3];
+/// assert_eq!(3, decimate_min_max(&waveform, &mut columns));
+/// assert_eq!([(0, 1), (-1, 9), (-1, 0)], columns);
+/// ```
+pub fn decimate_min_max<T: PartialOrd + Copy>(input: &[T], output: &mut [(T, T)]) -> usize {
+    let buckets = output.len().min(input.len());
+    for (index, pair) in output.iter_mut().take(buckets).enumerate() {
+        let Some(bucket) = input.get(bucket_bounds(index, input.len(), buckets)) else {
+            return index;
+        };
+        let Some(&first) = bucket.first() else {
+            return index;
+        };
+        *pair = bucket
+            .iter()
+            .copied()
+            .collect::<MinMaxTracker<T>>()
+            .range()
+            .unwrap_or((first, first));
+    }
+    buckets
+}
+
+/// Downsamples `(x, y)` points to `output.len()` points with Largest-Triangle-Three-Buckets.
+///
+/// The first and last points are always kept. The points between them are split into buckets,
+/// and from each the point spanning the largest triangle with the point chosen before it and the
+/// average of the next bucket is kept, which keeps the visual shape of the series.
+/// Points are expected to be sorted by `x`. If `input` fits into `output` it is copied unchanged.
+/// Returns the number of points written.
+///
+/// ```
+/// use map_to_range::resample::downsample_lttb;
+///
+/// let mut series = [(0., 0.); 10];
+/// for (x, point) in series.iter_mut().enumerate() {
+///     *point = (x as f64, if x == 4 { 10. } else { 0. });
+/// }
+/// let mut plotted = [(0., 0.); 4];
+/// assert_eq!(4, downsample_lttb(&series, &mut plotted));
+/// assert_eq!([(0., 0.), (4., 10.), (5., 0.), (9., 0.)], plotted);
+/// ```
+pub fn downsample_lttb(input: &[(f64, f64)], output: &mut [(f64, f64)]) -> usize {
+    if input.len() <= output.len() {
+        if let Some(copy) = output.get_mut(..input.len()) {
+            copy.copy_from_slice(input);
+        }
+        return input.len();
+    }
+    let (Some(&first), Some(&last)) = (input.first(), input.last()) else {
+        return 0;
+    };
+    let Some((last_slot, rest)) = output.split_last_mut() else {
+        return 0;
+    };
+    let Some((first_slot, middle)) = rest.split_first_mut() else {
+        // A single point budget keeps the first point.
+        *last_slot = first;
+        return 1;
+    };
+    *first_slot = first;
+    *last_slot = last;
+    let inner = input.get(1..input.len() - 1).unwrap_or_default();
+    let buckets = middle.len();
+    let mut chosen = first;
+    for (index, slot) in middle.iter_mut().enumerate() {
+        let bucket = inner
+            .get(bucket_bounds(index, inner.len(), buckets))
+            .unwrap_or_default();
+        let next = if index + 1 < buckets {
+            average(inner.get(bucket_bounds(index + 1, inner.len(), buckets)))
+        } else {
+            None
+        };
+        let (next_x, next_y) = next.unwrap_or(last);
+        let area = |&(x, y): &(f64, f64)| {
+            ((chosen.0 - next_x) * (y - chosen.1) - (chosen.0 - x) * (next_y - chosen.1)).abs()
+        };
+        let mut best = bucket.first().copied().unwrap_or(chosen);
+        let mut best_area = 0.;
+        for point in bucket {
+            let point_area = area(point);
+            if point_area > best_area {
+                best = *point;
+                best_area = point_area;
+            }
+        }
+        *slot = best;
+        chosen = best;
+    }
+    output.len()
+}
+
+/// The average of `points`, `None` if there are none.
+fn average(points: Option<&[(f64, f64)]>) -> Option<(f64, f64)> {
+    let points = points.filter(|points| !points.is_empty())?;
+    let (x, y) = points
+        .iter()
+        .fold((0., 0.), |(x, y), point| (x + point.0, y + point.1));
+    let len = points.len() as f64;
+    Some((x / len, y / len))
+}
+
+/// The indices of bucket `index` when splitting `len` items into `buckets` nearly equal parts.
+fn bucket_bounds(index: usize, len: usize, buckets: usize) -> core::ops::Range<usize> {
+    let bound = |index: usize| {
+        (index as u64 * len as u64)
+            .checked_div(buckets as u64)
+            .map_or(0, |bound| bound as usize)
+    };
+    bound(index)..bound(index + 1)
+}
+
+/// The time of sample `index` at `rate_hz`, in nanoseconds and rounded down.
+///
+/// Returns `None` if `rate_hz` is zero or the time does not fit into `u64`.
+///
+/// ```
+/// use map_to_range::resample::{index_to_nanos, nanos_to_index};
+///
+/// assert_eq!(Some(1_000_000_000), index_to_nanos(48_000, 48_000));
+/// assert_eq!(Some(22_675), index_to_nanos(1, 44_100));
+/// assert_eq!(Some(44_100), nanos_to_index(1_000_000_000, 44_100));
+/// ```
+#[must_use]
+pub fn index_to_nanos(index: u64, rate_hz: u32) -> Option<u64> {
+    let nanos = u128::from(index) * NANOS_PER_SECOND;
+    u64::try_from(nanos.checked_div(u128::from(rate_hz))?).ok()
+}
+
+/// The index of the sample at or before `nanos` at `rate_hz`.
+///
+/// Returns `None` if `rate_hz` is zero.
+#[must_use]
+pub fn nanos_to_index(nanos: u64, rate_hz: u32) -> Option<u64> {
+    if rate_hz == 0 {
+        return None;
+    }
+    // Below 2^64 * 2^32 / 10^9, so the quotient always fits.
+    u64::try_from(u128::from(nanos) * u128::from(rate_hz) / NANOS_PER_SECOND).ok()
+}
+
+const NANOS_PER_SECOND: u128 = 1_000_000_000;
+
+const fn gcd(mut a: u32, mut b: u32) -> u32 {
+    while b != 0 {
+        let remainder = a % b;
+        a = b;
+        b = remainder;
+    }
+    a
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rate_converter() {
+        assert_eq!(None, RateConverter::new(0, 48_000));
+        let converter = RateConverter::new(48_000, 44_100);
+        assert_eq!(Some((160, 147)), converter.map(|c| c.ratio()));
+        assert_eq!(Some(Some(0)), converter.map(|c| c.map_index(1)));
+        assert_eq!(Some(Some(1)), converter.map(|c| c.map_index_rounded(1)));
+        assert_eq!(Some(Some(44_100)), converter.map(|c| c.map_index(48_000)));
+        let inverse = converter.map(|c| c.inverse());
+        assert_eq!(RateConverter::new(44_100, 48_000), inverse);
+        let upsample = RateConverter::new(1, 4);
+        assert_eq!(Some(None), upsample.map(|c| c.map_index(u64::MAX)));
+        for blocks in [1, 999, 123_456_789] {
+            let position = converter.and_then(|c| c.map_position(blocks * 160 + 1));
+            assert_eq!(Some((blocks * 147, 147)), position);
+        }
+    }
+    #[test]
+    fn test_resample_linear() {
+        let halve = RateConverter::new(4, 2);
+        let input = [1_f32, 2., 3., 4., 5.];
+        let mut output = [0.; 4];
+        let written = halve.map(|c| resample_linear(&input, c, &mut output));
+        assert_eq!(Some(3), written);
+        assert_eq!(Some([1., 3., 5., 0.]), Some(output));
+        let mut short = [0.; 2];
+        assert_eq!(
+            Some(2),
+            halve.map(|c| resample_linear(&input, c, &mut short))
+        );
+        assert_eq!(Some(0), halve.map(|c| resample_linear(&[], c, &mut output)));
+        let double = RateConverter::new(1, 2);
+        let mut stereo = [[0_f32; 2]; 3];
+        let written = double.map(|c| resample_linear(&[[0., 1.], [1., 0.]], c, &mut stereo));
+        assert_eq!(Some(3), written);
+        assert_eq!(Some([[0., 1.], [0.5, 0.5], [1., 0.]]), Some(stereo));
+    }
+    #[test]
+    fn test_decimate_min_max() {
+        let input = [0, 1, 2, 3, 4, 5, 6, 100, 8, 9];
+        let mut output = [(0, 0); 4];
+        assert_eq!(4, decimate_min_max(&input, &mut output));
+        assert_eq!([(0, 1), (2, 4), (5, 6), (8, 100)], output);
+        let mut wide = [(0., 0.); 4];
+        assert_eq!(2, decimate_min_max(&[f64::NAN, 1.], &mut wide));
+        assert!(wide[0].0.is_nan() && wide[0].1.is_nan());
+        assert_eq!(Some((1., 1.)), Some(wide[1]));
+        assert_eq!(0, decimate_min_max::<u8>(&[], &mut [(0, 0); 2]));
+        let mut pair = [(0., 0.)];
+        assert_eq!(1, decimate_min_max(&[3., f32::NAN, -1.], &mut pair));
+        assert_eq!(Some((-1., 3.)), Some(pair[0]));
+        assert_eq!(0, decimate_min_max(&[1, 2], &mut []));
+    }
+    #[test]
+    fn test_downsample_lttb() {
+        let input = [
+            (0., 0.),
+            (1., 1.),
+            (2., -3.),
+            (3., 0.),
+            (4., 0.5),
+            (5., 4.),
+            (6., 0.),
+        ];
+        let mut output = [(0., 0.); 4];
+        assert_eq!(4, downsample_lttb(&input, &mut output));
+        assert_eq!([(0., 0.), (2., -3.), (5., 4.), (6., 0.)], output);
+        let mut two = [(0., 0.); 2];
+        assert_eq!(2, downsample_lttb(&input, &mut two));
+        assert_eq!([(0., 0.), (6., 0.)], two);
+        let mut one = [(9., 9.)];
+        assert_eq!(1, downsample_lttb(&input, &mut one));
+        assert_eq!([(0., 0.)], one);
+        let mut roomy = [(9., 9.); 8];
+        assert_eq!(7, downsample_lttb(&input, &mut roomy));
+        assert_eq!(Some(input), roomy.first_chunk().copied());
+        assert_eq!(0, downsample_lttb(&input, &mut []));
+    }
+    #[test]
+    fn test_nanos() {
+        assert_eq!(None, index_to_nanos(1, 0));
+        assert_eq!(None, nanos_to_index(1, 0));
+        assert_eq!(None, index_to_nanos(u64::MAX, 1));
+        assert_eq!(Some(u64::MAX), index_to_nanos(u64::MAX, 1_000_000_000));
+        assert_eq!(Some(1), nanos_to_index(22_676, 44_100));
+        assert_eq!(Some(0), nanos_to_index(22_675, 44_100));
+    }
+}
@@ -0,0 +1,434 @@
+//! Irregularly timestamped series of samples, like logged sensor data.
+//!
+//! Times are `f64` seconds, or any other unit as long as it is used consistently.
+
+use crate::lerp::{Interpolation, Lerp};
+use crate::math;
+
+/// What a resampler produces between two samples that are further apart than the allowed gap.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum GapPolicy {
+    /// Interpolates across the gap like between any other pair of samples.
+    #[default]
+    Interpolate,
+    /// Holds the value of the sample before the gap, until the sample after it.
+    Hold,
+    /// Produces no values inside the gap, so the output has a hole in its timestamps.
+    Skip,
+}
+
+/// Resamples `(time, value)` samples to a fixed rate, interpolating linearly by default.
+///
+/// The first output is at the time of the first sample, the following ones every `period` after
+/// it until the last sample. Samples with a time that is not finite or not after the previous
+/// sample are ignored.
+///
+/// ```
+/// use map_to_range::series::{FixedRate, GapPolicy};
+///
+/// let log = [(0., 10_f32), (0.3, 13.), (1.0, 20.), (5.0, 0.)];
+/// let resampled: Vec<_> = FixedRate::new(log, 0.5).unwrap().collect();
+/// assert_eq!(vec![(0., 10.), (0.5, 15.), (1., 20.), (1.5, 17.5)], resampled[..4]);
+///
+/// let held: Vec<_> = FixedRate::new(log, 1.).unwrap().with_gaps(2., GapPolicy::Hold).collect();
+/// assert_eq!(vec![(0., 10.), (1., 20.), (2., 20.), (3., 20.), (4., 20.), (5., 0.)], held);
+/// ```
+#[derive(Debug, Clone)]
+pub struct FixedRate<I, T> {
+    samples: I,
+    period: f64,
+    max_gap: f64,
+    gap_policy: GapPolicy,
+    interpolation: Interpolation,
+    start: Option<f64>,
+    index: u64,
+    previous: Option<(f64, T)>,
+    next: Option<(f64, T)>,
+}
+
+impl<I, T> FixedRate<I, T>
+where
+    I: Iterator<Item = (f64, T)>,
+    T: Lerp,
+{
+    /// A resampler producing a value every `period`, interpolating across any gap.
+    ///
+    /// Returns `None` if `period` is not finite and positive.
+    #[must_use]
+    pub fn new<S: IntoIterator<IntoIter = I>>(samples: S, period: f64) -> Option<Self> {
+        (period.is_finite() && period > 0.).then(|| Self {
+            samples: samples.into_iter(),
+            period,
+            max_gap: f64::INFINITY,
+            gap_policy: GapPolicy::Interpolate,
+            interpolation: Interpolation::Linear,
+            start: None,
+            index: 0,
+            previous: None,
+            next: None,
+        })
+    }
+    /// Handles samples further apart than `max_gap` by `policy`.
+    #[must_use]
+    pub fn with_gaps(mut self, max_gap: f64, policy: GapPolicy) -> Self {
+        self.max_gap = max_gap;
+        self.gap_policy = policy;
+        self
+    }
+    /// Produces values between samples by `interpolation` instead of linearly.
+    ///
+    /// ```
+    /// use map_to_range::{lerp::Interpolation, series::FixedRate};
+    ///
+    /// let presets = [(0., 1_f32), (1., 2.), (2., 3.)];
+    /// let stepped = FixedRate::new(presets, 0.5).unwrap().with_interpolation(Interpolation::Hold);
+    /// let values: Vec<_> = stepped.map(|(_, value)| value).collect();
+    /// assert_eq!(vec![1., 1., 2., 2., 3.], values);
+    /// ```
+    #[must_use]
+    pub fn with_interpolation(mut self, interpolation: Interpolation) -> Self {
+        self.interpolation = interpolation;
+        self
+    }
+    /// The next usable sample, after the newest one read so far.
+    fn pull(&mut self) -> Option<(f64, T)> {
+        let newest = self.next.or(self.previous).map(|(time, _)| time);
+        self.samples
+            .find(|&(time, _)| time.is_finite() && newest.is_none_or(|newest| time > newest))
+    }
+}
+
+impl<I, T> Iterator for FixedRate<I, T>
+where
+    I: Iterator<Item = (f64, T)>,
+    T: Lerp,
+{
+    type Item = (f64, T);
+
+    fn next(&mut self) -> Option<(f64, T)> {
+        if self.start.is_none() {
+            self.next = self.pull();
+            self.start = self.next.map(|(time, _)| time);
+        }
+        let start = self.start?;
+        loop {
+            let time = start + self.index as f64 * self.period;
+            while self.next.is_some_and(|(next, _)| next < time) {
+                self.previous = self.next.take();
+                self.next = self.pull();
+            }
+            let (next_time, next_value) = self.next?;
+            self.index += 1;
+            let Some((previous_time, previous_value)) = self.previous else {
+                return Some((time, next_value));
+            };
+            if next_time <= time {
+                return Some((time, next_value));
+            }
+            let width = next_time - previous_time;
+            if width > self.max_gap {
+                match self.gap_policy {
+                    GapPolicy::Interpolate => {}
+                    GapPolicy::Hold => return Some((time, previous_value)),
+                    GapPolicy::Skip => {
+                        // Jumps to the first output after the gap instead of walking through it.
+                        let after_gap = -math::floor(-(next_time - start) / self.period);
+                        self.index = self.index.max(after_gap as u64);
+                        continue;
+                    }
+                }
+            }
+            let t = (time - previous_time) / width;
+            let value = self
+                .interpolation
+                .interpolate(previous_value, next_value, t);
+            return Some((time, value));
+        }
+    }
+}
+
+/// Fills the holes of a `(time, value)` series by interpolating between its neighbours.
+///
+/// Missing values are interpolated between the known values before and after them, by time.
+/// Missing values before the first or after the last known value take that value instead.
+/// With [`with_max_step`](Self::with_max_step), evenly spaced points are also inserted where
+/// consecutive samples are too far apart.
+///
+/// Looking ahead for the next known value clones the iterator, so every run of missing values
+/// is read twice but nothing is buffered.
+///
+/// ```
+/// use map_to_range::series::FillGaps;
+///
+/// let readings = [Some(1.), None, None, Some(4.), None];
+/// let series = readings.into_iter().enumerate().map(|(i, value)| (i as f64, value));
+/// let filled: Vec<_> = FillGaps::new(series).map(|(_, value)| value).collect();
+/// assert_eq!(vec![1., 2., 3., 4., 4.], filled);
+///
+/// let sparse = [(0., Some(0.)), (1., Some(10.)), (4., Some(40.))];
+/// let filled: Vec<_> = FillGaps::new(sparse).with_max_step(1.).collect();
+/// assert_eq!(vec![(0., 0.), (1., 10.), (2., 20.), (3., 30.), (4., 40.)], filled);
+/// ```
+#[derive(Debug, Clone)]
+pub struct FillGaps<I, T> {
+    samples: I,
+    max_step: f64,
+    interpolation: Interpolation,
+    pending: Option<(f64, Option<T>)>,
+    before: Option<(f64, T)>,
+    after: Option<(f64, T)>,
+    exhausted: bool,
+    last_time: Option<f64>,
+    /// The start and step of the gap points are being inserted into, and the points so far.
+    gap: Option<(f64, f64)>,
+    inserted: u64,
+}
+
+impl<I, T> FillGaps<I, T>
+where
+    I: Iterator<Item = (f64, Option<T>)> + Clone,
+    T: Lerp,
+{
+    /// Fills missing values, without inserting any points.
+    #[must_use]
+    pub fn new<S: IntoIterator<IntoIter = I>>(samples: S) -> Self {
+        Self {
+            samples: samples.into_iter(),
+            max_step: f64::INFINITY,
+            interpolation: Interpolation::Linear,
+            pending: None,
+            before: None,
+            after: None,
+            exhausted: false,
+            last_time: None,
+            gap: None,
+            inserted: 0,
+        }
+    }
+    /// Inserts points so that no two consecutive outputs are more than `max_step` apart.
+    ///
+    /// A step that is not finite and positive inserts no points.
+    #[must_use]
+    pub fn with_max_step(mut self, max_step: f64) -> Self {
+        self.max_step = if max_step.is_finite() && max_step > 0. {
+            max_step
+        } else {
+            f64::INFINITY
+        };
+        self
+    }
+    /// Fills values between known ones by `interpolation` instead of linearly.
+    #[must_use]
+    pub fn with_interpolation(mut self, interpolation: Interpolation) -> Self {
+        self.interpolation = interpolation;
+        self
+    }
+    /// The first known value at or after `time`.
+    fn known_after(&mut self, time: f64) -> Option<(f64, T)> {
+        if let Some((pending, Some(value))) = self.pending {
+            return Some((pending, value));
+        }
+        if self.after.is_some_and(|(after, _)| after >= time) || self.exhausted {
+            return self.after.filter(|&(after, _)| after >= time);
+        }
+        self.after = self
+            .samples
+            .clone()
+            .find_map(|(time, value)| value.map(|value| (time, value)));
+        self.exhausted = self.after.is_none();
+        self.after
+    }
+    /// The value at `time`, from the known values around it.
+    fn value_at(&mut self, time: f64) -> Option<T> {
+        match (self.before, self.known_after(time)) {
+            (Some((before, from)), Some((after, to))) if after > before => {
+                let t = (time - before) / (after - before);
+                Some(self.interpolation.interpolate(from, to, t))
+            }
+            (_, Some((_, value))) | (Some((_, value)), None) => Some(value),
+            (None, None) => None,
+        }
+    }
+}
+
+impl<I, T> Iterator for FillGaps<I, T>
+where
+    I: Iterator<Item = (f64, Option<T>)> + Clone,
+    T: Lerp,
+{
+    type Item = (f64, T);
+
+    fn next(&mut self) -> Option<(f64, T)> {
+        loop {
+            if self.pending.is_none() {
+                self.pending = Some(self.samples.next()?);
+            }
+            let (time, value) = self.pending?;
+            if self.gap.is_none() {
+                let width = self.last_time.map_or(0., |last| time - last);
+                if width > self.max_step {
+                    let step = width / -math::floor(-width / self.max_step);
+                    // A step too small to move away from the start inserts nothing.
+                    self.gap = self
+                        .last_time
+                        .filter(|&last| last + step > last)
+                        .map(|last| (last, step));
+                    self.inserted = 0;
+                }
+            }
+            if let Some((start, step)) = self.gap {
+                // Multiplying from the start of the gap keeps rounding errors from adding up.
+                self.inserted += 1;
+                let inserted = start + self.inserted as f64 * step;
+                if inserted < time && self.last_time.is_none_or(|last| inserted > last) {
+                    self.last_time = Some(inserted);
+                    // Without a known value around the first point, there is none for the
+                    // others either, so the rest of the gap is skipped at once.
+                    if let Some(value) = self.value_at(inserted) {
+                        return Some((inserted, value));
+                    }
+                }
+            }
+            let filled = value.or_else(|| self.value_at(time));
+            self.pending = None;
+            self.gap = None;
+            self.last_time = Some(time);
+            if let Some(value) = value {
+                self.before = Some((time, value));
+            }
+            if let Some(value) = filled {
+                return Some((time, value));
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fixed_rate_interpolates() {
+        let samples = [(1., 0.), (1.25, 1.), (2., 4.)];
+        let mut resampled = FixedRate::new(samples, 0.25).into_iter().flatten();
+        for expected in [(1., 0.), (1.25, 1.), (1.5, 2.), (1.75, 3.), (2., 4.)] {
+            assert_eq!(Some(expected), resampled.next());
+        }
+        assert_eq!(None, resampled.next());
+    }
+    #[test]
+    fn test_fixed_rate_skips_gaps_and_bad_samples() {
+        let samples = [
+            (0., 0.),
+            (f64::NAN, 100.),
+            (1., 10.),
+            (0.5, 100.),
+            (4., 40.),
+            (5., 50.),
+        ];
+        let mut skipped = FixedRate::new(samples, 1.)
+            .map(|rate| rate.with_gaps(2., GapPolicy::Skip))
+            .into_iter()
+            .flatten();
+        for expected in [(0., 0.), (1., 10.), (4., 40.), (5., 50.)] {
+            assert_eq!(Some(expected), skipped.next());
+        }
+        assert_eq!(None, skipped.next());
+        let mut long_gap = FixedRate::new([(0., 0.), (1e7, 1.)], 1e-3)
+            .map(|rate| rate.with_gaps(1., GapPolicy::Skip))
+            .into_iter()
+            .flatten();
+        assert_eq!(Some((0., 0.)), long_gap.next());
+        assert_eq!(Some((1e7, 1.)), long_gap.next());
+        assert_eq!(None, long_gap.next());
+    }
+    #[test]
+    fn test_fixed_rate_interpolation_modes() {
+        let samples = [(0., 0.), (1., 10.), (2., 20.)];
+        let mut nearest = FixedRate::new(samples, 0.25)
+            .map(|rate| rate.with_interpolation(Interpolation::Nearest))
+            .into_iter()
+            .flatten()
+            .map(|(_, value)| value);
+        for expected in [0., 0., 10., 10., 10., 10., 20., 20., 20.] {
+            assert_eq!(Some(expected), nearest.next());
+        }
+        assert_eq!(None, nearest.next());
+    }
+    #[test]
+    fn test_fixed_rate_edges() {
+        assert!(FixedRate::new([(0., 1.)], 0.).is_none());
+        assert!(FixedRate::new([(0., 1.)], f64::NAN).is_none());
+        let mut single = FixedRate::new([(3., 1_f32)], 1.).into_iter().flatten();
+        assert_eq!(Some((3., 1.)), single.next());
+        assert_eq!(None, single.next());
+        let empty = FixedRate::new(core::iter::empty::<(f64, f32)>(), 1.).map(|mut r| r.next());
+        assert_eq!(Some(None), empty);
+    }
+    #[test]
+    fn test_fill_gaps_interpolates_by_time() {
+        let samples = [
+            (0., None),
+            (1., Some(10.)),
+            (2., None),
+            (5., None),
+            (6., Some(60.)),
+            (7., None),
+        ];
+        let mut filled = FillGaps::new(samples);
+        for expected in [
+            (0., 10.),
+            (1., 10.),
+            (2., 20.),
+            (5., 50.),
+            (6., 60.),
+            (7., 60.),
+        ] {
+            assert_eq!(Some(expected), filled.next());
+        }
+        assert_eq!(None, filled.next());
+    }
+    #[test]
+    fn test_fill_gaps_inserts_points() {
+        let samples = [(0., Some(0_f32)), (1., None), (2.5, Some(5.))];
+        let mut filled = FillGaps::new(samples).with_max_step(0.5);
+        for expected in [
+            (0., 0.),
+            (0.5, 1.),
+            (1., 2.),
+            (1.5, 3.),
+            (2., 4.),
+            (2.5, 5.),
+        ] {
+            assert_eq!(Some(expected), filled.next());
+        }
+        assert_eq!(None, filled.next());
+        let mut nothing = FillGaps::new([(0., None::<f64>), (10., None)]).with_max_step(1.);
+        assert_eq!(None, nothing.next());
+        let mut nearest = FillGaps::new(samples).with_interpolation(Interpolation::Nearest);
+        for expected in [(0., 0.), (1., 0.), (2.5, 5.)] {
+            assert_eq!(Some(expected), nearest.next());
+        }
+        let mut unlimited = FillGaps::new([(0., Some(1.)), (9., Some(2.))]).with_max_step(-1.);
+        assert_eq!(Some((0., 1.)), unlimited.next());
+        assert_eq!(Some((9., 2.)), unlimited.next());
+    }
+    #[test]
+    fn test_fill_gaps_inserts_exact_points() {
+        let mut tenths = FillGaps::new([(0., Some(0.)), (1., Some(10.))]).with_max_step(0.1);
+        for _ in 0..5 {
+            tenths.next();
+        }
+        assert_eq!(Some((0.5, 5.)), tenths.next());
+        assert_eq!(5, tenths.count());
+        let mut tiny = FillGaps::new([(1e9, Some(0.)), (1e9 + 1., Some(1.)), (1e9 + 2., None)])
+            .with_max_step(1e-12);
+        for expected in [(1e9, 0.), (1e9 + 1., 1.), (1e9 + 2., 1.)] {
+            assert_eq!(Some(expected), tiny.next());
+        }
+        assert_eq!(None, tiny.next());
+        let mut unknown =
+            FillGaps::new([(0., None::<f64>), (1e6, None), (2e6, None)]).with_max_step(1e-9);
+        assert_eq!(None, unknown.next());
+    }
+}
@@ -0,0 +1,101 @@
+//! Mapping servo angles to control pulse widths.
+
+use core::cmp::Ordering;
+
+use crate::MapRange;
+
+/// Maps angles to pulse widths in microseconds for hobby servos.
+///
+/// Angles outside of `angle_range` are clamped, and the final pulse width,
+/// including the trim, never leaves `pulse_range`. This keeps the servo away from its
+/// mechanical end stops no matter what the caller passes in.
+///
+/// ```
+/// use map_to_range::servo::Servo;
+///
+/// let servo = Servo::default();
+/// assert_eq!(Some(1500), servo.pulse_width(90_u8));
+/// assert_eq!(Some(2000), servo.pulse_width(270_u16));
+///
+/// let trimmed = Servo { trim: 25, ..Servo::default() };
+/// assert_eq!(Some(1525), trimmed.pulse_width(90_u8));
+/// assert_eq!(Some(2000), trimmed.pulse_width(180_u8));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Servo {
+    /// The angles in degrees that correspond to the ends of `pulse_range`.
+    /// The first angle has to be smaller than the second.
+    pub angle_range: (f64, f64),
+    /// The pulse widths in microseconds at the ends of `angle_range`.
+    /// Swap them to reverse the direction of the servo.
+    pub pulse_range: (u16, u16),
+    /// An offset in microseconds added to every pulse, to correct the center position.
+    pub trim: i16,
+}
+
+impl Default for Servo {
+    /// 0° to 180° mapped to 1000µs to 2000µs, without trim.
+    fn default() -> Self {
+        Self {
+            angle_range: (0., 180.),
+            pulse_range: (1000, 2000),
+            trim: 0,
+        }
+    }
+}
+
+impl Servo {
+    /// The pulse width in microseconds for `angle` in degrees.
+    ///
+    /// Returns `None` if the angle is `NaN` or the angle range is not increasing.
+    #[must_use]
+    pub fn pulse_width<T: MapRange>(&self, angle: T) -> Option<u16> {
+        let (lowest_angle, highest_angle) = self.angle_range;
+        if lowest_angle.partial_cmp(&highest_angle) != Some(Ordering::Less) {
+            return None;
+        }
+        let angle = angle.checked_f64_cast()?;
+        if angle.is_nan() {
+            return None;
+        }
+        let pulse_range = (self.pulse_range.0 as f64, self.pulse_range.1 as f64);
+        let pulse = angle
+            .clamp(lowest_angle, highest_angle)
+            .map_range(self.angle_range, pulse_range)?
+            + self.trim as f64;
+        let lowest_pulse = pulse_range.0.min(pulse_range.1);
+        let highest_pulse = pulse_range.0.max(pulse_range.1);
+        Some((pulse.clamp(lowest_pulse, highest_pulse) + 0.5) as u16)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pulse_width() {
+        let servo = Servo::default();
+        assert_eq!(Some(1000), servo.pulse_width(0_u8));
+        assert_eq!(Some(1000), servo.pulse_width(-10_i8));
+        assert_eq!(Some(1250), servo.pulse_width(45_f32));
+        assert_eq!(Some(1003), servo.pulse_width(0.5_f32));
+        assert_eq!(None, servo.pulse_width(f64::NAN));
+    }
+    #[test]
+    fn test_reversed_and_trimmed() {
+        let servo = Servo {
+            angle_range: (-90., 90.),
+            pulse_range: (2400, 600),
+            trim: -50,
+        };
+        assert_eq!(Some(2350), servo.pulse_width(-90_i16));
+        assert_eq!(Some(1450), servo.pulse_width(0_i16));
+        assert_eq!(Some(600), servo.pulse_width(90_i16));
+        let broken = Servo {
+            angle_range: (90., -90.),
+            ..servo
+        };
+        assert_eq!(None, broken.pulse_width(0_i16));
+    }
+}
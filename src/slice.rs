@@ -0,0 +1,390 @@
+//! Mapping whole buffers at once.
+
+use crate::policy::{self, OutOfRangePolicy};
+use crate::tracker::{MinMaxTracker, RunningStats};
+use crate::{lerp::Lerp, math, MapRange};
+
+/// How fractional results are turned into the output type.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Quantization {
+    /// Every element is cast on its own and the fractional part is dropped,
+    /// exactly like [`MapRange::map_range`] does.
+    #[default]
+    Truncate,
+    /// The fractional part that is lost on one element is carried over to the next one.
+    ///
+    /// This keeps the sum of the buffer correct, so downsampling audio or LED frames
+    /// to fewer bits does not lose energy.
+    ErrorDiffusion,
+}
+
+/// Maps every element of `input` from `from_range` to `to_range` and writes it into `output`.
+///
+/// Returns `None` if the buffers differ in length, or as soon as an element is outside of
+/// `from_range`, leaving the rest of `output` untouched.
+///
+/// ```
+/// use map_to_range::slice::{map_slice_into, Quantization};
+///
+/// let input = [100_u16; 4];
+/// let mut output = [0_u8; 4];
+///
+/// map_slice_into(&input, (0, 1000), &mut output, (0, 10), Quantization::Truncate);
+/// assert_eq!([1, 1, 1, 1], output);
+///
+/// // 1.5 on average, like the input
+/// let input = [150_u16; 4];
+/// map_slice_into(&input, (0, 1000), &mut output, (0, 10), Quantization::ErrorDiffusion);
+/// assert_eq!([1, 2, 1, 2], output);
+/// ```
+pub fn map_slice_into<T: MapRange, U: MapRange>(
+    input: &[T],
+    from_range: (T, T),
+    output: &mut [U],
+    to_range: (U, U),
+    quantization: Quantization,
+) -> Option<()> {
+    if input.len() != output.len() {
+        return None;
+    }
+    let from = (
+        from_range.0.checked_f64_cast()?,
+        from_range.1.checked_f64_cast()?,
+    );
+    let to = (
+        to_range.0.checked_f64_cast()?,
+        to_range.1.checked_f64_cast()?,
+    );
+    let (lowest, highest) = (to.0.min(to.1), to.0.max(to.1));
+    let mut carry = 0.;
+    for (value, slot) in input.iter().zip(output) {
+        let exact = value.checked_f64_cast()?.map_range(from, to)?;
+        *slot = match quantization {
+            Quantization::Truncate => U::checked_cast_back(exact)?,
+            Quantization::ErrorDiffusion => {
+                let wanted = exact + carry;
+                let quantized = U::checked_cast_back(wanted.clamp(lowest, highest))?;
+                // Limit the carry, so a saturated output does not bias the following elements.
+                carry = (wanted - quantized.checked_f64_cast()?).clamp(-1., 1.);
+                quantized
+            }
+        };
+    }
+    Some(())
+}
+
+/// Maps every element of `values` in place from the range between the smallest and largest
+/// element onto `to_range`.
+///
+/// Returns the smallest and largest element, to undo the normalization later, or `None` if
+/// all elements are equal or there are none. An element that cannot be mapped, like `NaN`,
+/// also returns `None`, leaving it and all elements after it untouched.
+///
+/// ```
+/// use map_to_range::slice::normalize_slice;
+///
+/// let mut capture = [20., 30., 25., 40.];
+/// assert_eq!(Some((20., 40.)), normalize_slice(&mut capture, (0., 1.)));
+/// assert_eq!([0., 0.5, 0.25, 1.], capture);
+/// ```
+pub fn normalize_slice<T: MapRange>(values: &mut [T], to_range: (T, T)) -> Option<(T, T)> {
+    let bounds = values
+        .iter()
+        .copied()
+        .collect::<MinMaxTracker<T>>()
+        .range()?;
+    for value in values {
+        *value = value.map_range(bounds, to_range)?;
+    }
+    Some(bounds)
+}
+
+/// Rescales `values` in place to a mean of zero and a standard deviation of one, their z-scores.
+///
+/// Returns the mean and standard deviation before rescaling, or `None` and leaves `values`
+/// untouched if all of them are equal, there are none or one is not finite.
+///
+/// ```
+/// use map_to_range::slice::standardize_slice;
+///
+/// let mut values = [2., 4., 4., 4., 5., 5., 7., 9.];
+/// assert_eq!(Some((5., 2.)), standardize_slice(&mut values));
+/// assert_eq!([-1.5, -0.5, -0.5, -0.5, 0., 0., 1., 2.], values);
+/// ```
+pub fn standardize_slice(values: &mut [f32]) -> Option<(f64, f64)> {
+    let (mean, std_dev) = statistics(values)?;
+    for value in values {
+        *value = ((f64::from(*value) - mean) / std_dev) as f32;
+    }
+    Some((mean, std_dev))
+}
+
+/// Standardizes `values` like [`standardize_slice`], then maps z-scores from `-sigmas..=sigmas`
+/// onto `to_range`, clamping everything further out.
+///
+/// Returns the mean and standard deviation, or `None` and leaves `values` untouched if all of
+/// them are equal, there are none, one is not finite or `sigmas` is not positive.
+///
+/// ```
+/// use map_to_range::slice::standardize_slice_clamped;
+///
+/// let mut values = [2., 4., 4., 4., 5., 5., 7., 9.];
+/// standardize_slice_clamped(&mut values, 1., (0., 1.));
+/// assert_eq!([0., 0.25, 0.25, 0.25, 0.5, 0.5, 1., 1.], values);
+/// ```
+pub fn standardize_slice_clamped(
+    values: &mut [f32],
+    sigmas: f64,
+    to_range: (f32, f32),
+) -> Option<(f64, f64)> {
+    let (mean, std_dev) = statistics(values)?;
+    let to_range = (f64::from(to_range.0), f64::from(to_range.1));
+    let from_range = (-sigmas, sigmas);
+    // Rejects sigmas that are not positive, before anything is written.
+    policy::map_f64(0., from_range, to_range, OutOfRangePolicy::Clamp)?;
+    for value in values {
+        let z = (f64::from(*value) - mean) / std_dev;
+        let mapped = policy::map_f64(z, from_range, to_range, OutOfRangePolicy::Clamp)?;
+        *value = mapped as f32;
+    }
+    Some((mean, std_dev))
+}
+
+/// The mean and a non zero standard deviation of finite `values`.
+fn statistics(values: &[f32]) -> Option<(f64, f64)> {
+    if values.iter().any(|value| !value.is_finite()) {
+        return None;
+    }
+    let stats: RunningStats = values.iter().map(|value| f64::from(*value)).collect();
+    let std_dev = stats.std_dev().filter(|std_dev| *std_dev > 0.)?;
+    Some((stats.mean()?, std_dev))
+}
+
+/// How the two buffers of [`crossfade_slices`] are weighted.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum FadeLaw {
+    /// The gains add up to one. Right for correlated signals, like two takes of the same loop
+    /// or two renderings of the same scene.
+    #[default]
+    Linear,
+    /// The squared gains add up to one, so uncorrelated audio keeps its loudness and does not
+    /// dip in the middle of the fade.
+    EqualPower,
+}
+
+/// Blends `a` at `t = 0` into `b` at `t = 1` and writes the result into `output`.
+///
+/// `T::default()` has to be silence, like zero for samples.
+/// Returns `None` if the buffers differ in length or `t` is outside of `0..=1`.
+///
+/// ```
+/// use map_to_range::slice::{crossfade_slices, FadeLaw};
+///
+/// let mut output = [0_i16; 2];
+/// crossfade_slices(&[1000, 0], &[0, 1000], 0.5, FadeLaw::Linear, &mut output);
+/// assert_eq!([500, 500], output);
+/// crossfade_slices(&[1000, 0], &[0, 1000], 0.5, FadeLaw::EqualPower, &mut output);
+/// assert_eq!([707, 707], output);
+/// ```
+pub fn crossfade_slices<T: Lerp + Default>(
+    a: &[T],
+    b: &[T],
+    t: f64,
+    law: FadeLaw,
+    output: &mut [T],
+) -> Option<()> {
+    if a.len() != b.len() || a.len() != output.len() || !(0. ..=1.).contains(&t) {
+        return None;
+    }
+    let (gain_a, gain_b) = (math::sqrt(1. - t), math::sqrt(t));
+    for ((a, b), slot) in a.iter().zip(b).zip(output) {
+        *slot = match law {
+            FadeLaw::Linear => a.lerp(*b, t),
+            FadeLaw::EqualPower => a.mix(gain_a, *b, gain_b),
+        };
+    }
+    Some(())
+}
+
+/// Maps every element of `input` like [`map_slice_into`], into a new `Vec`.
+///
+/// ```
+/// use map_to_range::slice::{map_range_vec, Quantization};
+///
+/// let percent = map_range_vec(&[0_u16, 512, 1023], (0, 1023), (0_u8, 100), Quantization::Truncate);
+/// assert_eq!(Some(vec![0, 50, 100]), percent);
+/// ```
+#[cfg(feature = "alloc")]
+#[must_use]
+pub fn map_range_vec<T: MapRange, U: MapRange>(
+    input: &[T],
+    from_range: (T, T),
+    to_range: (U, U),
+    quantization: Quantization,
+) -> Option<alloc::vec::Vec<U>> {
+    let mut output = alloc::vec![to_range.0; input.len()];
+    map_slice_into(input, from_range, &mut output, to_range, quantization)?;
+    Some(output)
+}
+
+/// A lookup table of `len` entries, where entry `i` is `shape(i / (len - 1))` mapped from
+/// `0..=1` onto `to_range`.
+///
+/// ```
+/// use map_to_range::{easing::Easing, slice::lookup_table};
+///
+/// let gamma = lookup_table(5, (0_u8, 255), |t| Easing::QuadIn.apply(t));
+/// assert_eq!(Some(vec![0, 15, 63, 143, 255]), gamma);
+/// ```
+///
+/// Returns `None` if `len` is below 2 or an entry does not fit into `T`.
+#[cfg(feature = "alloc")]
+#[must_use]
+pub fn lookup_table<T: MapRange>(
+    len: usize,
+    to_range: (T, T),
+    shape: impl Fn(f64) -> f64,
+) -> Option<alloc::vec::Vec<T>> {
+    if len < 2 {
+        return None;
+    }
+    let to = (
+        to_range.0.checked_f64_cast()?,
+        to_range.1.checked_f64_cast()?,
+    );
+    let last = (len - 1) as f64;
+    (0..len)
+        .map(|index| {
+            let shaped = shape(index as f64 / last);
+            T::checked_cast_back(shaped.map_range((0., 1.), to)?)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_error_diffusion_keeps_sum() {
+        let input = [0.3_f32; 100];
+        let mut output = [0_u8; 100];
+        let result = map_slice_into(
+            &input,
+            (0., 1.),
+            &mut output,
+            (0, 1),
+            Quantization::Truncate,
+        );
+        assert_eq!(Some(()), result);
+        assert_eq!(0, output.iter().map(|value| *value as u32).sum::<u32>());
+        let result = map_slice_into(
+            &input,
+            (0., 1.),
+            &mut output,
+            (0, 1),
+            Quantization::ErrorDiffusion,
+        );
+        assert_eq!(Some(()), result);
+        assert_eq!(30, output.iter().map(|value| *value as u32).sum::<u32>());
+    }
+    #[test]
+    fn test_error_diffusion_stays_in_range() {
+        let input = [-0.5_f64, 1., 1., -1., -1.];
+        let mut output = [0_i8; 5];
+        let result = map_slice_into(
+            &input,
+            (-1., 1.),
+            &mut output,
+            (-100, 100),
+            Quantization::ErrorDiffusion,
+        );
+        assert_eq!(Some(()), result);
+        assert_eq!([-50, 100, 100, -100, -100], output);
+    }
+    #[test]
+    fn test_rejects() {
+        let mut output = [0_u8; 2];
+        let mismatched =
+            map_slice_into(&[1_u8], (0, 1), &mut output, (0, 1), Quantization::Truncate);
+        assert_eq!(None, mismatched);
+        let outside = map_slice_into(
+            &[0_u8, 2],
+            (0, 1),
+            &mut output,
+            (0, 9),
+            Quantization::Truncate,
+        );
+        assert_eq!(None, outside);
+    }
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn test_vec_helpers() {
+        let mapped = map_range_vec(&[0.5_f32, 2.], (0., 1.), (0_u8, 10), Quantization::Truncate);
+        assert_eq!(None, mapped);
+        assert_eq!(
+            Some(alloc::vec![0, 5, 10]),
+            lookup_table(3, (0_u8, 10), |t| t)
+        );
+        assert_eq!(None, lookup_table(1, (0_u8, 10), |t| t));
+        assert_eq!(None, lookup_table(3, (0_u8, 10), |t| t * 2.));
+    }
+    #[test]
+    fn test_normalize_slice() {
+        let mut pixels = [10_u8, 60, 35];
+        assert_eq!(Some((10, 60)), normalize_slice(&mut pixels, (0, 255)));
+        assert_eq!([0, 255, 127], pixels);
+        let mut flat = [3_i32; 4];
+        assert_eq!(None, normalize_slice(&mut flat, (0, 1)));
+        assert_eq!(None, normalize_slice::<f32>(&mut [], (0., 1.)));
+        let mut with_nan = [0., f64::NAN, 2.];
+        assert_eq!(None, normalize_slice(&mut with_nan, (0., 1.)));
+        assert_eq!(Some(0.), with_nan.first().copied());
+    }
+    #[test]
+    fn test_standardize_slice() {
+        let mut values = [1_f32, 3.];
+        assert_eq!(Some((2., 1.)), standardize_slice(&mut values));
+        assert_eq!(Some([-1., 1.]), Some(values));
+        let mut flat = [5_f32; 3];
+        assert_eq!(None, standardize_slice(&mut flat));
+        assert_eq!(None, standardize_slice(&mut []));
+        let mut infinite = [1., f32::INFINITY];
+        assert_eq!(None, standardize_slice_clamped(&mut infinite, 2., (0., 1.)));
+        let mut values = [1_f32, 3., 2.];
+        assert_eq!(None, standardize_slice_clamped(&mut values, 0., (0., 1.)));
+        assert_eq!(Some([1., 3., 2.]), Some(values));
+        let mapped = standardize_slice_clamped(&mut values, 3., (-128., 127.));
+        assert!(mapped.is_some());
+        assert_eq!(Some(-0.5), values.last().copied());
+    }
+    #[test]
+    fn test_crossfade_slices() {
+        let a = [1_f64, -1.];
+        let b = [0., 0.5];
+        let mut output = [0.; 2];
+        for law in [FadeLaw::Linear, FadeLaw::EqualPower] {
+            assert_eq!(Some(()), crossfade_slices(&a, &b, 0., law, &mut output));
+            assert_eq!(Some(a), Some(output));
+            assert_eq!(Some(()), crossfade_slices(&a, &b, 1., law, &mut output));
+            assert_eq!(Some(b), Some(output));
+            assert_eq!(None, crossfade_slices(&a, &b, 1.5, law, &mut output));
+            assert_eq!(None, crossfade_slices(&a, &b[..1], 0.5, law, &mut output));
+        }
+        assert_eq!(
+            Some(()),
+            crossfade_slices(&a, &a, 0.5, FadeLaw::EqualPower, &mut output)
+        );
+        assert!((output[0] - core::f64::consts::SQRT_2).abs() < 1e-15);
+        let mut loud = [0_i16; 3];
+        let faded = crossfade_slices(
+            &[30_000, -32_768, 20_000],
+            &[0, 0, 20_000],
+            0.5,
+            FadeLaw::EqualPower,
+            &mut loud,
+        );
+        assert_eq!(Some(()), faded);
+        assert_eq!([21_213, -23_170, 28_284], loud);
+    }
+}
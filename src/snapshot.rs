@@ -0,0 +1,225 @@
+//! Smoothing timestamped state that arrives irregularly, e.g. over a network.
+
+use crate::lerp::Lerp;
+
+/// A ring buffer of the last `N` timestamped values, sampled with a delay.
+///
+/// Rendering slightly in the past means there usually are snapshots on both sides of the
+/// render time to interpolate between. When the newest snapshot is too old, the state is
+/// extrapolated from the last two snapshots for at most `max_extrapolation`, and held afterwards.
+///
+/// ```
+/// use map_to_range::snapshot::SnapshotBuffer;
+///
+/// let mut buffer = SnapshotBuffer::<f64, 8>::new(0.25).with_max_extrapolation(0.25);
+/// buffer.push(1.0, 10.);
+/// buffer.push(1.5, 20.);
+/// assert_eq!(Some(15.), buffer.sample(1.5));
+/// assert_eq!(Some(25.), buffer.sample(2.));
+/// // Extrapolation stops 0.25 after the newest snapshot.
+/// assert_eq!(Some(25.), buffer.sample(3.));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SnapshotBuffer<T, const N: usize> {
+    snapshots: [Option<(f64, T)>; N],
+    oldest: usize,
+    len: usize,
+    delay: f64,
+    max_extrapolation: f64,
+}
+
+impl<T: Lerp, const N: usize> SnapshotBuffer<T, N> {
+    /// An empty buffer, sampling `delay` behind the requested render time, without extrapolation.
+    #[must_use]
+    pub fn new(delay: f64) -> Self {
+        Self {
+            snapshots: [None; N],
+            oldest: 0,
+            len: 0,
+            delay,
+            max_extrapolation: 0.,
+        }
+    }
+    /// Allows extrapolating up to `horizon` past the newest snapshot.
+    #[must_use]
+    pub fn with_max_extrapolation(mut self, horizon: f64) -> Self {
+        self.max_extrapolation = horizon;
+        self
+    }
+    /// Adds a snapshot, dropping the oldest one when the buffer is full.
+    ///
+    /// Returns `None` and ignores the snapshot if `time` is not after the newest snapshot,
+    /// or the buffer has no capacity.
+    pub fn push(&mut self, time: f64, value: T) -> Option<()> {
+        if N == 0 || self.newest().is_some_and(|(newest, _)| time <= newest) || time.is_nan() {
+            return None;
+        }
+        let index = (self.oldest + self.len) % N;
+        *self.snapshots.get_mut(index)? = Some((time, value));
+        if self.len == N {
+            self.oldest = (self.oldest + 1) % N;
+        } else {
+            self.len += 1;
+        }
+        Some(())
+    }
+    /// The state at `render_time - delay`.
+    ///
+    /// Returns `None` if the buffer is empty or the time is `NaN`.
+    #[must_use]
+    pub fn sample(&self, render_time: f64) -> Option<T> {
+        let time = render_time - self.delay;
+        if time.is_nan() {
+            return None;
+        }
+        let (first_time, first) = self.get(0)?;
+        if time <= first_time {
+            return Some(first);
+        }
+        let mut previous = (first_time, first);
+        for index in 1..self.len {
+            let next = self.get(index)?;
+            if time <= next.0 {
+                return Some(
+                    previous
+                        .1
+                        .lerp(next.1, (time - previous.0) / (next.0 - previous.0)),
+                );
+            }
+            previous = next;
+        }
+        // Past the newest snapshot, `previous` is the newest one.
+        let Some(before) = self.len.checked_sub(2).and_then(|index| self.get(index)) else {
+            return Some(previous.1);
+        };
+        let ahead = (time - previous.0).min(self.max_extrapolation).max(0.);
+        let t = (previous.0 + ahead - before.0) / (previous.0 - before.0);
+        Some(before.1.lerp(previous.1, t))
+    }
+    /// The newest snapshot, if any.
+    #[must_use]
+    pub fn newest(&self) -> Option<(f64, T)> {
+        self.get(self.len.checked_sub(1)?)
+    }
+    /// The snapshot at `index`, counted from the oldest one.
+    #[must_use]
+    pub fn get(&self, index: usize) -> Option<(f64, T)> {
+        if index >= self.len {
+            return None;
+        }
+        *self.snapshots.get((self.oldest + index) % N)?
+    }
+    /// The number of stored snapshots.
+    #[must_use]
+    pub const fn len(&self) -> usize {
+        self.len
+    }
+    /// Returns `true` if there are no snapshots.
+    #[must_use]
+    pub const fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+    /// Removes all snapshots.
+    pub fn clear(&mut self) {
+        self.len = 0;
+    }
+}
+
+/// Predicts a value ahead of the last received samples, by continuing the line
+/// through the last two samples.
+///
+/// Predictions stop moving `horizon` after the newest sample, so a lost link does not
+/// let the value run away.
+///
+/// ```
+/// use map_to_range::snapshot::DeadReckoning;
+///
+/// let mut position = DeadReckoning::new(0.5);
+/// position.update(0., 0_f64);
+/// position.update(1., 2.);
+/// assert_eq!(Some(3.), position.predict(1.5));
+/// assert_eq!(Some(3.), position.predict(10.));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DeadReckoning<T> {
+    samples: SnapshotBuffer<T, 2>,
+}
+
+impl<T: Lerp> DeadReckoning<T> {
+    /// An extrapolator without samples, predicting at most `horizon` past the newest sample.
+    #[must_use]
+    pub fn new(horizon: f64) -> Self {
+        Self {
+            samples: SnapshotBuffer::new(0.).with_max_extrapolation(horizon),
+        }
+    }
+    /// Adds a received sample.
+    ///
+    /// Returns `None` and ignores the sample if `time` is not after the newest sample.
+    pub fn update(&mut self, time: f64, value: T) -> Option<()> {
+        self.samples.push(time, value)
+    }
+    /// The predicted value at `time`.
+    ///
+    /// With only one sample, that sample is held. Returns `None` without any samples.
+    #[must_use]
+    pub fn predict(&self, time: f64) -> Option<T> {
+        self.samples.sample(time)
+    }
+    /// Returns `true` if `time` is further than the horizon past the newest sample,
+    /// so predictions have stopped moving.
+    #[must_use]
+    pub fn is_stale(&self, time: f64) -> bool {
+        self.samples
+            .newest()
+            .is_none_or(|(newest, _)| time - newest > self.samples.max_extrapolation)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_interpolates_between_snapshots() {
+        let mut buffer = SnapshotBuffer::<[f32; 2], 3>::new(0.);
+        assert_eq!(None, buffer.sample(0.));
+        assert_eq!(Some(()), buffer.push(0., [0., 0.]));
+        assert_eq!(Some([0., 0.]), buffer.sample(5.));
+        assert_eq!(Some(()), buffer.push(1., [10., -10.]));
+        assert_eq!(Some(()), buffer.push(3., [30., -30.]));
+        assert_eq!(Some([20., -20.]), buffer.sample(2.));
+        assert_eq!(Some([0., 0.]), buffer.sample(-1.));
+        assert_eq!(None, buffer.push(3., [0., 0.]));
+    }
+    #[test]
+    fn test_drops_oldest() {
+        let mut buffer = SnapshotBuffer::<f64, 2>::new(0.5).with_max_extrapolation(1.);
+        for (time, value) in [(0., 0.), (1., 10.), (2., 30.)] {
+            assert_eq!(Some(()), buffer.push(time, value));
+        }
+        assert_eq!(2, buffer.len());
+        assert_eq!(Some((1., 10.)), buffer.get(0));
+        assert_eq!(Some(10.), buffer.sample(1.));
+        assert_eq!(Some(20.), buffer.sample(2.));
+        assert_eq!(Some(40.), buffer.sample(3.));
+        assert_eq!(Some(50.), buffer.sample(10.));
+        buffer.clear();
+        assert!(buffer.is_empty());
+        assert_eq!(None, SnapshotBuffer::<f64, 0>::new(0.).push(0., 0.));
+    }
+    #[test]
+    fn test_dead_reckoning() {
+        let mut heading = DeadReckoning::new(2.);
+        assert_eq!(None, heading.predict(0.));
+        assert!(heading.is_stale(0.));
+        assert_eq!(Some(()), heading.update(1., [0_f32, 10.]));
+        assert_eq!(Some([0., 10.]), heading.predict(2.));
+        assert_eq!(Some(()), heading.update(2., [1., 8.]));
+        assert_eq!(Some([3., 4.]), heading.predict(4.));
+        assert_eq!(Some([3., 4.]), heading.predict(5.));
+        assert!(!heading.is_stale(4.));
+        assert!(heading.is_stale(4.5));
+        assert_eq!(None, heading.update(2., [0., 0.]));
+    }
+}
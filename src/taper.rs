@@ -0,0 +1,87 @@
+//! Response curves of potentiometers, for knobs that should feel like their analog counterparts.
+
+use crate::{easing::Easing, math};
+
+/// The resistance curve of a potentiometer, applied to the normalized knob position.
+///
+/// ```
+/// use map_to_range::mapper::Mapper;
+/// use map_to_range::taper::Taper;
+///
+/// let volume = Mapper::from(0..=1000).to(0..=1000).taper(Taper::Audio).build().unwrap();
+/// assert_eq!(Some(100), volume.map(500));
+/// assert_eq!(Some(1000), volume.map(1000));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Taper {
+    /// The output follows the knob position, a "B" taper.
+    #[default]
+    Linear,
+    /// A logarithmic "A" taper for volume knobs, at 10% of the output in the middle of the travel.
+    ///
+    /// Loudness is perceived logarithmically, so this feels even across the whole travel.
+    Audio,
+    /// The mirrored audio taper, a "C" taper, at 90% of the output in the middle of the travel.
+    ReverseAudio,
+    /// Slow at both ends and fast in the middle, for fine control near the limits.
+    SCurve,
+}
+
+impl Taper {
+    /// Applies the taper to the knob position `t` in `0..=1`. Values outside of it are clamped.
+    ///
+    /// ```
+    /// use map_to_range::taper::Taper;
+    ///
+    /// assert_eq!(0.5, Taper::Linear.apply(0.5));
+    /// assert!((Taper::Audio.apply(0.5) - 0.1).abs() < 1e-15);
+    /// assert_eq!(1., Taper::ReverseAudio.apply(3.));
+    /// ```
+    #[must_use]
+    pub fn apply(self, t: f64) -> f64 {
+        let t = t.clamp(0., 1.);
+        match self {
+            Self::Linear => t,
+            Self::Audio => audio(t),
+            Self::ReverseAudio => 1. - audio(1. - t),
+            Self::SCurve => Easing::SmoothStep.apply(t),
+        }
+    }
+}
+
+/// `(81^t - 1) / 80`, the exponential through `(0, 0)`, `(0.5, 0.1)` and `(1, 1)`.
+fn audio(t: f64) -> f64 {
+    if t >= 1. {
+        return 1.;
+    }
+    (math::exp(t * LN_81) - 1.) / 80.
+}
+
+const LN_81: f64 = 4. * 1.098_612_288_668_109_8;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tapers() {
+        for taper in [
+            Taper::Linear,
+            Taper::Audio,
+            Taper::ReverseAudio,
+            Taper::SCurve,
+        ] {
+            assert_eq!(Some(0.), Some(taper.apply(0.)));
+            assert_eq!(Some(1.), Some(taper.apply(1.)));
+            assert_eq!(Some(0.), Some(taper.apply(-1.)));
+            let mut previous = 0.;
+            for step in 1..=100 {
+                let value = taper.apply(f64::from(step) / 100.);
+                assert!(value > previous);
+                previous = value;
+            }
+        }
+        assert!((Taper::ReverseAudio.apply(0.5) - 0.9).abs() < 1e-15);
+        assert_eq!(Some(0.5), Some(Taper::SCurve.apply(0.5)));
+    }
+}
@@ -0,0 +1,185 @@
+//! Converting ADC readings of an NTC thermistor in a voltage divider into temperatures.
+
+use crate::{math, MapRange};
+
+/// Difference between degrees celsius and kelvin.
+const ZERO_CELSIUS: f64 = 273.15;
+
+/// How the resistance of a thermistor relates to its temperature.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ThermistorModel {
+    /// The beta equation `1/T = 1/T0 + ln(R/R0)/beta`, as found in most datasheets.
+    Beta {
+        /// The beta coefficient in kelvin.
+        beta: f64,
+        /// The resistance in ohms at `nominal_temperature`.
+        nominal_resistance: f64,
+        /// The temperature in degrees celsius the nominal resistance is specified at, usually 25.
+        nominal_temperature: f64,
+    },
+    /// The Steinhart-Hart equation `1/T = a + b*ln(R) + c*ln(R)³`, with T in kelvin
+    /// and R in ohms.
+    SteinhartHart {
+        /// The constant coefficient.
+        a: f64,
+        /// The linear coefficient.
+        b: f64,
+        /// The cubic coefficient.
+        c: f64,
+    },
+}
+
+impl ThermistorModel {
+    /// The temperature in degrees celsius at `resistance` ohms.
+    ///
+    /// Returns `None` if the resistance is not positive or the model produces no valid temperature.
+    #[must_use]
+    pub fn temperature(&self, resistance: f64) -> Option<f64> {
+        if resistance.is_nan() || resistance <= 0. {
+            return None;
+        }
+        let inverse = match *self {
+            ThermistorModel::Beta {
+                beta,
+                nominal_resistance,
+                nominal_temperature,
+            } => {
+                1. / (nominal_temperature + ZERO_CELSIUS)
+                    + math::ln(resistance / nominal_resistance) / beta
+            }
+            ThermistorModel::SteinhartHart { a, b, c } => {
+                let ln = math::ln(resistance);
+                a + b * ln + c * ln * ln * ln
+            }
+        };
+        let kelvin = 1. / inverse;
+        (kelvin.is_finite() && kelvin > 0.).then_some(kelvin - ZERO_CELSIUS)
+    }
+}
+
+/// Where the thermistor sits in the voltage divider measured by the ADC.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Divider {
+    /// The thermistor connects the ADC input to ground, the series resistor connects it to the
+    /// reference voltage.
+    #[default]
+    ThermistorToGround,
+    /// The thermistor connects the ADC input to the reference voltage, the series resistor
+    /// connects it to ground.
+    ThermistorToReference,
+}
+
+/// The full pipeline from ADC counts over resistance to temperature.
+///
+/// ```
+/// use map_to_range::thermistor::{Divider, Thermistor, ThermistorModel};
+///
+/// let thermistor = Thermistor {
+///     model: ThermistorModel::Beta {
+///         beta: 3950.,
+///         nominal_resistance: 10_000.,
+///         nominal_temperature: 25.,
+///     },
+///     series_resistance: 10_000.,
+///     full_scale: 4095.,
+///     divider: Divider::ThermistorToGround,
+/// };
+/// // Half of the reference voltage means both resistors are equal.
+/// assert_eq!(Some(25_i8), thermistor.temperature(2047.5_f32));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Thermistor {
+    /// The relation between resistance and temperature.
+    pub model: ThermistorModel,
+    /// The resistance in ohms of the fixed resistor in the divider.
+    pub series_resistance: f64,
+    /// The ADC reading at the reference voltage, e.g. `4095` for a 12 bit ADC.
+    pub full_scale: f64,
+    /// The layout of the divider.
+    pub divider: Divider,
+}
+
+impl Thermistor {
+    /// The resistance of the thermistor in ohms for an ADC reading.
+    ///
+    /// Returns `None` if the reading is outside of `(0, full_scale)`, as the resistance
+    /// would be zero or infinite there.
+    #[must_use]
+    pub fn resistance<T: MapRange>(&self, counts: T) -> Option<f64> {
+        let ratio = counts.checked_f64_cast()? / self.full_scale;
+        if !(ratio > 0. && ratio < 1.) {
+            return None;
+        }
+        let resistance = match self.divider {
+            Divider::ThermistorToGround => self.series_resistance * ratio / (1. - ratio),
+            Divider::ThermistorToReference => self.series_resistance * (1. - ratio) / ratio,
+        };
+        Some(resistance)
+    }
+    /// The temperature in degrees celsius for an ADC reading, without any casting.
+    #[must_use]
+    pub fn temperature_f64<T: MapRange>(&self, counts: T) -> Option<f64> {
+        self.model.temperature(self.resistance(counts)?)
+    }
+    /// The temperature in degrees celsius for an ADC reading.
+    ///
+    /// Returns `None` if the reading is invalid or the temperature does not fit into `U`.
+    #[must_use]
+    pub fn temperature<T: MapRange, U: MapRange>(&self, counts: T) -> Option<U> {
+        U::checked_cast_back(self.temperature_f64(counts)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BETA: ThermistorModel = ThermistorModel::Beta {
+        beta: 3950.,
+        nominal_resistance: 10_000.,
+        nominal_temperature: 25.,
+    };
+
+    #[test]
+    fn test_beta_model() {
+        let at_nominal = BETA.temperature(10_000.);
+        assert!(at_nominal.is_some_and(|t| (t - 25.).abs() < 1e-9));
+        // A typical 10k NTC with beta 3950 has about 33.6k at 0°C.
+        let freezing = BETA.temperature(33_620.);
+        assert!(freezing.is_some_and(|t| t.abs() < 0.1));
+        assert_eq!(None, BETA.temperature(0.));
+        assert_eq!(None, BETA.temperature(-1.));
+    }
+    #[test]
+    fn test_steinhart_hart_model() {
+        // Coefficients of a common 10k NTC.
+        let model = ThermistorModel::SteinhartHart {
+            a: 1.009_249_522e-3,
+            b: 2.378_405_444e-4,
+            c: 2.019_202_697e-7,
+        };
+        let room = model.temperature(10_000.);
+        assert!(room.is_some_and(|t| (t - 25.).abs() < 0.5));
+    }
+    #[test]
+    fn test_divider() {
+        let mut thermistor = Thermistor {
+            model: BETA,
+            series_resistance: 10_000.,
+            full_scale: 1023.,
+            divider: Divider::ThermistorToGround,
+        };
+        let low = thermistor.resistance(255.75_f64);
+        assert!(low.is_some_and(|r| (r - 10_000. / 3.).abs() < 1e-6));
+        thermistor.divider = Divider::ThermistorToReference;
+        let high = thermistor.resistance(255.75_f64);
+        assert!(high.is_some_and(|r| (r - 30_000.).abs() < 1e-6));
+        assert_eq!(None, thermistor.resistance(0_u16));
+        assert_eq!(None, thermistor.resistance(1023_u16));
+        assert_eq!(None, thermistor.temperature::<u16, u8>(1200));
+        // Hotter than nominal, so the ntc has less resistance than the series resistor.
+        assert!(thermistor
+            .temperature::<u16, f32>(700)
+            .is_some_and(|t| t > 25.));
+    }
+}
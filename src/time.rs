@@ -0,0 +1,106 @@
+//! Mapping points in time within a time span, for progress bars and time axes.
+//!
+//! All positions are computed from whole nanoseconds, without a detour through epoch seconds.
+
+use core::time::Duration;
+
+use crate::MapRange;
+
+/// Maps `elapsed` within `0..=span` onto `to_range`.
+///
+/// Returns `None` if `span` is zero, `elapsed` is longer than `span` or the result does not fit
+/// into `U`.
+///
+/// ```
+/// use core::time::Duration;
+/// use map_to_range::time::map_duration;
+///
+/// let song = Duration::from_secs(200);
+/// assert_eq!(Some(25_u8), map_duration(Duration::from_secs(50), song, (0, 100)));
+/// ```
+#[must_use]
+pub fn map_duration<U: MapRange>(elapsed: Duration, span: Duration, to_range: (U, U)) -> Option<U> {
+    let position = elapsed.as_nanos() as f64;
+    let span = span.as_nanos() as f64;
+    let to_range = (
+        to_range.0.checked_f64_cast()?,
+        to_range.1.checked_f64_cast()?,
+    );
+    U::checked_cast_back(position.map_range((0., span), to_range)?)
+}
+
+/// Maps `time` within the span from `start` to `end` onto `to_range`.
+///
+/// Returns `None` if `time` lies outside of the span, the span is empty or reversed or the
+/// result does not fit into `U`.
+///
+/// ```
+/// use std::time::{Duration, SystemTime};
+/// use map_to_range::time::map_system_time;
+///
+/// let start = SystemTime::UNIX_EPOCH + Duration::from_secs(1_700_000_000);
+/// let end = start + Duration::from_secs(3600);
+/// let x = map_system_time(start + Duration::from_secs(900), (start, end), (0_u16, 800));
+/// assert_eq!(Some(200), x);
+/// ```
+#[cfg(feature = "std")]
+#[must_use]
+pub fn map_system_time<U: MapRange>(
+    time: std::time::SystemTime,
+    span: (std::time::SystemTime, std::time::SystemTime),
+    to_range: (U, U),
+) -> Option<U> {
+    let elapsed = time.duration_since(span.0).ok()?;
+    map_duration(elapsed, span.1.duration_since(span.0).ok()?, to_range)
+}
+
+/// Maps `time` within the span from `start` to `end` onto `to_range`, like [`map_system_time`].
+///
+/// Returns `None` if `time` lies outside of the span, the span is empty or reversed or the
+/// result does not fit into `U`.
+#[cfg(feature = "std")]
+#[must_use]
+pub fn map_instant<U: MapRange>(
+    time: std::time::Instant,
+    span: (std::time::Instant, std::time::Instant),
+    to_range: (U, U),
+) -> Option<U> {
+    let elapsed = time.checked_duration_since(span.0)?;
+    map_duration(elapsed, span.1.checked_duration_since(span.0)?, to_range)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_map_duration() {
+        let span = Duration::from_millis(1500);
+        assert_eq!(Some(1.), map_duration(span, span, (0., 1.)));
+        assert_eq!(Some(0.), map_duration(Duration::ZERO, span, (0., 1.)));
+        assert_eq!(None, map_duration(Duration::from_secs(2), span, (0., 1.)));
+        assert_eq!(None, map_duration(Duration::ZERO, Duration::ZERO, (0., 1.)));
+        let nanos = map_duration(Duration::from_nanos(1), Duration::from_nanos(4), (0., 1.));
+        assert_eq!(Some(0.25), nanos);
+    }
+    #[test]
+    #[cfg(feature = "std")]
+    fn test_map_std_times() {
+        use std::time::{Instant, SystemTime};
+        let start = SystemTime::UNIX_EPOCH;
+        let end = start + Duration::from_secs(10);
+        let before = map_system_time(start, (end, start), (0_u8, 10));
+        assert_eq!(None, before);
+        assert_eq!(Some(10), map_system_time(end, (start, end), (0_u8, 10)));
+        let now = Instant::now();
+        let later = now + Duration::from_secs(4);
+        assert_eq!(
+            Some(50_u8),
+            map_instant(now + Duration::from_secs(2), (now, later), (0, 100))
+        );
+        assert_eq!(
+            None,
+            map_instant(later + Duration::from_secs(1), (now, later), (0_u8, 100))
+        );
+    }
+}
@@ -0,0 +1,115 @@
+//! Running a simulation at a fixed rate while rendering at any frame rate.
+
+use crate::lerp::Lerp;
+
+/// Accumulates frame times into a whole number of fixed simulation steps.
+///
+/// The leftover time is exposed as [`FixedTimestep::alpha`], for blending the previous and the
+/// current simulation state when rendering.
+///
+/// ```
+/// use map_to_range::timestep::FixedTimestep;
+///
+/// let mut timestep = FixedTimestep::new(0.25);
+/// assert_eq!(2, timestep.advance(0.625));
+/// assert_eq!(0.5, timestep.alpha());
+/// assert_eq!(15., timestep.interpolate(10., 20.));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FixedTimestep {
+    step: f64,
+    accumulator: f64,
+    max_steps: u32,
+}
+
+impl FixedTimestep {
+    /// A timestep of `step`, running at most 8 steps per frame.
+    #[must_use]
+    pub const fn new(step: f64) -> Self {
+        Self {
+            step,
+            accumulator: 0.,
+            max_steps: 8,
+        }
+    }
+    /// Limits the steps per frame, so a slow simulation does not fall further behind each frame.
+    /// Time beyond the limit is dropped.
+    #[must_use]
+    pub const fn with_max_steps(mut self, max_steps: u32) -> Self {
+        self.max_steps = max_steps;
+        self
+    }
+    /// Adds the time of the last frame and returns how many simulation steps to run now.
+    ///
+    /// Negative and non-finite frame times are ignored, and no steps are run for a step that is
+    /// not positive.
+    pub fn advance(&mut self, frame_time: f64) -> u32 {
+        if frame_time.is_finite() && frame_time > 0. {
+            self.accumulator += frame_time;
+        }
+        if self.step.is_nan() || self.step <= 0. {
+            return 0;
+        }
+        let mut steps = 0;
+        while self.accumulator >= self.step && steps < self.max_steps {
+            self.accumulator -= self.step;
+            steps += 1;
+        }
+        if self.accumulator >= self.step {
+            self.accumulator %= self.step;
+        }
+        steps
+    }
+    /// How far the render time is between the previous and the current simulation step, in `0..1`.
+    #[must_use]
+    pub fn alpha(&self) -> f64 {
+        if self.step > 0. {
+            (self.accumulator / self.step).clamp(0., 1.)
+        } else {
+            0.
+        }
+    }
+    /// Blends the state of the previous and the current simulation step by [`FixedTimestep::alpha`].
+    #[must_use]
+    pub fn interpolate<T: Lerp>(&self, previous: T, current: T) -> T {
+        previous.lerp(current, self.alpha())
+    }
+    /// The fixed step length.
+    #[must_use]
+    pub const fn step(&self) -> f64 {
+        self.step
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_accumulates() {
+        let mut timestep = FixedTimestep::new(0.5);
+        assert_eq!(0, timestep.advance(0.25));
+        assert_eq!(Some(0.5), Some(timestep.alpha()));
+        assert_eq!(1, timestep.advance(0.5));
+        assert_eq!(Some(0.5), Some(timestep.alpha()));
+        assert_eq!(0, timestep.advance(-1.));
+        assert_eq!(0, timestep.advance(f64::NAN));
+        assert_eq!(1, timestep.advance(0.25));
+        assert_eq!(Some(0.), Some(timestep.alpha()));
+    }
+    #[test]
+    fn test_max_steps() {
+        let mut timestep = FixedTimestep::new(1.).with_max_steps(3);
+        assert_eq!(3, timestep.advance(10.5));
+        assert_eq!(Some(0.5), Some(timestep.alpha()));
+        assert_eq!(0, FixedTimestep::new(0.).advance(1.));
+    }
+    #[test]
+    fn test_ignores_infinite_frames() {
+        let mut timestep = FixedTimestep::new(0.5);
+        assert_eq!(0, timestep.advance(f64::INFINITY));
+        assert_eq!(Some(0.), Some(timestep.alpha()));
+        assert_eq!(1, timestep.advance(0.75));
+        assert_eq!(Some(0.5), Some(timestep.alpha()));
+    }
+}
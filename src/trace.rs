@@ -0,0 +1,23 @@
+//! The intermediate values of a mapping, to find out why it failed.
+
+use crate::MapRangeError;
+
+/// The steps of a mapping, as returned by
+/// [`MapRange::map_range_traced`](crate::MapRange::map_range_traced).
+///
+/// Every step is computed in `f64` as far as possible, even if the mapping fails, so a value just
+/// outside of its range shows up as a position slightly below 0 or above 1.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MapTrace<T> {
+    /// Where the value lies in the range it is mapped from, 0 at its start and 1 at its end.
+    ///
+    /// `None` if the range is empty or something is not finite.
+    pub position: Option<f64>,
+    /// The result before it is cast back into `T`.
+    ///
+    /// `None` if the range is empty or something is not finite.
+    pub unrounded: Option<f64>,
+    /// The result of the mapping, or the reason it failed, like
+    /// [`MapRange::try_map_range`](crate::MapRange::try_map_range).
+    pub result: Result<T, MapRangeError>,
+}
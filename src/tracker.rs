@@ -0,0 +1,345 @@
+//! Keeping track of the smallest and largest value seen, to map readings onto their observed range,
+//! and normalizing whole sequences that way.
+//!
+//! [`RunningStats`] tracks the mean and standard deviation the same way.
+
+use crate::{math, MapRange};
+
+/// The smallest and largest value passed to [`update`](Self::update) so far.
+///
+/// Values that are not comparable with themselves, like `NaN`, are ignored.
+///
+/// ```
+/// use map_to_range::tracker::MinMaxTracker;
+///
+/// let mut tracker = MinMaxTracker::new();
+/// for reading in [512_u16, 300, 890, 640] {
+///     tracker.update(reading);
+/// }
+/// assert_eq!(Some((300, 890)), tracker.range());
+/// assert_eq!(Some(50_u8), tracker.map(595, (0, 100)));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MinMaxTracker<T> {
+    range: Option<(T, T)>,
+}
+
+impl<T> Default for MinMaxTracker<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> MinMaxTracker<T> {
+    /// A tracker that has not seen any value yet.
+    #[must_use]
+    pub const fn new() -> Self {
+        Self { range: None }
+    }
+    /// Forgets all values seen so far.
+    pub fn reset(&mut self) {
+        self.range = None;
+    }
+}
+
+impl<T: PartialOrd + Copy> MinMaxTracker<T> {
+    /// Widens the tracked range to include `value`.
+    pub fn update(&mut self, value: T) {
+        if value.partial_cmp(&value).is_none() {
+            return;
+        }
+        self.range = Some(match self.range {
+            None => (value, value),
+            Some((min, max)) if value < min => (value, max),
+            Some((min, max)) if value > max => (min, value),
+            Some(range) => range,
+        });
+    }
+    /// The smallest and largest value seen, `None` before the first value.
+    #[must_use]
+    pub const fn range(&self) -> Option<(T, T)> {
+        self.range
+    }
+}
+
+impl<T: MapRange> MinMaxTracker<T> {
+    /// Maps `value` from the tracked range onto `to_range`.
+    ///
+    /// Returns `None` if no values were seen, the tracked range is empty or `value` lies outside of it.
+    #[must_use]
+    pub fn map<U: MapRange>(&self, value: T, to_range: (U, U)) -> Option<U> {
+        let (min, max) = self.range?;
+        let mapped = value.checked_f64_cast()?.map_range(
+            (min.checked_f64_cast()?, max.checked_f64_cast()?),
+            (
+                to_range.0.checked_f64_cast()?,
+                to_range.1.checked_f64_cast()?,
+            ),
+        )?;
+        U::checked_cast_back(mapped)
+    }
+}
+
+impl<T: PartialOrd + Copy> Extend<T> for MinMaxTracker<T> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, values: I) {
+        for value in values {
+            self.update(value);
+        }
+    }
+}
+
+impl<T: PartialOrd + Copy> FromIterator<T> for MinMaxTracker<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(values: I) -> Self {
+        let mut tracker = Self::new();
+        tracker.extend(values);
+        tracker
+    }
+}
+
+/// The mean and standard deviation of the values passed to [`update`](Self::update) so far,
+/// computed in a single pass with Welford's algorithm.
+///
+/// ```
+/// use map_to_range::tracker::RunningStats;
+///
+/// let stats: RunningStats = [2., 4., 4., 4., 5., 5., 7., 9.].into_iter().collect();
+/// assert_eq!(Some(5.), stats.mean());
+/// assert_eq!(Some(2.), stats.std_dev());
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct RunningStats {
+    count: u64,
+    mean: f64,
+    /// The sum of squared differences from the mean.
+    squares: f64,
+}
+
+impl RunningStats {
+    /// Statistics over no values yet.
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            count: 0,
+            mean: 0.,
+            squares: 0.,
+        }
+    }
+    /// Adds `value` to the statistics. Values that are not finite are ignored.
+    pub fn update(&mut self, value: f64) {
+        if !value.is_finite() {
+            return;
+        }
+        self.count += 1;
+        let delta = value - self.mean;
+        self.mean += delta / self.count as f64;
+        self.squares += delta * (value - self.mean);
+    }
+    /// The number of values seen.
+    #[must_use]
+    pub const fn count(&self) -> u64 {
+        self.count
+    }
+    /// The mean of the values, `None` before the first value.
+    #[must_use]
+    pub fn mean(&self) -> Option<f64> {
+        (self.count > 0).then_some(self.mean)
+    }
+    /// The population variance of the values, `None` before the first value.
+    #[must_use]
+    pub fn variance(&self) -> Option<f64> {
+        (self.count > 0).then(|| self.squares / self.count as f64)
+    }
+    /// The population standard deviation of the values, `None` before the first value.
+    #[must_use]
+    pub fn std_dev(&self) -> Option<f64> {
+        self.variance().map(math::sqrt)
+    }
+    /// Forgets all values seen so far.
+    pub fn reset(&mut self) {
+        *self = Self::new();
+    }
+}
+
+impl Extend<f64> for RunningStats {
+    fn extend<I: IntoIterator<Item = f64>>(&mut self, values: I) {
+        for value in values {
+            self.update(value);
+        }
+    }
+}
+
+impl FromIterator<f64> for RunningStats {
+    fn from_iter<I: IntoIterator<Item = f64>>(values: I) -> Self {
+        let mut stats = Self::new();
+        stats.extend(values);
+        stats
+    }
+}
+
+/// Normalizes a sequence onto `to_range`, by scanning a clone of `values` for its bounds first
+/// and then mapping every value from those bounds.
+///
+/// If all values are equal, or a value cannot be mapped like `NaN`, it comes out as `to_range.0`.
+///
+/// ```
+/// use map_to_range::tracker::normalize;
+///
+/// let capture = [-0.5_f32, 0., 1.5, 0.5];
+/// let pixels: Vec<u8> = normalize(capture.iter().copied(), (0, 255)).collect();
+/// assert_eq!(vec![0, 63, 255, 127], pixels);
+/// ```
+#[must_use]
+pub fn normalize<I, U>(values: I, to_range: (U, U)) -> Normalize<I, U>
+where
+    I: Iterator + Clone,
+    I::Item: MapRange,
+    U: MapRange,
+{
+    Normalize {
+        tracker: values.clone().collect(),
+        values,
+        to_range,
+    }
+}
+
+/// The iterator returned by [`normalize`].
+#[derive(Debug, Clone)]
+pub struct Normalize<I: Iterator, U> {
+    values: I,
+    tracker: MinMaxTracker<I::Item>,
+    to_range: (U, U),
+}
+
+impl<I, U> Iterator for Normalize<I, U>
+where
+    I: Iterator,
+    I::Item: MapRange,
+    U: MapRange,
+{
+    type Item = U;
+
+    fn next(&mut self) -> Option<U> {
+        let value = self.values.next()?;
+        Some(
+            self.tracker
+                .map(value, self.to_range)
+                .unwrap_or(self.to_range.0),
+        )
+    }
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.values.size_hint()
+    }
+}
+
+/// Normalizes a sequence onto `to_range` in a single pass, mapping every value from the bounds
+/// of the values seen up to and including it.
+///
+/// This is only an approximation of [`normalize`], as early values are mapped from a narrower
+/// range, but it works on iterators that can not be cloned or never end.
+/// Values that cannot be mapped, like the very first one, come out as `to_range.0`.
+///
+/// ```
+/// use map_to_range::tracker::normalize_running;
+///
+/// let percent: Vec<u8> = normalize_running([10_u16, 20, 15, 30].into_iter(), (0, 100)).collect();
+/// assert_eq!(vec![0, 100, 50, 100], percent);
+/// ```
+#[must_use]
+pub fn normalize_running<I, U>(values: I, to_range: (U, U)) -> NormalizeRunning<I, U>
+where
+    I: Iterator,
+    I::Item: MapRange,
+    U: MapRange,
+{
+    NormalizeRunning {
+        values,
+        tracker: MinMaxTracker::new(),
+        to_range,
+    }
+}
+
+/// The iterator returned by [`normalize_running`].
+#[derive(Debug, Clone)]
+pub struct NormalizeRunning<I: Iterator, U> {
+    values: I,
+    tracker: MinMaxTracker<I::Item>,
+    to_range: (U, U),
+}
+
+impl<I: Iterator, U> NormalizeRunning<I, U> {
+    /// The bounds seen so far.
+    #[must_use]
+    pub const fn tracker(&self) -> &MinMaxTracker<I::Item> {
+        &self.tracker
+    }
+}
+
+impl<I, U> Iterator for NormalizeRunning<I, U>
+where
+    I: Iterator,
+    I::Item: MapRange,
+    U: MapRange,
+{
+    type Item = U;
+
+    fn next(&mut self) -> Option<U> {
+        let value = self.values.next()?;
+        self.tracker.update(value);
+        Some(
+            self.tracker
+                .map(value, self.to_range)
+                .unwrap_or(self.to_range.0),
+        )
+    }
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.values.size_hint()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_min_max_tracker() {
+        let mut tracker: MinMaxTracker<f32> = [2., f32::NAN, -1.5, 4.].into_iter().collect();
+        assert_eq!(Some((-1.5, 4.)), tracker.range());
+        assert_eq!(Some(60_u8), tracker.map(-0.125, (0, 240)));
+        assert_eq!(None, tracker.map(5., (0_u8, 240)));
+        tracker.reset();
+        assert_eq!(None, tracker.range());
+        tracker.update(3.);
+        assert_eq!(Some((3., 3.)), tracker.range());
+        assert_eq!(None, tracker.map(3., (0_u8, 240)));
+    }
+    #[test]
+    fn test_running_stats() {
+        let mut stats = RunningStats::new();
+        assert_eq!(None, stats.mean());
+        assert_eq!(None, stats.std_dev());
+        stats.extend([1e9 + 4., 1e9 + 7., f64::NAN, 1e9 + 13., 1e9 + 16.]);
+        assert_eq!(4, stats.count());
+        assert_eq!(Some(1e9 + 10.), stats.mean());
+        assert_eq!(Some(22.5), stats.variance());
+        stats.reset();
+        assert_eq!(RunningStats::default(), stats);
+    }
+    #[test]
+    fn test_normalize() {
+        let mut flat = normalize([7_u8, 7, 7].into_iter(), (10_u8, 20));
+        assert_eq!(Some(10), flat.next());
+        assert_eq!((2, Some(2)), flat.size_hint());
+        let values = [1., f64::NAN, 3.];
+        let mut mapped = normalize(values.into_iter(), (0., 1.));
+        assert_eq!(Some(0.), mapped.next());
+        assert_eq!(Some(0.), mapped.next());
+        assert_eq!(Some(1.), mapped.next());
+        assert_eq!(None, mapped.next());
+
+        let mut running = normalize_running([4_i32, -4, 0].into_iter(), (-1., 1.));
+        assert_eq!(Some(-1.), running.next());
+        assert_eq!(Some(-1.), running.next());
+        assert_eq!(Some(0.), running.next());
+        assert_eq!(Some((-4, 4)), running.tracker().range());
+    }
+}
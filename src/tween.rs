@@ -0,0 +1,160 @@
+//! Animating a value from one state to another over time.
+
+use crate::{clock::Clock, clock::Ticker, easing::Easing, lerp::Lerp};
+
+/// An eased transition from `from` to `to` over `duration` seconds.
+///
+/// The tween can be driven by the caller's elapsed time with [`Tween::advance`],
+/// or by a platform clock with [`Tween::update`].
+///
+/// ```
+/// use map_to_range::{easing::Easing, tween::Tween};
+///
+/// let mut fade = Tween::new(0_f32, 100., 2.).with_easing(Easing::QuadIn);
+/// assert_eq!(25., fade.advance(1.));
+/// assert_eq!(100., fade.advance(5.));
+/// assert!(fade.is_finished());
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Tween<T> {
+    from: T,
+    to: T,
+    duration: f64,
+    easing: Easing,
+    elapsed: f64,
+}
+
+impl<T: Lerp> Tween<T> {
+    /// A linear tween, that has not started yet.
+    #[must_use]
+    pub fn new(from: T, to: T, duration: f64) -> Self {
+        Self {
+            from,
+            to,
+            duration,
+            easing: Easing::Linear,
+            elapsed: 0.,
+        }
+    }
+    /// Shapes the progress of the tween with `easing`.
+    #[must_use]
+    pub fn with_easing(mut self, easing: Easing) -> Self {
+        self.easing = easing;
+        self
+    }
+    /// Moves the tween forward by `dt` seconds and returns the new value.
+    ///
+    /// Negative and `NaN` times are ignored.
+    pub fn advance(&mut self, dt: f64) -> T {
+        if dt > 0. {
+            self.elapsed += dt;
+        }
+        self.value()
+    }
+    /// Moves the tween forward by the time since the last tick of `ticker`.
+    pub fn update<C: Clock>(&mut self, ticker: &mut Ticker<C>) -> T {
+        self.advance(ticker.tick())
+    }
+    /// The value `elapsed` seconds after the start, without changing the tween.
+    #[must_use]
+    pub fn sample(&self, elapsed: f64) -> T {
+        self.from
+            .lerp(self.to, self.easing.apply(self.progress_at(elapsed)))
+    }
+    /// The current value.
+    #[must_use]
+    pub fn value(&self) -> T {
+        self.sample(self.elapsed)
+    }
+    /// The progress of the tween in `0..=1`, before easing.
+    #[must_use]
+    pub fn progress(&self) -> f64 {
+        self.progress_at(self.elapsed)
+    }
+    /// Returns `true` once the whole duration has passed.
+    #[must_use]
+    pub fn is_finished(&self) -> bool {
+        self.progress() >= 1.
+    }
+    /// Starts the tween over.
+    pub fn reset(&mut self) {
+        self.elapsed = 0.;
+    }
+    /// The length of the tween in seconds.
+    #[must_use]
+    pub fn duration(&self) -> f64 {
+        self.duration
+    }
+    /// The value at `at`, for a tween that started at `started`, without changing the tween.
+    #[cfg(feature = "std")]
+    #[must_use]
+    pub fn sample_at(&self, started: std::time::Instant, at: std::time::Instant) -> T {
+        self.sample(at.saturating_duration_since(started).as_secs_f64())
+    }
+    /// Plays the rest of the tween in real time, calling `frame` with a new value every
+    /// `frame_time`, and with the final value at the end. This blocks the current thread.
+    ///
+    /// ```
+    /// use std::time::Duration;
+    /// use map_to_range::tween::Tween;
+    ///
+    /// let mut values = Vec::new();
+    /// Tween::new(0_f64, 1., 0.02).play_with(Duration::from_millis(5), |value| values.push(value));
+    /// assert_eq!(Some(&1.), values.last());
+    /// ```
+    #[cfg(feature = "std")]
+    pub fn play_with(&mut self, frame_time: std::time::Duration, mut frame: impl FnMut(T)) {
+        let mut last = std::time::Instant::now();
+        while !self.is_finished() {
+            std::thread::sleep(frame_time);
+            let now = std::time::Instant::now();
+            frame(self.advance(now.duration_since(last).as_secs_f64()));
+            last = now;
+        }
+    }
+    fn progress_at(&self, elapsed: f64) -> f64 {
+        // Tweens without a duration jump straight to the end.
+        if self.duration.is_nan() || self.duration <= 0. {
+            return 1.;
+        }
+        (elapsed / self.duration).clamp(0., 1.)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_advance() {
+        let mut tween = Tween::new([0_f64, 10.], [10., 0.], 4.);
+        assert_eq!(Some([0., 10.]), Some(tween.value()));
+        assert_eq!(Some([2.5, 7.5]), Some(tween.advance(1.)));
+        assert_eq!(Some([2.5, 7.5]), Some(tween.advance(-1.)));
+        assert_eq!(Some(0.25), Some(tween.progress()));
+        assert_eq!(Some([10., 0.]), Some(tween.advance(10.)));
+        assert!(tween.is_finished());
+        tween.reset();
+        assert_eq!(Some([0., 10.]), Some(tween.value()));
+        assert_eq!(Some([5., 5.]), Some(tween.sample(2.)));
+    }
+    #[test]
+    fn test_without_duration() {
+        let tween = Tween::new(1_f32, 2., 0.);
+        assert_eq!(Some(2.), Some(tween.value()));
+        assert!(tween.is_finished());
+    }
+    #[test]
+    #[cfg(feature = "std")]
+    fn test_sample_at() {
+        use std::time::{Duration, Instant};
+        let tween = Tween::new(0_f64, 10., 2.);
+        let started = Instant::now();
+        let value = tween.sample_at(started, started + Duration::from_millis(500));
+        assert_eq!(Some(2.5), Some(value));
+        assert_eq!(
+            Some(0.),
+            Some(tween.sample_at(started + Duration::from_secs(1), started))
+        );
+    }
+}
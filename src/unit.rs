@@ -0,0 +1,162 @@
+//! Zero cost unit tags, so values and ranges of different units cannot be mixed up.
+//!
+//! Units are plain marker types. A value can only be mapped with a from range of its own unit,
+//! and comes out tagged with the unit of the target range:
+//!
+//! ```
+//! use map_to_range::unit::{Tagged, TaggedRange};
+//!
+//! enum Raw {}
+//! enum Celsius {}
+//!
+//! let sensor = TaggedRange::<f64, Raw>::new(0., 4095.);
+//! let temperature = TaggedRange::<f64, Celsius>::new(-40., 125.);
+//!
+//! let celsius: Tagged<f64, Celsius> = Tagged::new(1241.).map_range(sensor, temperature).unwrap();
+//! assert!((celsius.value() - 10.).abs() < 0.01);
+//! ```
+//!
+//! Mapping a value with a range of another unit does not compile:
+//!
+//! ```compile_fail
+//! use map_to_range::unit::{Tagged, TaggedRange};
+//!
+//! enum Raw {}
+//! enum Celsius {}
+//!
+//! let temperature = TaggedRange::<f64, Celsius>::new(-40., 125.);
+//! let raw = Tagged::<f64, Raw>::new(1241.);
+//! raw.map_range(temperature, temperature);
+//! ```
+
+use core::fmt;
+use core::marker::PhantomData;
+
+use crate::MapRange;
+
+/// A value of type `T` in the unit `Unit`.
+pub struct Tagged<T, Unit> {
+    value: T,
+    // A function pointer keeps the tag from affecting auto traits like `Send`.
+    unit: PhantomData<fn() -> Unit>,
+}
+
+impl<T, Unit> Tagged<T, Unit> {
+    /// Tags `value` with `Unit`.
+    #[must_use]
+    pub const fn new(value: T) -> Self {
+        Self {
+            value,
+            unit: PhantomData,
+        }
+    }
+    /// The untagged value.
+    #[must_use]
+    pub fn value(self) -> T {
+        self.value
+    }
+}
+
+impl<T: MapRange, Unit> Tagged<T, Unit> {
+    /// Maps the value from `from_range` to `to_range`, like [`MapRange::map_range`].
+    ///
+    /// `from_range` has to be in the unit of the value, and the result is in the unit of `to_range`.
+    #[must_use]
+    pub fn map_range<To>(
+        self,
+        from_range: TaggedRange<T, Unit>,
+        to_range: TaggedRange<T, To>,
+    ) -> Option<Tagged<T, To>> {
+        self.value
+            .map_range(from_range.bounds, to_range.bounds)
+            .map(Tagged::new)
+    }
+}
+
+impl<T: Clone, Unit> Clone for Tagged<T, Unit> {
+    fn clone(&self) -> Self {
+        Self::new(self.value.clone())
+    }
+}
+
+impl<T: Copy, Unit> Copy for Tagged<T, Unit> {}
+
+impl<T: PartialEq, Unit> PartialEq for Tagged<T, Unit> {
+    fn eq(&self, other: &Self) -> bool {
+        self.value == other.value
+    }
+}
+
+impl<T: PartialOrd, Unit> PartialOrd for Tagged<T, Unit> {
+    fn partial_cmp(&self, other: &Self) -> Option<core::cmp::Ordering> {
+        self.value.partial_cmp(&other.value)
+    }
+}
+
+impl<T: fmt::Debug, Unit> fmt::Debug for Tagged<T, Unit> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("Tagged").field(&self.value).finish()
+    }
+}
+
+/// A range of values of type `T` in the unit `Unit`.
+pub struct TaggedRange<T, Unit> {
+    bounds: (T, T),
+    unit: PhantomData<fn() -> Unit>,
+}
+
+impl<T, Unit> TaggedRange<T, Unit> {
+    /// The range from `start` to `end` in `Unit`.
+    #[must_use]
+    pub const fn new(start: T, end: T) -> Self {
+        Self {
+            bounds: (start, end),
+            unit: PhantomData,
+        }
+    }
+    /// The untagged `(start, end)` of the range.
+    #[must_use]
+    pub fn bounds(self) -> (T, T) {
+        self.bounds
+    }
+}
+
+impl<T: Clone, Unit> Clone for TaggedRange<T, Unit> {
+    fn clone(&self) -> Self {
+        Self::new(self.bounds.0.clone(), self.bounds.1.clone())
+    }
+}
+
+impl<T: Copy, Unit> Copy for TaggedRange<T, Unit> {}
+
+impl<T: PartialEq, Unit> PartialEq for TaggedRange<T, Unit> {
+    fn eq(&self, other: &Self) -> bool {
+        self.bounds == other.bounds
+    }
+}
+
+impl<T: fmt::Debug, Unit> fmt::Debug for TaggedRange<T, Unit> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("TaggedRange").field(&self.bounds).finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    enum Percent {}
+    enum Duty {}
+
+    #[test]
+    fn test_tagged() {
+        let percent = TaggedRange::<u16, Percent>::new(0, 100);
+        let duty = TaggedRange::<u16, Duty>::new(0, 1023);
+        let mapped = Tagged::<u16, Percent>::new(50).map_range(percent, duty);
+        assert_eq!(Some(Tagged::new(511)), mapped);
+        assert_eq!(None, Tagged::new(101).map_range(percent, duty));
+        assert_eq!((0, 1023), duty.bounds());
+        assert!(Tagged::<u8, Duty>::new(1) < Tagged::new(2));
+        assert_eq!(0, core::mem::size_of::<TaggedRange<(), Duty>>());
+    }
+}
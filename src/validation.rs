@@ -0,0 +1,13 @@
+//! How strictly the ranges of a mapping are checked.
+
+/// The contract a mapping applies to its ranges, see
+/// [`MapRange::map_range_validated`](crate::MapRange::map_range_validated).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum Validation {
+    /// Rejects empty `from` ranges and reversed ranges on either side.
+    #[default]
+    Strict,
+    /// Accepts reversed ranges on either side, and maps the single value of an empty `from` range
+    /// to the start of the `to` range.
+    Lenient,
+}
@@ -0,0 +1,132 @@
+//! The camera of an interactive plot or map, mapping between world and screen coordinates.
+
+use crate::rect::Rect;
+
+/// A zoomable and pannable view onto a world.
+///
+/// A world point is drawn at `(world - pan) * zoom` on the screen, so `pan` is the world point
+/// at the screen origin and `zoom` the number of screen units per world unit.
+///
+/// ```
+/// use map_to_range::viewport::Viewport;
+///
+/// let mut view = Viewport::new();
+/// view.pan_by([100., 0.]);
+/// assert_eq!([150., 20.], view.world_to_screen([50., 20.]));
+///
+/// // Zooming in about the cursor keeps the world point under it in place.
+/// view.zoom_about([150., 20.], 4.);
+/// assert_eq!([150., 20.], view.world_to_screen([50., 20.]));
+/// assert_eq!([51., 20.], view.screen_to_world([154., 20.]));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Viewport {
+    zoom: f64,
+    pan: [f64; 2],
+    zoom_limits: (f64, f64),
+}
+
+impl Default for Viewport {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Viewport {
+    /// A view showing the world at its original scale, with the world origin at the screen origin.
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            zoom: 1.,
+            pan: [0., 0.],
+            zoom_limits: (f64::MIN_POSITIVE, f64::MAX),
+        }
+    }
+    /// Limits the zoom to `min..=max` in all later updates.
+    ///
+    /// Returns `None` if the limits are not positive and finite or `min` is above `max`.
+    #[must_use]
+    pub fn with_zoom_limits(mut self, min: f64, max: f64) -> Option<Self> {
+        if !(min > 0. && min <= max && max.is_finite()) {
+            return None;
+        }
+        self.zoom_limits = (min, max);
+        self.zoom = self.zoom.clamp(min, max);
+        Some(self)
+    }
+    /// The screen units per world unit.
+    #[must_use]
+    pub const fn zoom(&self) -> f64 {
+        self.zoom
+    }
+    /// The world point at the screen origin.
+    #[must_use]
+    pub const fn pan(&self) -> [f64; 2] {
+        self.pan
+    }
+    /// Where `world` is drawn on the screen.
+    #[must_use]
+    pub fn world_to_screen(&self, world: [f64; 2]) -> [f64; 2] {
+        let [x, y] = world;
+        let [pan_x, pan_y] = self.pan;
+        [(x - pan_x) * self.zoom, (y - pan_y) * self.zoom]
+    }
+    /// The world point drawn at `screen`.
+    #[must_use]
+    pub fn screen_to_world(&self, screen: [f64; 2]) -> [f64; 2] {
+        let [x, y] = screen;
+        let [pan_x, pan_y] = self.pan;
+        [x / self.zoom + pan_x, y / self.zoom + pan_y]
+    }
+    /// The part of the world visible on `screen`.
+    #[must_use]
+    pub fn visible_world(&self, screen: Rect) -> Rect {
+        let [left, top] = self.screen_to_world([screen.x.0, screen.y.0]);
+        let [right, bottom] = self.screen_to_world([screen.x.1, screen.y.1]);
+        Rect::new((left, right), (top, bottom))
+    }
+    /// Moves the view so that the world follows a drag by `screen_delta`.
+    pub fn pan_by(&mut self, screen_delta: [f64; 2]) {
+        for (pan, delta) in self.pan.iter_mut().zip(screen_delta) {
+            *pan -= delta / self.zoom;
+        }
+    }
+    /// Multiplies the zoom by `factor`, keeping the world point at `screen` in place.
+    ///
+    /// The zoom stops at its limits. Returns `None` and leaves the view untouched if `factor`
+    /// is not positive and finite.
+    pub fn zoom_about(&mut self, screen: [f64; 2], factor: f64) -> Option<()> {
+        if !(factor > 0. && factor.is_finite()) {
+            return None;
+        }
+        let anchor = self.screen_to_world(screen);
+        self.zoom = (self.zoom * factor).clamp(self.zoom_limits.0, self.zoom_limits.1);
+        for ((pan, anchor), screen) in self.pan.iter_mut().zip(anchor).zip(screen) {
+            *pan = anchor - screen / self.zoom;
+        }
+        Some(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_viewport() {
+        let mut view = Viewport::new()
+            .with_zoom_limits(0.5, 2.)
+            .unwrap_or_default();
+        assert_eq!(None, view.zoom_about([0., 0.], 0.));
+        assert_eq!(Some(()), view.zoom_about([100., 100.], 8.));
+        assert_eq!(Some(2.), Some(view.zoom()));
+        assert_eq!(Some([50., 50.]), Some(view.pan()));
+        view.pan_by([20., -40.]);
+        assert_eq!(Some([40., 70.]), Some(view.pan()));
+        let world = view.visible_world(Rect::new((0., 200.), (0., 100.)));
+        assert_eq!(Rect::new((40., 140.), (70., 120.)), world);
+        assert_eq!(Some([0., 0.]), Some(view.world_to_screen([40., 70.])));
+        assert_eq!(None, Viewport::new().with_zoom_limits(2., 1.));
+        assert_eq!(None, Viewport::new().with_zoom_limits(0., 1.));
+    }
+}
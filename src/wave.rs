@@ -0,0 +1,118 @@
+//! Integer sine and cosine approximations, for wave based effects without an FPU.
+//!
+//! A full period spans the whole range of the phase type, so a phase accumulator can simply
+//! wrap around on overflow.
+
+/// A quarter period of `sin` scaled to `i16::MAX`, in 64 steps.
+const QUARTER_SINE: [i16; 65] = [
+    0, 804, 1608, 2410, 3212, 4011, 4808, 5602, 6393, 7179, 7962, 8739, 9512, 10278, 11039, 11793,
+    12539, 13279, 14010, 14732, 15446, 16151, 16846, 17530, 18204, 18868, 19519, 20159, 20787,
+    21403, 22005, 22594, 23170, 23731, 24279, 24811, 25329, 25832, 26319, 26790, 27245, 27683,
+    28105, 28510, 28898, 29268, 29621, 29956, 30273, 30571, 30852, 31113, 31356, 31580, 31785,
+    31971, 32137, 32285, 32412, 32521, 32609, 32678, 32728, 32757, 32767,
+];
+
+/// The sine of `phase`, where `0..=65535` covers one period, scaled to `-32767..=32767`.
+///
+/// The result is at most 4 steps away from the exact sine.
+///
+/// ```
+/// use map_to_range::wave::sin16;
+///
+/// assert_eq!(0, sin16(0));
+/// assert_eq!(32767, sin16(16384));
+/// assert_eq!(-32767, sin16(49152));
+/// ```
+#[must_use]
+pub fn sin16(phase: u16) -> i16 {
+    const QUARTER: u16 = 1 << 14;
+    let within = phase % QUARTER;
+    let (position, negative) = match phase / QUARTER {
+        0 => (within, false),
+        1 => (QUARTER - within, false),
+        2 => (within, true),
+        _ => (QUARTER - within, true),
+    };
+    let index = (position >> 8) as usize;
+    let fraction = (position & 0xff) as i32;
+    let low = QUARTER_SINE.get(index).copied().unwrap_or(i16::MAX) as i32;
+    let high = QUARTER_SINE.get(index + 1).copied().unwrap_or(i16::MAX) as i32;
+    let value = (low + (((high - low) * fraction + 128) >> 8)) as i16;
+    if negative {
+        -value
+    } else {
+        value
+    }
+}
+
+/// The cosine of `phase`, where `0..=65535` covers one period, scaled to `-32767..=32767`.
+///
+/// ```
+/// assert_eq!(32767, map_to_range::wave::cos16(0));
+/// ```
+#[must_use]
+pub fn cos16(phase: u16) -> i16 {
+    sin16(phase.wrapping_add(1 << 14))
+}
+
+/// The sine of `phase`, where `0..=255` covers one period, scaled to `0..=255` around 128.
+///
+/// ```
+/// use map_to_range::wave::sin8;
+///
+/// assert_eq!(128, sin8(0));
+/// assert_eq!(255, sin8(64));
+/// assert_eq!(0, sin8(192));
+/// ```
+#[must_use]
+pub fn sin8(phase: u8) -> u8 {
+    ((sin16((phase as u16) << 8) as i32 + 32768) >> 8) as u8
+}
+
+/// The cosine of `phase`, where `0..=255` covers one period, scaled to `0..=255` around 128.
+///
+/// ```
+/// assert_eq!(255, map_to_range::wave::cos8(0));
+/// ```
+#[must_use]
+pub fn cos8(phase: u8) -> u8 {
+    sin8(phase.wrapping_add(64))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Reference sine through the taylor series, good enough for the test tolerance.
+    fn reference(phase: u16) -> f64 {
+        let mut x = phase as f64 / 65536. * 2. * core::f64::consts::PI;
+        if x > core::f64::consts::PI {
+            x -= 2. * core::f64::consts::PI;
+        }
+        let mut term = x;
+        let mut sum = 0.;
+        for n in 0..20 {
+            sum += term;
+            term *= -x * x / ((2 * n + 2) * (2 * n + 3)) as f64;
+        }
+        sum * 32767.
+    }
+
+    #[test]
+    fn test_sin16_accuracy() {
+        for phase in 0..=u16::MAX {
+            let error = (sin16(phase) as f64 - reference(phase)).abs();
+            assert!(error <= 4.);
+        }
+    }
+    #[test]
+    fn test_8_bit_variants() {
+        for phase in 0..=u8::MAX {
+            let expected = (reference((phase as u16) << 8) + 32768.) / 256.;
+            assert!((sin8(phase) as f64 - expected).abs() <= 1.);
+        }
+        assert_eq!(0, cos16(16384));
+        assert_eq!(-32767, cos16(32768));
+        assert_eq!(0, cos8(128));
+    }
+}